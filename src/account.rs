@@ -1,13 +1,22 @@
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+#[cfg(feature = "std")]
 use crate::{Client, Error};
+#[cfg(feature = "std")]
+use base64::Engine;
+#[cfg(feature = "std")]
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use serde_json::json;
 
 /// An asynchronous client to work with the deSEC account API.
+#[cfg(feature = "std")]
 pub struct AccountClient<'a> {
     pub(crate) client: &'a crate::Client,
 }
 
+#[cfg(feature = "std")]
 impl<'a> Client {
     /// Returns a wrapping client for the account API.
     pub fn account(&'a self) -> AccountClient<'a> {
@@ -27,10 +36,39 @@ pub struct AccountInformation {
     pub outreach_preference: bool,
 }
 
+#[cfg(feature = "time")]
+impl AccountInformation {
+    /// Returns the creation time of the account.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if `created` is not a valid timestamp.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn created_at(&self) -> Result<time::OffsetDateTime, Error> {
+        crate::timestamp::parse_timestamp(&self.created)
+    }
+
+    /// Returns the age of the account at the given point in time.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if `created` is not a valid timestamp.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn age(&self, now: time::OffsetDateTime) -> Result<time::Duration, Error> {
+        Ok(now - self.created_at()?)
+    }
+}
+
 /// Representation of a deSEC [`login`][reference].
 ///
 /// [reference]: https://desec.readthedocs.io/en/latest/auth/account.html#log-in
-#[derive(Serialize, Deserialize, Debug)]
+///
+/// The `Debug` output redacts the token. With the `zeroize` feature, the token is overwritten
+/// with zeros on drop. `Login` implements `Drop` regardless of the features, use
+/// [`core::mem::take`] to move the token out.
+#[derive(Serialize, Deserialize)]
 pub struct Login {
     pub allowed_subnets: Vec<String>,
     pub created: String,
@@ -43,6 +81,56 @@ pub struct Login {
     pub token: String,
 }
 
+impl core::fmt::Debug for Login {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Login")
+            .field("allowed_subnets", &self.allowed_subnets)
+            .field("created", &self.created)
+            .field("is_valid", &self.is_valid)
+            .field("last_used", &self.last_used)
+            .field("max_age", &self.max_age)
+            .field("max_unused_period", &self.max_unused_period)
+            .field("name", &self.name)
+            .field("perm_manage_tokens", &self.perm_manage_tokens)
+            .field("token", &"<redacted>")
+            .finish()
+    }
+}
+
+impl Drop for Login {
+    fn drop(&mut self) {
+        crate::scrub(&mut self.token);
+    }
+}
+
+#[cfg(feature = "time")]
+impl Login {
+    /// Returns the time the login token has been created.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if `created` is not a valid timestamp.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn created_at(&self) -> Result<time::OffsetDateTime, Error> {
+        crate::timestamp::parse_timestamp(&self.created)
+    }
+
+    /// Returns the time the login token has last been used, if any.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if `last_used` is not a valid timestamp.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn last_used_at(&self) -> Result<Option<time::OffsetDateTime>, Error> {
+        self.last_used
+            .as_deref()
+            .map(crate::timestamp::parse_timestamp)
+            .transpose()
+    }
+}
+
 /// Representation of a deSEC [`register`][reference] response.
 ///
 /// [reference]: https://desec.readthedocs.io/en/latest/auth/account.html#register-account
@@ -57,19 +145,94 @@ pub struct RegisterResponse {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Captcha {
     pub id: String,
+    /// Base64 encoded challenge, a PNG image for [`CaptchaKind::Image`] and a WAV recording for [`CaptchaKind::Audio`]
     pub challenge: String,
     pub kind: CaptchaKind,
 }
 
-/// Kind of challenge. Currently only image implemented.
+/// Maximum length of a captcha solution accepted by [`Captcha::validate_solution`].
+#[cfg(feature = "std")]
+const MAX_CAPTCHA_SOLUTION_LENGTH: usize = 32;
+
+#[cfg(feature = "std")]
+impl Captcha {
+    /// Decodes the base64 encoded challenge into the bytes of the image or audio recording,
+    /// see [`content_type`][content_type] for its format.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if the challenge is not valid base64.
+    ///
+    /// [content_type]: #method.content_type
+    /// [error]: ../enum.Error.html
+    pub fn decode(&self) -> Result<Vec<u8>, Error> {
+        base64::engine::general_purpose::STANDARD
+            .decode(self.challenge.trim())
+            .map_err(|error| {
+                Error::InvalidAPIResponse(
+                    format!("captcha challenge is not valid base64: {error}"),
+                    self.challenge.clone(),
+                )
+            })
+    }
+
+    /// Returns the MIME type of the decoded challenge, `image/png` or `audio/wav`.
+    pub fn content_type(&self) -> &'static str {
+        match self.kind {
+            CaptchaKind::Image => "image/png",
+            CaptchaKind::Audio => "audio/wav",
+        }
+    }
+
+    /// Checks whether `solution` is formally a valid solution for this kind of captcha.
+    ///
+    /// This is a format-only validation to catch obvious mistakes before registering,
+    /// e.g. an empty solution or characters which cannot occur in a captcha.
+    /// Whether the solution is actually correct can only be verified by the API.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if the solution is malformed.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn validate_solution(&self, solution: &str) -> Result<(), Error> {
+        let solution = solution.trim();
+        if solution.is_empty() {
+            return Err(Error::InvalidInput(
+                "captcha solution must not be empty".to_string(),
+            ));
+        }
+        if solution.len() > MAX_CAPTCHA_SOLUTION_LENGTH {
+            return Err(Error::InvalidInput(format!(
+                "captcha solution must not be longer than {MAX_CAPTCHA_SOLUTION_LENGTH} characters"
+            )));
+        }
+        if !solution.chars().all(|c| c.is_ascii_alphanumeric()) {
+            let kind = match self.kind {
+                CaptchaKind::Image => "an image",
+                CaptchaKind::Audio => "an audio",
+            };
+            return Err(Error::InvalidInput(format!(
+                "solution of {kind} captcha must only contain letters and digits"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Kind of [`challenge`][reference].
 ///
 /// [reference]: https://desec.readthedocs.io/en/latest/auth/account.html#obtain-a-captcha
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum CaptchaKind {
+    /// Image challenge, delivered as a PNG
     Image,
+    /// Audio challenge for accessibility, delivered as a WAV recording
+    Audio,
 }
 
+#[cfg(feature = "std")]
 impl<'a> AccountClient<'a> {
     /// Retrieves the account information.
     ///
@@ -82,9 +245,8 @@ impl<'a> AccountClient<'a> {
         let response = self.client.get("/auth/account/").await?;
         match response.status() {
             StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+                let response_text = crate::response_text(response).await?;
+                crate::parse_single(response_text)
             }
             _ => Err(Error::UnexpectedStatusCode(
                 response.status().into(),
@@ -113,7 +275,7 @@ impl<'a> AccountClient<'a> {
             .await?;
         match response.status() {
             StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
+                let response_text = crate::response_text(response).await?;
                 serde_json::from_str(&response_text)
                     .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
             }
@@ -126,11 +288,16 @@ impl<'a> AccountClient<'a> {
 
     /// Initiates a password reset using your email address and a captcha solution.
     ///
+    /// This is also the way to change the password of an account, as deSEC offers no endpoint
+    /// taking the current password. The API sends a code via email, which has to be passed to
+    /// [`confirm_password_reset`][confirm] together with the new password.
+    ///
     /// # Errors
     ///
     /// see [General errors][general_errors]
     ///
     /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [confirm]: #method.confirm_password_reset
     pub async fn request_password_reset(
         &self,
         email: &str,
@@ -155,7 +322,7 @@ impl<'a> AccountClient<'a> {
             .await?;
         match response.status() {
             StatusCode::ACCEPTED => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
+                let response_text = crate::response_text(response).await?;
                 serde_json::from_str(&response_text)
                     .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
             }
@@ -187,7 +354,7 @@ impl<'a> AccountClient<'a> {
             .await?;
         match response.status() {
             StatusCode::ACCEPTED => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
+                let response_text = crate::response_text(response).await?;
                 serde_json::from_str(&response_text)
                     .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
             }
@@ -227,7 +394,7 @@ impl<'a> AccountClient<'a> {
             .await?;
         match response.status() {
             StatusCode::ACCEPTED => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
+                let response_text = crate::response_text(response).await?;
                 serde_json::from_str(&response_text)
                     .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
             }
@@ -261,7 +428,7 @@ impl<'a> AccountClient<'a> {
             .await?;
         match response.status() {
             StatusCode::ACCEPTED => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
+                let response_text = crate::response_text(response).await?;
                 serde_json::from_str(&response_text)
                     .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
             }
@@ -273,37 +440,191 @@ impl<'a> AccountClient<'a> {
     }
 }
 
-/// Retrieves a base64 encoded captcha neccessary to register a new Account
+#[cfg(feature = "std")]
+impl Client {
+    /// Retrieves a base64 encoded image captcha neccessary to register a new Account.
+    ///
+    /// No authentication is required, see [`Client::unauthenticated`][unauthenticated].
+    /// Use [`get_captcha_of_kind`][get_captcha_of_kind] to retrieve an audio captcha.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [unauthenticated]: #method.unauthenticated
+    /// [get_captcha_of_kind]: #method.get_captcha_of_kind
+    /// [general_errors]: index.html#general-errors-for-all-clients
+    pub async fn get_captcha(&self) -> Result<Captcha, Error> {
+        self.get_captcha_of_kind(CaptchaKind::Image).await
+    }
+
+    /// Retrieves a base64 encoded captcha of the given kind neccessary to register a new Account.
+    ///
+    /// No authentication is required, see [`Client::unauthenticated`][unauthenticated].
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [unauthenticated]: #method.unauthenticated
+    /// [general_errors]: index.html#general-errors-for-all-clients
+    pub async fn get_captcha_of_kind(&self, kind: CaptchaKind) -> Result<Captcha, Error> {
+        let response = self
+            .post("/captcha/", Some(json!({ "kind": kind }).to_string()))
+            .await?;
+        match response.status() {
+            StatusCode::CREATED => {
+                let response_text = crate::response_text(response).await?;
+                serde_json::from_str(&response_text)
+                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+            }
+            _ => Err(Error::UnexpectedStatusCode(
+                response.status().into(),
+                response.text().await.unwrap_or_default(),
+            )),
+        }
+    }
+
+    /// Registers a new account using a captcha solution, a capture id and an optional first domain.
+    ///
+    /// No authentication is required, see [`Client::unauthenticated`][unauthenticated].
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [unauthenticated]: #method.unauthenticated
+    /// [general_errors]: index.html#general-errors-for-all-clients
+    pub async fn register(
+        &self,
+        email: &str,
+        password: &str,
+        captcha_id: &str,
+        captcha_solution: &str,
+        domain: Option<&str>,
+    ) -> Result<RegisterResponse, Error> {
+        let payload = if let Some(domain) = domain {
+            json!({
+                "email": email,
+                "password": password,
+                "captcha": {
+                    "id": captcha_id,
+                    "solution": captcha_solution
+                },
+                "domain": domain
+            })
+            .to_string()
+        } else {
+            json!({
+                "email": email,
+                "password": password,
+                "captcha": {
+                    "id": captcha_id,
+                    "solution": captcha_solution
+                }
+            })
+            .to_string()
+        };
+        let response = self.post("/auth/", Some(payload)).await?;
+        match response.status() {
+            StatusCode::ACCEPTED => {
+                let response_text = crate::response_text(response).await?;
+                serde_json::from_str(&response_text)
+                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+            }
+            _ => Err(Error::UnexpectedStatusCode(
+                response.status().into(),
+                response.text().await.unwrap_or_default(),
+            )),
+        }
+    }
+
+    /// Performs a login request using the given credentials and returns the login information.
+    ///
+    /// No authentication is required, see [`Client::unauthenticated`][unauthenticated].
+    /// To obtain a client authenticated with the token of the login,
+    /// use [`Client::new_from_credentials`][new_from_credentials].
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [unauthenticated]: #method.unauthenticated
+    /// [new_from_credentials]: #method.new_from_credentials
+    /// [general_errors]: index.html#general-errors-for-all-clients
+    pub async fn login(&self, email: &str, password: &str) -> Result<Login, Error> {
+        let response = self
+            .post(
+                "/auth/login/",
+                Some(
+                    json!({
+                        "email": email,
+                        "password": password,
+                    })
+                    .to_string(),
+                ),
+            )
+            .await?;
+        match response.status() {
+            StatusCode::OK => {
+                let response_text = crate::response_text(response).await?;
+                crate::parse_secret(response_text)
+            }
+            _ => Err(Error::UnexpectedStatusCode(
+                response.status().into(),
+                response.text().await.unwrap_or_default(),
+            )),
+        }
+    }
+}
+
+/// Retrieves a base64 encoded image captcha neccessary to register a new Account
+///
+/// Builds a new client on each call, use [`Client::get_captcha`][get_captcha] on a
+/// [reused client][unauthenticated] to share its connections.
 ///
 /// # Errors
 ///
 /// see [General errors][general_errors]
 ///
+/// [get_captcha]: ../struct.Client.html#method.get_captcha
+/// [unauthenticated]: ../struct.Client.html#method.unauthenticated
 /// [general_errors]: ../index.html#general-errors-for-all-clients
+#[cfg(feature = "std")]
 pub async fn get_captcha() -> Result<Captcha, Error> {
-    let client =
-        Client::new_unauth().map_err(|error| Error::ReqwestClientBuilder(error.to_string()))?;
-    let response = client.post("/captcha/", None).await?;
-    match response.status() {
-        StatusCode::CREATED => {
-            let response_text = response.text().await.map_err(Error::Reqwest)?;
-            serde_json::from_str(&response_text)
-                .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
-        }
-        _ => Err(Error::UnexpectedStatusCode(
-            response.status().into(),
-            response.text().await.unwrap_or_default(),
-        )),
-    }
+    Client::unauthenticated()?.get_captcha().await
+}
+
+/// Retrieves a base64 encoded captcha of the given kind neccessary to register a new Account
+///
+/// Builds a new client on each call, use [`Client::get_captcha_of_kind`][get_captcha_of_kind] on a
+/// [reused client][unauthenticated] to share its connections.
+///
+/// # Errors
+///
+/// see [General errors][general_errors]
+///
+/// [get_captcha_of_kind]: ../struct.Client.html#method.get_captcha_of_kind
+/// [unauthenticated]: ../struct.Client.html#method.unauthenticated
+/// [general_errors]: ../index.html#general-errors-for-all-clients
+#[cfg(feature = "std")]
+pub async fn get_captcha_of_kind(kind: CaptchaKind) -> Result<Captcha, Error> {
+    Client::unauthenticated()?.get_captcha_of_kind(kind).await
 }
 
 /// Registers a new account using a captcha solution, a capture id and an optional first domain.
 ///
+/// Builds a new client on each call, use [`Client::register`][register] on a
+/// [reused client][unauthenticated] to share its connections.
+///
 /// # Errors
 ///
 /// see [General errors][general_errors]
 ///
+/// [register]: ../struct.Client.html#method.register
+/// [unauthenticated]: ../struct.Client.html#method.unauthenticated
 /// [general_errors]: ../index.html#general-errors-for-all-clients
+#[cfg(feature = "std")]
 pub async fn register(
     email: &str,
     password: &str,
@@ -311,76 +632,116 @@ pub async fn register(
     captcha_solution: &str,
     domain: Option<&str>,
 ) -> Result<RegisterResponse, Error> {
-    let payload = if let Some(domain) = domain {
-        json!({
-            "email": email,
-            "password": password,
-            "captcha": {
-                "id": captcha_id,
-                "solution": captcha_solution
-            },
-            "domain": domain
-        })
-        .to_string()
-    } else {
-        json!({
-            "email": email,
-            "password": password,
-            "captcha": {
-                "id": captcha_id,
-                "solution": captcha_solution
-            }
-        })
-        .to_string()
-    };
-    let client =
-        Client::new_unauth().map_err(|error| Error::ReqwestClientBuilder(error.to_string()))?;
-    let response = client.post("/auth/", Some(payload)).await?;
-    match response.status() {
-        StatusCode::ACCEPTED => {
-            let response_text = response.text().await.map_err(Error::Reqwest)?;
-            serde_json::from_str(&response_text)
-                .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
-        }
-        _ => Err(Error::UnexpectedStatusCode(
-            response.status().into(),
-            response.text().await.unwrap_or_default(),
-        )),
-    }
+    Client::unauthenticated()?
+        .register(email, password, captcha_id, captcha_solution, domain)
+        .await
 }
 
 /// Performs a login request using the given credentials and returns the login information.
 ///
+/// Builds a new client on each call, use [`Client::login`][login] on a
+/// [reused client][unauthenticated] to share its connections.
+///
 /// # Errors
 ///
 /// see [General errors][general_errors]
 ///
+/// [login]: ../struct.Client.html#method.login
+/// [unauthenticated]: ../struct.Client.html#method.unauthenticated
 /// [general_errors]: ../index.html#general-errors-for-all-clients
+#[cfg(feature = "std")]
 pub async fn login(email: &str, password: &str) -> Result<Login, Error> {
-    let client =
-        Client::new_unauth().map_err(|error| Error::ReqwestClientBuilder(error.to_string()))?;
-    let response = client
-        .post(
-            "/auth/login/",
-            Some(
-                json!({
-                    "email": email,
-                    "password": password,
-                })
-                .to_string(),
-            ),
-        )
-        .await?;
-    match response.status() {
-        StatusCode::OK => {
-            // Build the final client using the token from the login
-            let response_text = response.text().await.map_err(Error::Reqwest)?;
-            Ok(serde_json::from_str(&response_text)
-                .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))?)
+    Client::unauthenticated()?.login(email, password).await
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_redacts_token() {
+        let login = Login {
+            allowed_subnets: vec!["0.0.0.0/0".to_string()],
+            created: "2024-01-01T00:00:00Z".to_string(),
+            is_valid: true,
+            last_used: None,
+            max_age: "7 00:00:00".to_string(),
+            max_unused_period: "01:00:00".to_string(),
+            name: "login".to_string(),
+            perm_manage_tokens: true,
+            token: "4pnk7u-NHvrEkFzrhFDRTjGFyX_S".to_string(),
+        };
+        let debug = format!("{login:?}");
+        assert!(debug.contains(r#"token: "<redacted>""#));
+        assert!(!debug.contains("4pnk7u"));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn account_age_is_computed_from_creation() {
+        let mut account = AccountInformation {
+            created: "2024-01-01T00:00:00.250000Z".to_string(),
+            email: "user@example.com".to_string(),
+            id: "9ab16e5c-805d-4ab1-9030-af3f5a541d47".to_string(),
+            limit_domains: 15,
+            outreach_preference: false,
+        };
+        let created = account.created_at().unwrap();
+        assert_eq!(created.unix_timestamp(), 1704067200);
+        let now = created + time::Duration::days(3);
+        assert_eq!(account.age(now).unwrap(), time::Duration::days(3));
+        account.created = "yesterday".to_string();
+        assert!(matches!(
+            account.age(now),
+            Err(Error::InvalidAPIResponse(_, _))
+        ));
+    }
+
+    fn captcha(kind: CaptchaKind, challenge: &str) -> Captcha {
+        Captcha {
+            id: "7e0a5b1c-9f5a-4b6f-8c3d-2e1f0a9b8c7d".to_string(),
+            challenge: challenge.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn malformed_captcha_solutions_are_rejected() {
+        let image = captcha(CaptchaKind::Image, "");
+        assert!(image.validate_solution("AB12CD").is_ok());
+        assert!(image.validate_solution(" ab12cd\n").is_ok());
+        for solution in [
+            "",
+            "   ",
+            &"A".repeat(MAX_CAPTCHA_SOLUTION_LENGTH + 1),
+            "AB 12",
+            "AB-12",
+        ] {
+            match image.validate_solution(solution) {
+                Err(Error::InvalidInput(_)) => (),
+                _ => panic!("Should yield desec_api::Error::InvalidInput for {solution:?}"),
+            }
+        }
+        match captcha(CaptchaKind::Audio, "").validate_solution("12.34") {
+            Err(Error::InvalidInput(message)) => assert!(message.contains("an audio captcha")),
+            _ => panic!("Should yield desec_api::Error::InvalidInput"),
+        }
+    }
+
+    #[test]
+    fn captcha_challenge_is_decoded() {
+        let image = captcha(CaptchaKind::Image, "iVBORw0KGgo=\n");
+        assert_eq!(image.decode().unwrap(), b"\x89PNG\r\n\x1a\n");
+        assert_eq!(image.content_type(), "image/png");
+        let audio = captcha(CaptchaKind::Audio, "UklGRg==");
+        assert_eq!(audio.decode().unwrap(), b"RIFF");
+        assert_eq!(audio.content_type(), "audio/wav");
+        match captcha(CaptchaKind::Image, "not base64!").decode() {
+            Err(Error::InvalidAPIResponse(message, challenge)) => {
+                assert!(message.contains("not valid base64"));
+                assert_eq!(challenge, "not base64!");
+            }
+            _ => panic!("Should yield desec_api::Error::InvalidAPIResponse"),
         }
-        _ => Err(Error::UnexpectedStatusCode(
-            response.status().into(),
-            response.text().await.unwrap_or_default(),
-        )),
     }
 }
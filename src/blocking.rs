@@ -0,0 +1,458 @@
+//! A blocking client for contexts without an async runtime, e.g. simple scripts.
+//!
+//! The [`Client`][client] mirrors the asynchronous [`crate::Client`] and its wrapping clients
+//! ([`account`][account], [`domain`][domain], [`rrset`][rrset] and [`token`][token]) with the same
+//! method signatures minus `async`. Each blocking client drives the asynchronous one on its own
+//! single threaded runtime, the same approach `reqwest::blocking` takes, so request construction,
+//! retries and status code handling are shared and the two clients cannot drift apart.
+//!
+//! As with `reqwest::blocking`, the methods must not be called from within an async runtime.
+//!
+//! Streams, the [`RrsetQuery`][query] builder and the import from an async reader are only
+//! available on the asynchronous client.
+//!
+//! ```no_run
+//! use desec_api::blocking::Client;
+//!
+//! let client = Client::new("i-T3b1h_OI-H9ab8tRS98stGtURe".to_string()).unwrap();
+//! let domains = client.domain().get_domains().unwrap();
+//! ```
+//!
+//! [client]: struct.Client.html
+//! [account]: struct.Client.html#method.account
+//! [domain]: struct.Client.html#method.domain
+//! [rrset]: struct.Client.html#method.rrset
+//! [token]: struct.Client.html#method.token
+//! [query]: ../rrset/struct.RrsetQuery.html
+
+use crate::account::{AccountInformation, Captcha, CaptchaKind, Login, RegisterResponse};
+use crate::diagnostics::Diagnostics;
+use crate::domain::{DNSSECKeyInfo, Domain, ZoneDrift};
+use crate::pagination::Page;
+use crate::rrset::{NewRrset, RecordType, RecordValue, ResourceRecordSet, ZoneDiff};
+use crate::token::{Token, TokenBuilder, TokenPolicy, TokenPolicyBuilder};
+use crate::Error;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+// Generates blocking methods which delegate to the method of the same name of the async client
+// returned by `$api` and wait for its completion.
+macro_rules! blocking_methods {
+    ($api:ident, $path:literal; $($(#[$meta:meta])* fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            #[doc = concat!("Blocking variant of [`", $path, "::", stringify!($name), "`].")]
+            $(#[$meta])*
+            pub fn $name(&self $(, $arg: $ty)*) -> $ret {
+                self.client.block_on(self.client.inner.$api().$name($($arg),*))
+            }
+        )*
+    };
+}
+
+/// A blocking client to work with the deSEC API, see the [module documentation][module].
+///
+/// Clones share the runtime and the state of the asynchronous client.
+///
+/// [module]: index.html
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: crate::Client,
+    runtime: Arc<Runtime>,
+}
+
+impl Client {
+    /// Creates a new blocking client using the given API token.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::ReqwestClientBuilder`][error] if the http client or the runtime cannot be built.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn new(token: String) -> Result<Self, Error> {
+        Client::from_async(crate::Client::new(token)?)
+    }
+
+    /// Creates a new blocking client using the given API token, sending all requests to the given base URL.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::ReqwestClientBuilder`][error] if the http client or the runtime cannot be built.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn new_with_url(token: String, base_url: &str) -> Result<Self, Error> {
+        Client::from_async(crate::Client::new_with_url(token, base_url)?)
+    }
+
+    /// Creates a new blocking client using the given credentials.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub fn new_from_credentials(email: &str, password: &str) -> Result<Self, Error> {
+        let runtime = Arc::new(build_runtime()?);
+        let inner = runtime.block_on(crate::Client::new_from_credentials(email, password))?;
+        Ok(Client { inner, runtime })
+    }
+
+    /// Creates a new unauthenticated blocking client, e.g. to obtain a captcha, register and log in.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::ReqwestClientBuilder`][error] if the http client or the runtime cannot be built.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn unauthenticated() -> Result<Self, Error> {
+        Client::from_async(crate::Client::unauthenticated()?)
+    }
+
+    /// Wraps an asynchronous client, e.g. one configured with the [`ClientBuilder`][builder].
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::ReqwestClientBuilder`][error] if the runtime cannot be built.
+    ///
+    /// [builder]: ../struct.ClientBuilder.html
+    /// [error]: ../enum.Error.html
+    pub fn from_async(client: crate::Client) -> Result<Self, Error> {
+        Ok(Client {
+            inner: client,
+            runtime: Arc::new(build_runtime()?),
+        })
+    }
+
+    /// Returns the wrapped asynchronous client, e.g. to access its configuration.
+    pub fn inner(&self) -> &crate::Client {
+        &self.inner
+    }
+
+    /// Returns the wrapped asynchronous client mutably, e.g. to change its configuration.
+    pub fn inner_mut(&mut self) -> &mut crate::Client {
+        &mut self.inner
+    }
+
+    /// Returns a wrapping client for the account API.
+    pub fn account(&self) -> AccountClient<'_> {
+        AccountClient { client: self }
+    }
+
+    /// Returns a wrapping client for the domain API.
+    pub fn domain(&self) -> DomainClient<'_> {
+        DomainClient { client: self }
+    }
+
+    /// Returns a wrapping client for the Resource Record Sets (RRsets) API.
+    pub fn rrset(&self) -> RrsetClient<'_> {
+        RrsetClient { client: self }
+    }
+
+    /// Returns a wrapping client for the token API.
+    pub fn token(&self) -> TokenClient<'_> {
+        TokenClient { client: self }
+    }
+
+    /// Blocking variant of [`crate::Client::logout`].
+    ///
+    /// # Errors
+    ///
+    /// see [`crate::Client::logout`]
+    pub fn logout(self) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.logout())
+    }
+
+    /// Blocking variant of [`crate::Client::shutdown`].
+    ///
+    /// # Errors
+    ///
+    /// see [`crate::Client::shutdown`]
+    pub fn shutdown(self) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.shutdown())
+    }
+
+    /// Blocking variant of [`crate::Client::warm_up`].
+    ///
+    /// # Errors
+    ///
+    /// see [`crate::Client::warm_up`]
+    pub fn warm_up(&self) -> Result<(), Error> {
+        self.block_on(self.inner.warm_up())
+    }
+
+    /// Blocking variant of [`crate::Client::options`].
+    ///
+    /// # Errors
+    ///
+    /// see [`crate::Client::options`]
+    pub fn options(&self, path: &str) -> Result<Vec<String>, Error> {
+        self.block_on(self.inner.options(path))
+    }
+
+    /// Blocking variant of [`crate::Client::verify_token`].
+    ///
+    /// # Errors
+    ///
+    /// see [`crate::Client::verify_token`]
+    pub fn verify_token(&self) -> Result<bool, Error> {
+        self.block_on(self.inner.verify_token())
+    }
+
+    /// Blocking variant of [`crate::Client::get_captcha`].
+    ///
+    /// # Errors
+    ///
+    /// see [`crate::Client::get_captcha`]
+    pub fn get_captcha(&self) -> Result<Captcha, Error> {
+        self.block_on(self.inner.get_captcha())
+    }
+
+    /// Blocking variant of [`crate::Client::get_captcha_of_kind`].
+    ///
+    /// # Errors
+    ///
+    /// see [`crate::Client::get_captcha_of_kind`]
+    pub fn get_captcha_of_kind(&self, kind: CaptchaKind) -> Result<Captcha, Error> {
+        self.block_on(self.inner.get_captcha_of_kind(kind))
+    }
+
+    /// Blocking variant of [`crate::Client::register`].
+    ///
+    /// # Errors
+    ///
+    /// see [`crate::Client::register`]
+    pub fn register(
+        &self,
+        email: &str,
+        password: &str,
+        captcha_id: &str,
+        captcha_solution: &str,
+        domain: Option<&str>,
+    ) -> Result<RegisterResponse, Error> {
+        self.block_on(
+            self.inner
+                .register(email, password, captcha_id, captcha_solution, domain),
+        )
+    }
+
+    /// Blocking variant of [`crate::Client::login`].
+    ///
+    /// # Errors
+    ///
+    /// see [`crate::Client::login`]
+    pub fn login(&self, email: &str, password: &str) -> Result<Login, Error> {
+        self.block_on(self.inner.login(email, password))
+    }
+
+    /// Blocking variant of [`crate::Client::diagnose`].
+    ///
+    /// # Errors
+    ///
+    /// see [`crate::Client::diagnose`]
+    pub fn diagnose(&self) -> Result<Diagnostics, Error> {
+        self.block_on(self.inner.diagnose())
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+/// Blocking variant of [`crate::account::get_captcha`].
+///
+/// # Errors
+///
+/// see [`crate::account::get_captcha`]
+pub fn get_captcha() -> Result<Captcha, Error> {
+    build_runtime()?.block_on(crate::account::get_captcha())
+}
+
+/// Blocking variant of [`crate::account::get_captcha_of_kind`].
+///
+/// # Errors
+///
+/// see [`crate::account::get_captcha_of_kind`]
+pub fn get_captcha_of_kind(kind: CaptchaKind) -> Result<Captcha, Error> {
+    build_runtime()?.block_on(crate::account::get_captcha_of_kind(kind))
+}
+
+/// Blocking variant of [`crate::account::register`].
+///
+/// # Errors
+///
+/// see [`crate::account::register`]
+pub fn register(
+    email: &str,
+    password: &str,
+    captcha_id: &str,
+    captcha_solution: &str,
+    domain: Option<&str>,
+) -> Result<RegisterResponse, Error> {
+    build_runtime()?.block_on(crate::account::register(
+        email,
+        password,
+        captcha_id,
+        captcha_solution,
+        domain,
+    ))
+}
+
+/// Blocking variant of [`crate::account::login`].
+///
+/// # Errors
+///
+/// see [`crate::account::login`]
+pub fn login(email: &str, password: &str) -> Result<Login, Error> {
+    build_runtime()?.block_on(crate::account::login(email, password))
+}
+
+fn build_runtime() -> Result<Runtime, Error> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|error| Error::ReqwestClientBuilder(format!("cannot build the runtime: {error}")))
+}
+
+/// A blocking client to work with the deSEC account API.
+pub struct AccountClient<'a> {
+    client: &'a Client,
+}
+
+impl<'a> AccountClient<'a> {
+    blocking_methods! { account, "crate::account::AccountClient";
+        fn get_account_info(&self) -> Result<AccountInformation, Error>;
+        fn update_outreach_preference(&self, outreach_preference: bool) -> Result<AccountInformation, Error>;
+        fn request_password_reset(&self, email: &str, captcha_id: &str, captcha_solution: &str) -> Result<AccountInformation, Error>;
+        fn confirm_password_reset(&self, new_password: &str, code: &str) -> Result<AccountInformation, Error>;
+        fn update_email(&self, email: &str, password: &str, new_email: &str) -> Result<AccountInformation, Error>;
+        fn delete_account(&self, email: &str, password: &str) -> Result<AccountInformation, Error>;
+    }
+}
+
+/// A blocking client to work with the deSEC domain API.
+pub struct DomainClient<'a> {
+    client: &'a Client,
+}
+
+impl<'a> DomainClient<'a> {
+    blocking_methods! { domain, "crate::domain::DomainClient";
+        fn create_domain(&self, domain: &str) -> Result<Domain, Error>;
+        fn create_domain_with_zonefile(&self, domain: &str, zonefile: &str) -> Result<Domain, Error>;
+        fn create_all_or_none(&self, names: &[&str]) -> Result<Vec<Domain>, Error>;
+        fn get_domains(&self) -> Result<Vec<Domain>, Error>;
+        fn get_domains_page(&self, cursor: Option<&str>) -> Result<Page<Domain>, Error>;
+        fn count_domains(&self) -> Result<u64, Error>;
+        fn get_domain(&self, domain: &str) -> Result<Domain, Error>;
+        fn try_get_domain(&self, domain: &str) -> Result<Option<Domain>, Error>;
+        fn wait_for_dnssec_keys(&self, domain: &str, timeout: Duration) -> Result<Vec<DNSSECKeyInfo>, Error>;
+        fn delete_domain(&self, domain: &str) -> Result<(), Error>;
+        fn delete_domain_if_empty(&self, domain: &str) -> Result<bool, Error>;
+        fn delete_domain_force(&self, domain: &str) -> Result<(), Error>;
+        fn get_owning_domain(&self, qname: &str) -> Result<Vec<Domain>, Error>;
+        fn get_zonefile(&self, domain: &str) -> Result<String, Error>;
+        fn get_zone_structured(&self, domain: &str) -> Result<Vec<ResourceRecordSet>, Error>;
+        fn nameservers(&self, domain: &str) -> Result<Vec<String>, Error>;
+        fn detect_drift(&self, domain: &str, baseline_zonefile: &str) -> Result<ZoneDrift, Error>;
+    }
+}
+
+/// A blocking client to create, update or delete Resource Record Sets (RRsets).
+pub struct RrsetClient<'a> {
+    client: &'a Client,
+}
+
+impl<'a> RrsetClient<'a> {
+    blocking_methods! { rrset, "crate::rrset::RrsetClient";
+        fn create_rrset(&self, domain: &str, subname: Option<&str>, rrset_type: &str, ttl: u64, records: &[String]) -> Result<ResourceRecordSet, Error>;
+        fn create_rrsets(&self, domain: &str, rrsets: &[NewRrset]) -> Result<Vec<ResourceRecordSet>, Error>;
+        fn create_rrset_recommended_ttl(&self, domain: &str, subname: Option<&str>, rrset_type: &str, records: &[String]) -> Result<ResourceRecordSet, Error>;
+        fn create_rrset_with_values(&self, domain: &str, subname: Option<&str>, rrset_type: &str, ttl: u64, records: &[RecordValue]) -> Result<ResourceRecordSet, Error>;
+        fn get_rrsets(&self, domain: &str) -> Result<Vec<ResourceRecordSet>, Error>;
+        fn get_rrsets_grouped(&self, domain: &str) -> Result<BTreeMap<String, Vec<ResourceRecordSet>>, Error>;
+        fn get_rrsets_page(&self, domain: &str, cursor: Option<&str>) -> Result<Page<ResourceRecordSet>, Error>;
+        fn count_rrsets(&self, domain: &str) -> Result<u64, Error>;
+        #[cfg(feature = "time")]
+        fn get_rrsets_modified_since(&self, domain: &str, since: time::OffsetDateTime) -> Result<Vec<ResourceRecordSet>, Error>;
+        fn get_rrsets_by_type(&self, domain: &str, r#type: impl Into<RecordType>) -> Result<Vec<ResourceRecordSet>, Error>;
+        fn get_rrsets_by_subname(&self, domain: &str, subname: &str) -> Result<Vec<ResourceRecordSet>, Error>;
+        fn get_rrsets_filtered(&self, domain: &str, subname: Option<&str>, rrset_type: Option<&str>) -> Result<Vec<ResourceRecordSet>, Error>;
+        fn get_rrset(&self, domain: &str, subname: Option<&str>, rrset_type: &str) -> Result<ResourceRecordSet, Error>;
+        fn try_get_rrset(&self, domain: &str, subname: Option<&str>, rrset_type: &str) -> Result<Option<ResourceRecordSet>, Error>;
+        fn get_rrsets_ordered(&self, domain: &str, targets: &[(Option<String>, String)]) -> Vec<Result<ResourceRecordSet, Error>>;
+        fn put_rrset(&self, domain: &str, subname: Option<&str>, rrset_type: &str, ttl: u64, records: &[String]) -> Result<Option<ResourceRecordSet>, Error>;
+        fn update_address(&self, domain: &str, subname: Option<&str>, new_ip: IpAddr, ttl: u64) -> Result<ResourceRecordSet, Error>;
+        fn sync_dynamic_dns(&self, domain: &str, subname: Option<&str>, new_ip: IpAddr, ttl: u64) -> Result<bool, Error>;
+        fn set_acme_challenge(&self, domain: &str, subname: Option<&str>, token_value: &str, ttl: u64) -> Result<ResourceRecordSet, Error>;
+        fn clear_acme_challenge(&self, domain: &str, subname: Option<&str>) -> Result<(), Error>;
+        fn patch_rrset_from(&self, rrset: &ResourceRecordSet) -> Result<Option<ResourceRecordSet>, Error>;
+        fn patch_rrset(&self, domain: &str, subname: Option<&str>, rrset_type: &str, records: &[String], ttl: u64) -> Result<Option<ResourceRecordSet>, Error>;
+        fn patch_rrsets(&self, domain: &str, rrsets: &[ResourceRecordSet]) -> Result<Vec<ResourceRecordSet>, Error>;
+        fn patch_rrset_with_values(&self, domain: &str, subname: Option<&str>, rrset_type: &str, records: &[RecordValue], ttl: u64) -> Result<Option<ResourceRecordSet>, Error>;
+        fn append_record(&self, domain: &str, subname: Option<&str>, rrset_type: &str, record: &str) -> Result<ResourceRecordSet, Error>;
+        fn remove_record(&self, domain: &str, subname: Option<&str>, rrset_type: &str, record: &str) -> Result<Option<ResourceRecordSet>, Error>;
+        fn delete_rrset(&self, domain: &str, subname: Option<&str>, rrset_type: &str) -> Result<(), Error>;
+        fn delete_rrsets(&self, domain: &str, targets: &[(Option<String>, String)]) -> Result<(), Error>;
+        fn delete_rrsets_by_type(&self, domain: &str, rrset_type: impl Into<RecordType>) -> Result<usize, Error>;
+        fn sync_zone(&self, domain: &str, desired: &[ResourceRecordSet], dry_run: bool) -> Result<ZoneDiff, Error>;
+    }
+}
+
+/// A blocking client to work with the deSEC token API.
+pub struct TokenClient<'a> {
+    client: &'a Client,
+}
+
+impl<'a> TokenClient<'a> {
+    blocking_methods! { token, "crate::token::TokenClient";
+        fn create(&self, name: Option<String>, allowed_subnets: Option<Vec<String>>, perm_manage_tokens: Option<bool>, max_age: Option<String>, max_unused_period: Option<String>) -> Result<Token, Error>;
+        fn create_token(&self, token: TokenBuilder) -> Result<Token, Error>;
+        fn delete(&self, token_id: &str) -> Result<(), Error>;
+        fn list(&self) -> Result<Vec<Token>, Error>;
+        fn list_page(&self, cursor: Option<&str>) -> Result<Page<Token>, Error>;
+        fn count(&self) -> Result<u64, Error>;
+        fn get(&self, token_id: &str) -> Result<Token, Error>;
+        fn try_get(&self, token_id: &str) -> Result<Option<Token>, Error>;
+        fn patch(&self, token_id: &str, name: Option<String>, allowed_subnets: Option<Vec<String>>, perm_manage_tokens: Option<bool>, max_age: Option<String>, max_unused_period: Option<String>) -> Result<Token, Error>;
+        fn update_token(&self, token_id: &str, token: TokenBuilder) -> Result<Token, Error>;
+        fn create_policy(&self, token_id: &str, domain: Option<String>, subname: Option<String>, r#type: Option<String>, perm_write: Option<bool>) -> Result<TokenPolicy, Error>;
+        fn create_default_policy(&self, token_id: &str, perm_write: bool) -> Result<TokenPolicy, Error>;
+        fn create_token_policy(&self, token_id: &str, policy: TokenPolicyBuilder) -> Result<TokenPolicy, Error>;
+        fn patch_policy(&self, token_id: &str, policy_id: &str, domain: Option<String>, subname: Option<String>, r#type: Option<String>, perm_write: Option<bool>) -> Result<TokenPolicy, Error>;
+        fn get_policy(&self, token_id: &str, policy_id: &str) -> Result<TokenPolicy, Error>;
+        fn try_get_policy(&self, token_id: &str, policy_id: &str) -> Result<Option<TokenPolicy>, Error>;
+        fn list_policies(&self, token_id: &str) -> Result<Vec<TokenPolicy>, Error>;
+        fn delete_policy(&self, token_id: &str, policy_id: &str) -> Result<(), Error>;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    #[test]
+    fn blocking_client_works_without_runtime() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/api/v1", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0u8; 1024];
+            let read = stream.read(&mut buffer).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n[]")
+                .unwrap();
+            String::from_utf8_lossy(&buffer[..read]).to_string()
+        });
+        let client = crate::Client::builder()
+            .no_proxy()
+            .base_url(&base_url)
+            .build()
+            .unwrap();
+        let client = Client::from_async(client).unwrap();
+        assert!(client.domain().get_domains().unwrap().is_empty());
+        assert!(server.join().unwrap().starts_with("GET /api/v1/domains/"));
+    }
+}
@@ -0,0 +1,68 @@
+use crate::rrset::ResourceRecordSet;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+/// Identifies a cached RRset by domain, subname and type.
+pub(crate) type RrsetKey = (String, String, String);
+
+/// An RRset as retrieved from the API.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedRrset {
+    pub(crate) rrset: ResourceRecordSet,
+    /// ETag of the response the RRset was retrieved with
+    pub(crate) etag: Option<String>,
+    /// Time the RRset was retrieved or last revalidated
+    pub(crate) fetched: Instant,
+}
+
+/// Cache of retrieved RRsets, shared by all clones of a client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RrsetCache {
+    entries: Arc<Mutex<HashMap<RrsetKey, CachedRrset>>>,
+}
+
+impl RrsetCache {
+    fn entries(&self) -> MutexGuard<'_, HashMap<RrsetKey, CachedRrset>> {
+        // The map stays consistent even if a thread panicked while holding the lock
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn get(&self, key: &RrsetKey) -> Option<CachedRrset> {
+        self.entries().get(key).cloned()
+    }
+
+    pub(crate) fn insert(&self, key: RrsetKey, rrset: ResourceRecordSet, etag: Option<String>) {
+        self.entries().insert(
+            key,
+            CachedRrset {
+                rrset,
+                etag,
+                fetched: Instant::now(),
+            },
+        );
+    }
+
+    /// Marks the cached RRset as fresh after a successful revalidation.
+    pub(crate) fn refresh(&self, key: &RrsetKey) {
+        if let Some(entry) = self.entries().get_mut(key) {
+            entry.fetched = Instant::now();
+        }
+    }
+
+    pub(crate) fn remove(&self, key: &RrsetKey) {
+        self.entries().remove(key);
+    }
+
+    /// Removes all cached RRsets of the given domain.
+    pub(crate) fn remove_domain(&self, domain: &str) {
+        self.entries()
+            .retain(|(cached_domain, _, _), _| cached_domain != domain);
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries().clear();
+    }
+}
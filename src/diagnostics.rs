@@ -0,0 +1,127 @@
+//! Connectivity check for the deSEC API.
+
+use crate::{Client, Error};
+use std::time::{Duration, Instant};
+
+/// Result of [`Client::diagnose`][diagnose].
+///
+/// [diagnose]: ../struct.Client.html#method.diagnose
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// Whether the API responded to an unauthenticated request
+    pub reachable: bool,
+    /// Round-trip time of the unauthenticated request
+    pub latency: Option<Duration>,
+    /// Whether the credentials of the client are accepted by the API
+    pub authenticated: bool,
+    /// Round-trip time of the authenticated request
+    pub authenticated_latency: Option<Duration>,
+    /// Description of the problem if the API is unreachable or the authentication failed
+    pub error: Option<String>,
+}
+
+impl Client {
+    /// Checks whether the API accepts the credentials of the client, e.g. at startup.
+    ///
+    /// A single authenticated request listing the tokens is sent. A token lacking the permission
+    /// to manage tokens is rejected with 403 Forbidden, which still proves it is valid.
+    ///
+    /// # Errors
+    ///
+    /// This method returns `Ok(false)` instead of failing with [`Error::Unauthorized`][error],
+    /// all other errors are passed on, see [General errors][general_errors].
+    ///
+    /// [error]: enum.Error.html
+    /// [general_errors]: index.html#general-errors-for-all-clients
+    pub async fn verify_token(&self) -> Result<bool, Error> {
+        match self.get("/auth/tokens/").await {
+            Ok(_) | Err(Error::Forbidden { .. }) => Ok(true),
+            Err(Error::Unauthorized(_)) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Checks whether the API is reachable and the client is authenticated.
+    ///
+    /// First an unauthenticated request (obtaining a captcha) is sent to check the connectivity,
+    /// followed by an authenticated request (retrieving the account information).
+    /// Both requests use the configuration of this client, e.g. its proxies and extra headers,
+    /// only the `Authorization` header is left out of the unauthenticated request.
+    /// Failures of these requests are reported in the returned [`Diagnostics`][diagnostics],
+    /// so network failures can be told apart from invalid credentials.
+    ///
+    /// # Errors
+    ///
+    /// This method does not fail, the `Result` is kept for future checks.
+    ///
+    /// [diagnostics]: diagnostics/struct.Diagnostics.html
+    pub async fn diagnose(&self) -> Result<Diagnostics, Error> {
+        let mut unauth = self.clone();
+        unauth.authorization = None;
+        unauth.extra_headers.remove(reqwest::header::AUTHORIZATION);
+
+        let start = Instant::now();
+        match unauth.post("/captcha/", None).await {
+            Err(Error::Reqwest(error)) | Err(Error::AmbiguousWrite(_, error)) => {
+                return Ok(Diagnostics {
+                    reachable: false,
+                    latency: None,
+                    authenticated: false,
+                    authenticated_latency: None,
+                    error: Some(format!("API is unreachable: {error}")),
+                })
+            }
+            // Any response, even an error status, proves connectivity
+            _ => (),
+        }
+        let latency = Some(start.elapsed());
+
+        let start = Instant::now();
+        let result = self.account().get_account_info().await;
+        let authenticated_latency = Some(start.elapsed());
+        Ok(match result {
+            Ok(_) => Diagnostics {
+                reachable: true,
+                latency,
+                authenticated: true,
+                authenticated_latency,
+                error: None,
+            },
+            Err(error) => Diagnostics {
+                reachable: true,
+                latency,
+                authenticated: false,
+                authenticated_latency,
+                error: Some(error.to_string()),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{mock_client_with, response};
+    use crate::Client;
+
+    #[tokio::test]
+    async fn diagnose_uses_client_configuration_without_token() {
+        let (client, server) = mock_client_with(
+            Client::builder()
+                .token("secret".to_string())
+                .user_agent("diagnose-test".to_string())
+                .default_header("X-Api-Gateway-Key", "gateway"),
+            vec![response(201, "{}"), response(401, "")],
+        )
+        .await;
+        let diagnostics = client.diagnose().await.unwrap();
+        assert!(diagnostics.reachable);
+        assert!(!diagnostics.authenticated);
+        let requests = server.await.unwrap();
+        let probe = requests[0].to_ascii_lowercase();
+        assert!(probe.starts_with("post /api/v1/captcha/ "));
+        assert!(probe.contains("x-api-gateway-key: gateway\r\n"));
+        assert!(probe.contains("user-agent: diagnose-test\r\n"));
+        assert!(!probe.contains("authorization"));
+        assert!(requests[1].contains("authorization: Token secret\r\n"));
+    }
+}
@@ -1,12 +1,33 @@
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+#[cfg(feature = "std")]
+use crate::pagination::Page;
+use crate::rrset::{ModifiedRrset, ResourceRecordSet};
+#[cfg(feature = "std")]
 use crate::{Client, Error};
+#[cfg(feature = "std")]
+use futures_util::Stream;
+#[cfg(feature = "std")]
+use log::debug;
+#[cfg(feature = "std")]
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use serde_json::json;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+#[cfg(feature = "std")]
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+#[cfg(feature = "std")]
+use tokio::time::{sleep, Duration, Instant};
 
 /// An asynchronous client to work with the deSEC domain API.
+#[cfg(feature = "std")]
 pub struct DomainClient<'a> {
     pub(crate) client: &'a crate::Client,
 }
 
+#[cfg(feature = "std")]
 impl<'a> Client {
     /// Returns a wrapping client for the domain API.
     pub fn domain(&'a self) -> DomainClient<'a> {
@@ -16,18 +37,66 @@ impl<'a> Client {
 
 /// Representation of a deSEC [`domain`][reference].
 ///
+/// The same type is used for the list and the detail view of domains,
+/// fields which are not part of both views are optional.
+///
 /// [reference]: https://desec.readthedocs.io/en/latest/dns/domains.html#domain-field-reference
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Domain {
     pub created: String,
+    /// Only part of the detail view, `None` for domains retrieved via the list view
+    #[serde(default)]
     pub keys: Option<Vec<DNSSECKeyInfo>>,
     pub minimum_ttl: u16,
     pub name: String,
+    /// `None` if the domain has not been published yet
+    #[serde(default)]
     pub published: Option<String>,
     pub touched: String,
+    /// Only set when the domain was created from a zonefile
+    #[serde(default)]
     pub zonefile: Option<String>,
 }
 
+#[cfg(feature = "time")]
+impl Domain {
+    /// Returns the creation time of the domain.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if `created` is not a valid timestamp.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn created_at(&self) -> Result<time::OffsetDateTime, Error> {
+        crate::timestamp::parse_timestamp(&self.created)
+    }
+
+    /// Returns the time of the last modification of the domain.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if `touched` is not a valid timestamp.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn touched_at(&self) -> Result<time::OffsetDateTime, Error> {
+        crate::timestamp::parse_timestamp(&self.touched)
+    }
+
+    /// Returns the time the domain has last been published, if it has been published yet.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if `published` is not a valid timestamp.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn published_at(&self) -> Result<Option<time::OffsetDateTime>, Error> {
+        self.published
+            .as_deref()
+            .map(crate::timestamp::parse_timestamp)
+            .transpose()
+    }
+}
+
 /// Representation of a deSEC [`DNSSEC`][reference] key.
 ///
 /// [reference]: https://desec.readthedocs.io/en/latest/dns/domains.html#domain-field-reference
@@ -41,6 +110,69 @@ pub struct DNSSECKeyInfo {
     pub managed: bool,
 }
 
+#[cfg(feature = "std")]
+impl DNSSECKeyInfo {
+    /// Parses the DS records of the key, e.g. to upload them to the registrar of the parent zone.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if a DS record is malformed.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn parse_ds(&self) -> Result<Vec<DsRecord>, Error> {
+        self.ds.iter().map(|ds| DsRecord::parse(ds)).collect()
+    }
+}
+
+/// Fields of a DS record, see [`DNSSECKeyInfo::parse_ds`][parse_ds].
+///
+/// [parse_ds]: struct.DNSSECKeyInfo.html#method.parse_ds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DsRecord {
+    pub key_tag: u16,
+    pub algorithm: u8,
+    pub digest_type: u8,
+    /// Hexadecimal digest in upper case, without whitespace
+    pub digest: String,
+}
+
+#[cfg(feature = "std")]
+impl DsRecord {
+    /// Parses a DS record in presentation format, e.g. `12345 13 2 3C1B...`.
+    fn parse(ds: &str) -> Result<Self, Error> {
+        let invalid =
+            |message: &str| Error::InvalidAPIResponse(message.to_string(), ds.to_string());
+        let mut fields = ds.split_whitespace();
+        let mut number = |name: &str| {
+            fields
+                .next()
+                .ok_or_else(|| invalid(&format!("DS record without {name}")))
+                .map(str::to_string)
+        };
+        let key_tag = number("key tag")?
+            .parse()
+            .map_err(|_| invalid("invalid key tag of DS record"))?;
+        let algorithm = number("algorithm")?
+            .parse()
+            .map_err(|_| invalid("invalid algorithm of DS record"))?;
+        let digest_type = number("digest type")?
+            .parse()
+            .map_err(|_| invalid("invalid digest type of DS record"))?;
+        // The digest may be split into several whitespace separated parts
+        let digest: String = fields.collect::<Vec<_>>().concat().to_ascii_uppercase();
+        if digest.is_empty() || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid("invalid digest of DS record"));
+        }
+        Ok(DsRecord {
+            key_tag,
+            algorithm,
+            digest_type,
+            digest,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
 impl<'a> DomainClient<'a> {
     /// Creates a new domain and returns the newly created [`Domain`][domain].
     ///
@@ -53,11 +185,14 @@ impl<'a> DomainClient<'a> {
     pub async fn create_domain(&self, domain: &str) -> Result<Domain, Error> {
         let response = self
             .client
-            .post("/domains/", Some(format!("{{\"name\": \"{domain}\"}}")))
+            .post(
+                "/domains/",
+                Some(self.client.serialize_body(&json!({ "name": domain }))?),
+            )
             .await?;
         match response.status() {
             StatusCode::CREATED => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
+                let response_text = crate::response_text(response).await?;
                 serde_json::from_str(&response_text)
                     .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
             }
@@ -68,18 +203,41 @@ impl<'a> DomainClient<'a> {
         }
     }
 
-    /// Retrieves a list of all domains that you own in the account.
+    /// Creates a new domain with the RRsets of the given zonefile in a single request,
+    /// e.g. to migrate an existing zone, and returns the newly created [`Domain`][domain].
+    ///
+    /// The zonefile is in the usual BIND format. deSEC ignores the records it manages itself,
+    /// e.g. SOA, DNSKEY and the NS records of the apex. If any record is rejected,
+    /// the domain is not created.
     ///
     /// # Errors
     ///
-    /// see [General errors][general_errors]
+    /// This method fails with [`Error::ApiError`][error] if the zonefile is rejected, the messages
+    /// of the rejected records are available through `ApiErrorBody::field("zonefile")`.
     ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
     /// [general_errors]: ../index.html#general-errors-for-all-clients
-    pub async fn get_domains(&self) -> Result<Vec<Domain>, Error> {
-        let response = self.client.get("/domains/").await?;
+    /// [domain]: ../domain/struct.Domain.html
+    pub async fn create_domain_with_zonefile(
+        &self,
+        domain: &str,
+        zonefile: &str,
+    ) -> Result<Domain, Error> {
+        let response = self
+            .client
+            .post(
+                "/domains/",
+                Some(
+                    self.client
+                        .serialize_body(&json!({ "name": domain, "zonefile": zonefile }))?,
+                ),
+            )
+            .await?;
         match response.status() {
-            StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
+            StatusCode::CREATED => {
+                let response_text = crate::response_text(response).await?;
                 serde_json::from_str(&response_text)
                     .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
             }
@@ -90,6 +248,125 @@ impl<'a> DomainClient<'a> {
         }
     }
 
+    /// Creates all of the given domains or none of them.
+    ///
+    /// Before creating anything, the number of existing domains is checked against the
+    /// `limit_domains` of the account. If the creation of any domain fails, the domains created
+    /// so far are deleted again and the error of the failed creation is returned. Failures during
+    /// this rollback are only logged, so in rare cases (e.g. lost connectivity) domains may remain.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if the domains would exceed the domain limit of the account.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn create_all_or_none(&self, names: &[&str]) -> Result<Vec<Domain>, Error> {
+        let limit = self
+            .client
+            .account()
+            .get_account_info()
+            .await?
+            .limit_domains;
+        let existing = self.count_domains().await?;
+        if existing + names.len() as u64 > limit {
+            return Err(Error::InvalidInput(format!(
+                "creating {} domains would exceed the limit of {limit} domains, {existing} already exist",
+                names.len()
+            )));
+        }
+        let mut created: Vec<Domain> = Vec::with_capacity(names.len());
+        for name in names {
+            match self.create_domain(name).await {
+                Ok(domain) => created.push(domain),
+                Err(error) => {
+                    for domain in created.iter().rev() {
+                        if let Err(rollback_error) = self.delete_domain(&domain.name).await {
+                            debug!(
+                                "Rollback failed to delete domain {}: {}",
+                                domain.name, rollback_error
+                            );
+                        }
+                    }
+                    return Err(error);
+                }
+            }
+        }
+        Ok(created)
+    }
+
+    /// Retrieves a list of all domains that you own in the account.
+    ///
+    /// All pages of the listing are followed.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn get_domains(&self) -> Result<Vec<Domain>, Error> {
+        self.client.get_all_pages("/domains/").await
+    }
+
+    /// Lazily streams all domains in the account, page by page.
+    ///
+    /// The next page is only requested once all domains of the current page have been consumed.
+    /// The stream ends after yielding the first error.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub fn stream_domains(&self) -> impl Stream<Item = Result<Domain, Error>> + 'a {
+        self.client.stream_items("/domains/".to_string())
+    }
+
+    /// Retrieves a single page of the domains in the account.
+    ///
+    /// Pass `None` to get the first page and the [`next_cursor`][page] of a page to get the following one.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [page]: ../pagination/struct.Page.html
+    pub async fn get_domains_page(&self, cursor: Option<&str>) -> Result<Page<Domain>, Error> {
+        self.client.get_page("/domains/", cursor).await
+    }
+
+    /// Returns the number of domains in the account.
+    ///
+    /// See [pagination][pagination] on how the count is determined.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [pagination]: ../pagination/index.html
+    pub async fn count_domains(&self) -> Result<u64, Error> {
+        self.client.count_items::<Domain>("/domains/").await
+    }
+
+    /// Retrieves a specific domain of your account, `None` if it does not exist.
+    ///
+    /// Unlike [`get_domain`][get_domain], a missing resource is not reported as [`Error::NotFound`][error].
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [get_domain]: #method.get_domain
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn try_get_domain(&self, domain: &str) -> Result<Option<Domain>, Error> {
+        crate::not_found_as_none(self.get_domain(domain).await)
+    }
+
     /// Retrieves a specific domain of your account.
     ///
     /// # Errors
@@ -104,9 +381,8 @@ impl<'a> DomainClient<'a> {
             .await?;
         match response.status() {
             StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+                let response_text = crate::response_text(response).await?;
+                crate::parse_single(response_text)
             }
             _ => Err(Error::UnexpectedStatusCode(
                 response.status().into(),
@@ -115,13 +391,72 @@ impl<'a> DomainClient<'a> {
         }
     }
 
+    /// Waits until deSEC has generated the DNSSEC keys of the given domain and returns them.
+    ///
+    /// Keys are created asynchronously shortly after a domain was created, so the `keys` field of
+    /// a freshly created [`Domain`][domain] may still be empty. The domain is polled with increasing
+    /// intervals (capped by `max_wait_retry`) until keys are present or `timeout` has elapsed.
+    ///
+    /// # DNSSEC key rollover
+    ///
+    /// deSEC does not offer an API endpoint to rotate or regenerate the DNSSEC keys of a domain,
+    /// keys are generated once and managed by deSEC. The only way to obtain new keys via the API
+    /// is to delete and recreate the domain, which also removes all of its RRsets and temporarily
+    /// takes the zone offline. For a rollover without downtime contact the deSEC support.
+    /// After new keys are available, the DS records contained in [`DNSSECKeyInfo`][keyinfo] need to
+    /// be updated at the registrar of the domain.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::AsyncOperationTimeout`][error] if no keys are available
+    /// after `timeout`.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [domain]: struct.Domain.html
+    /// [keyinfo]: struct.DNSSECKeyInfo.html
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn wait_for_dnssec_keys(
+        &self,
+        domain: &str,
+        timeout: Duration,
+    ) -> Result<Vec<DNSSECKeyInfo>, Error> {
+        let deadline = Instant::now() + timeout;
+        let mut polls = 0;
+        loop {
+            let keys = self.get_domain(domain).await?.keys.unwrap_or_default();
+            polls += 1;
+            if !keys.is_empty() {
+                return Ok(keys);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::AsyncOperationTimeout(
+                    polls,
+                    format!("/domains/{domain}/"),
+                ));
+            }
+            let ttw = Duration::from_secs(crate::transient_backoff(
+                polls,
+                *self.client.get_max_wait_retry(),
+            ));
+            debug!("DNSSEC keys of {domain} not yet available, waiting {ttw:?}");
+            sleep(ttw.min(deadline - now)).await;
+        }
+    }
+
     /// Deletes the given domain from your account.
     ///
+    /// The domain is deleted regardless of the records it contains, see
+    /// [`delete_domain_if_empty`][if_empty] for a guarded variant.
+    ///
     /// # Errors
     ///
     /// see [General errors][general_errors]
     ///
     /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [if_empty]: #method.delete_domain_if_empty
     pub async fn delete_domain(&self, domain: &str) -> Result<(), Error> {
         let response = self
             .client
@@ -136,6 +471,52 @@ impl<'a> DomainClient<'a> {
         }
     }
 
+    /// Deletes the given domain only if it contains no records besides the ones created by deSEC.
+    ///
+    /// Records managed by deSEC (SOA and the DNSSEC types) as well as the NS RRset at the zone
+    /// apex are not considered user records. Returns `true` if the domain was deleted and `false`
+    /// if user records remain, in which case the domain is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn delete_domain_if_empty(&self, domain: &str) -> Result<bool, Error> {
+        let rrsets = self.client.rrset().get_rrsets(domain).await?;
+        let user_rrsets = rrsets
+            .iter()
+            .filter(|rrset| {
+                let apex = rrset.subname.as_deref().unwrap_or_default().is_empty();
+                let managed = is_managed(&rrset.rrset_type)
+                    || (apex && rrset.rrset_type == crate::rrset::RecordType::NS);
+                !managed
+            })
+            .count();
+        if user_rrsets > 0 {
+            debug!("Not deleting {domain}, {user_rrsets} RRsets with user records remain");
+            return Ok(false);
+        }
+        self.delete_domain(domain).await?;
+        Ok(true)
+    }
+
+    /// Deletes the given domain from your account regardless of the records it contains.
+    ///
+    /// This is the same as [`delete_domain`][delete_domain], making the intent explicit
+    /// next to [`delete_domain_if_empty`][if_empty].
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [delete_domain]: #method.delete_domain
+    /// [if_empty]: #method.delete_domain_if_empty
+    pub async fn delete_domain_force(&self, domain: &str) -> Result<(), Error> {
+        self.delete_domain(domain).await
+    }
+
     /// Returns the account-domain which is responsible for the given DNS name.
     ///
     /// Let’s say you have the domains example.net, dev.example.net and git.dev.example.net,
@@ -157,9 +538,8 @@ impl<'a> DomainClient<'a> {
             .await?;
         match response.status() {
             StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+                let response_text = crate::response_text(response).await?;
+                crate::parse_list(response_text)
             }
             _ => Err(Error::UnexpectedStatusCode(
                 response.status().into(),
@@ -181,11 +561,658 @@ impl<'a> DomainClient<'a> {
             .get(format!("/domains/{domain}/zonefile/").as_str())
             .await?;
         match response.status() {
-            StatusCode::OK => response.text().await.map_err(Error::Reqwest),
+            StatusCode::OK => crate::response_text(response).await,
             _ => Err(Error::UnexpectedStatusCode(
                 response.status().into(),
                 response.text().await.unwrap_or_default(),
             )),
         }
     }
+
+    /// Returns the zone of the given domain as a list of [`ResourceRecordSet`][rrset].
+    ///
+    /// This is the structured counterpart to [`get_zonefile`][zonefile]. The RRsets are retrieved
+    /// via the RRset list endpoint, so no zonefile parsing is involved.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [rrset]: ../rrset/struct.ResourceRecordSet.html
+    /// [zonefile]: #method.get_zonefile
+    pub async fn get_zone_structured(&self, domain: &str) -> Result<Vec<ResourceRecordSet>, Error> {
+        self.client.rrset().get_rrsets(domain).await
+    }
+
+    /// Imports a zonefile into the given domain while reading it, returning the number of imported RRsets.
+    ///
+    /// Records are parsed line by line and sent in bulk requests of up to
+    /// [`IMPORT_CHUNK_RRSETS`][chunk] RRsets, which are additionally split according to the
+    /// [maximum body size][max_body_bytes] of the client. This keeps memory usage flat even for
+    /// zonefiles of several megabytes. Throttled requests are retried like all other requests,
+    /// which paces the import according to the rate limits. The progress is logged at debug level.
+    ///
+    /// Imported RRsets are created or replaced, other RRsets of the zone are left untouched.
+    /// Records managed by deSEC (SOA and the DNSSEC types) and the NS RRset at the zone apex
+    /// are skipped. If records of an already imported RRset appear later in the zonefile,
+    /// they are merged with the imported ones.
+    ///
+    /// Note that the import is not atomic, if it fails the chunks sent before remain applied.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if the zonefile cannot be read or parsed.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [chunk]: constant.IMPORT_CHUNK_RRSETS.html
+    /// [max_body_bytes]: ../struct.Client.html#method.set_max_body_bytes
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn import_zonefile_stream<R: AsyncBufRead + Unpin>(
+        &self,
+        domain: &str,
+        reader: R,
+    ) -> Result<usize, Error> {
+        let mut parser = crate::zonefile::Parser::new(domain);
+        let mut imported = HashSet::new();
+        let mut lines = reader.lines();
+        let mut line_number = 0;
+        while let Some(line) = lines
+            .next_line()
+            .await
+            .map_err(|error| Error::InvalidInput(format!("line {}: {error}", line_number + 1)))?
+        {
+            line_number += 1;
+            parser.parse_line(line_number, &line)?;
+            if parser.rrset_count() >= IMPORT_CHUNK_RRSETS {
+                let rrsets = parser.take_completed();
+                self.import_chunk(domain, rrsets, &mut imported).await?;
+                debug!(
+                    "Imported {} RRsets into {domain} up to line {line_number}",
+                    imported.len()
+                );
+            }
+        }
+        let rrsets = parser.finish()?;
+        self.import_chunk(domain, rrsets, &mut imported).await?;
+        debug!("Imported {} RRsets into {domain}", imported.len());
+        Ok(imported.len())
+    }
+
+    // Creates or replaces the given RRsets with bulk requests,
+    // merging RRsets which were already imported by a previous chunk.
+    async fn import_chunk(
+        &self,
+        domain: &str,
+        rrsets: Vec<ResourceRecordSet>,
+        imported: &mut HashSet<(String, String)>,
+    ) -> Result<(), Error> {
+        let mut payload = Vec::new();
+        for mut rrset in rrsets {
+            let subname = rrset.subname.clone().unwrap_or_default();
+            let rrset_type = rrset.rrset_type.to_string();
+            if MANAGED_TYPES.contains(&rrset_type.as_str())
+                || (subname.is_empty() && rrset_type == "NS")
+            {
+                continue;
+            }
+            if !imported.insert((subname.clone(), rrset_type.clone())) {
+                let previous = self
+                    .client
+                    .rrset()
+                    .get_rrset(
+                        domain,
+                        Some(subname.as_str()).filter(|s| !s.is_empty()),
+                        &rrset_type,
+                    )
+                    .await?;
+                for record in previous.records {
+                    if !rrset.records.contains(&record) {
+                        rrset.records.push(record);
+                    }
+                }
+            }
+            payload.push(json!({
+                "subname": subname,
+                "type": rrset_type,
+                "ttl": rrset.ttl,
+                "records": rrset.records
+            }));
+        }
+        if payload.is_empty() {
+            return Ok(());
+        }
+        self.client.rrset_cache.remove_domain(domain);
+        for body in self.client.serialize_bulk_bodies(&payload)? {
+            let response = self
+                .client
+                .patch(format!("/domains/{domain}/rrsets/").as_str(), body)
+                .await?;
+            match response.status() {
+                StatusCode::OK | StatusCode::NO_CONTENT => (),
+                _ => {
+                    return Err(Error::UnexpectedStatusCode(
+                        response.status().into(),
+                        response.text().await.unwrap_or_default(),
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the hostnames of the nameservers responsible for the given domain.
+    ///
+    /// These are the values of the NS RRset at the zone apex, which need to be configured at your
+    /// registrar after creating a domain. Trailing dots are removed.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn nameservers(&self, domain: &str) -> Result<Vec<String>, Error> {
+        let rrset = self.client.rrset().get_rrset(domain, None, "NS").await?;
+        Ok(rrset
+            .records
+            .iter()
+            .map(|record| record.trim_end_matches('.').to_string())
+            .collect())
+    }
+
+    /// Compares the live zone of the given domain against a baseline zonefile, e.g. a snapshot
+    /// taken earlier with [`get_zonefile`][zonefile], to detect out-of-band changes.
+    ///
+    /// Both sides are normalized before comparison: hostname targets are fully qualified and
+    /// RRsets are compared by TTL and their set of records, ignoring order and insignificant whitespace.
+    /// Records managed by deSEC itself (SOA and the DNSSEC types DNSKEY, CDS, CDNSKEY, RRSIG,
+    /// NSEC, NSEC3 and NSEC3PARAM) are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if the baseline zonefile cannot be parsed.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [zonefile]: #method.get_zonefile
+    pub async fn detect_drift(
+        &self,
+        domain: &str,
+        baseline_zonefile: &str,
+    ) -> Result<ZoneDrift, Error> {
+        let mut baseline = crate::zonefile::parse(domain, baseline_zonefile)?;
+        baseline.retain(|rrset| !is_managed(&rrset.rrset_type));
+        let origin = format!("{}.", domain.trim_end_matches('.'));
+        let mut live = self.client.rrset().get_rrsets(domain).await?;
+        live.retain(|rrset| !is_managed(&rrset.rrset_type));
+        for rrset in live.iter_mut() {
+            for record in rrset.records.iter_mut() {
+                *record = crate::rrset::qualify_target(rrset.rrset_type.as_str(), record, &origin);
+            }
+        }
+        let diff = crate::rrset::diff(&baseline, &live);
+        Ok(ZoneDrift {
+            added: diff.to_create,
+            removed: diff.to_delete,
+            modified: diff.to_update,
+        })
+    }
+}
+
+/// Maximum number of RRsets sent per bulk request by [`DomainClient::import_zonefile_stream`][import].
+///
+/// [import]: struct.DomainClient.html#method.import_zonefile_stream
+pub const IMPORT_CHUNK_RRSETS: usize = 100;
+
+/// Types of records which are managed by deSEC and ignored for drift detection, emptiness checks and imports.
+#[cfg(feature = "std")]
+pub(crate) const MANAGED_TYPES: [&str; 8] = [
+    "SOA",
+    "DNSKEY",
+    "CDS",
+    "CDNSKEY",
+    "RRSIG",
+    "NSEC",
+    "NSEC3",
+    "NSEC3PARAM",
+];
+
+/// Returns whether records of the given type are managed by deSEC, see [`MANAGED_TYPES`].
+#[cfg(feature = "std")]
+pub(crate) fn is_managed(rrset_type: &crate::rrset::RecordType) -> bool {
+    MANAGED_TYPES.iter().any(|managed| rrset_type == managed)
+}
+
+/// Differences between the live zone and a baseline, see [`DomainClient::detect_drift`].
+#[derive(Debug, Clone, Default)]
+pub struct ZoneDrift {
+    /// RRsets which exist in the live zone but not in the baseline
+    pub added: Vec<ResourceRecordSet>,
+    /// RRsets which exist in the baseline but not in the live zone
+    pub removed: Vec<ResourceRecordSet>,
+    /// RRsets whose TTL or records differ, `before` is the baseline and `after` the live state
+    pub modified: Vec<ModifiedRrset>,
+}
+
+impl ZoneDrift {
+    /// Returns whether the live zone matches the baseline.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        domain_json, mock_client, mock_client_with, page_response, request_body, response,
+        rrset_json,
+    };
+
+    #[test]
+    fn ds_records_are_parsed() {
+        let key = DNSSECKeyInfo {
+            ds: vec![
+                "6006 13 2 8b1d4d2b0e4e0e6a1f0ae1fca57b5b0a8cd4e1b0fbe9e9b83b7a3b43c1e8a2c7"
+                    .to_string(),
+                "6006 13 4 1E0A 9F3B".to_string(),
+            ],
+            ..Default::default()
+        };
+        let ds = key.parse_ds().unwrap();
+        assert_eq!(
+            ds[0],
+            DsRecord {
+                key_tag: 6006,
+                algorithm: 13,
+                digest_type: 2,
+                digest: "8B1D4D2B0E4E0E6A1F0AE1FCA57B5B0A8CD4E1B0FBE9E9B83B7A3B43C1E8A2C7"
+                    .to_string(),
+            }
+        );
+        assert_eq!(ds[1].digest, "1E0A9F3B");
+        let key = DNSSECKeyInfo {
+            ds: vec!["6006 13".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            key.parse_ds(),
+            Err(Error::InvalidAPIResponse(_, _))
+        ));
+    }
+
+    #[test]
+    fn domain_list_view_deserializes() {
+        let json = r#"[
+            {
+                "created": "2018-09-18T16:36:16.510368Z",
+                "published": "2018-09-18T17:21:38.348112Z",
+                "name": "example.com",
+                "minimum_ttl": 3600,
+                "touched": "2018-09-18T17:21:38.348112Z"
+            },
+            {
+                "created": "2023-01-02T10:00:00.000000Z",
+                "published": null,
+                "name": "example.org",
+                "minimum_ttl": 3600,
+                "touched": "2023-01-02T10:00:00.000000Z"
+            }
+        ]"#;
+        let domains: Vec<Domain> = serde_json::from_str(json).unwrap();
+        assert_eq!(domains.len(), 2);
+        assert_eq!(domains[0].name, "example.com");
+        assert!(domains[0].keys.is_none());
+        assert!(domains[0].zonefile.is_none());
+        assert!(domains[1].published.is_none());
+    }
+
+    #[test]
+    fn domain_detail_view_deserializes() {
+        let json = r#"{
+            "created": "2018-09-18T16:36:16.510368Z",
+            "keys": [
+                {
+                    "dnskey": "257 3 13 WFRl60NbYvqOjsmRIWbWQ4SqKOLhvPJHf2pf9tUcFk0VXN7S9DYXbzb/m84uPtDYNbWVHqUBlZ4fSPaM9AFw4Q==",
+                    "ds": [
+                        "6006 13 2 8d2aa4fd12b6d1bd9c8e8b0fd4ba1e49d4dd76bd2d7a0dbc6dae9e4c7be72e1b",
+                        "6006 13 4 b81a6a4f40bf0e64e4196d27b1f5b9219bdf1cf2e5f0b56e79e2eeaf5b9b9a4f7aa6d6fb3b6a9e6ec04eb72c3fc8c4b3"
+                    ],
+                    "flags": 257,
+                    "keytype": "csk",
+                    "managed": true
+                }
+            ],
+            "minimum_ttl": 3600,
+            "name": "example.com",
+            "published": "2018-09-18T17:21:38.348112Z",
+            "touched": "2018-09-18T17:21:38.348112Z"
+        }"#;
+        let domain: Domain = serde_json::from_str(json).unwrap();
+        let keys = domain.keys.unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].keyflags, 257);
+        assert_eq!(keys[0].ds.len(), 2);
+        assert_eq!(domain.minimum_ttl, 3600);
+    }
+
+    #[tokio::test]
+    async fn structured_zone_is_listed_from_all_rrset_pages() {
+        let (client, server) = mock_client(vec![
+            page_response(
+                &format!(
+                    "[{}]",
+                    rrset_json("example.com", "", "NS", 3600, &["ns1.desec.io."])
+                ),
+                Some("c1"),
+            ),
+            page_response(
+                &format!(
+                    "[{}]",
+                    rrset_json("example.com", "www", "A", 300, &["192.0.2.1"])
+                ),
+                None,
+            ),
+        ])
+        .await;
+        let zone = client
+            .domain()
+            .get_zone_structured("example.com")
+            .await
+            .unwrap();
+        let requests = server.await.unwrap();
+        assert_eq!(zone.len(), 2);
+        assert_eq!(zone[0].rrset_type, "NS");
+        assert_eq!(zone[1].subname.as_deref(), Some("www"));
+        assert_eq!(zone[1].records, ["192.0.2.1"]);
+        assert!(requests[0].starts_with("GET /api/v1/domains/example.com/rrsets/ "));
+        assert!(requests[1].starts_with("GET /api/v1/domains/example.com/rrsets/?cursor=c1 "));
+    }
+
+    #[tokio::test]
+    async fn nameservers_are_read_from_apex_ns_rrset() {
+        let (client, server) = mock_client(vec![response(
+            200,
+            &rrset_json(
+                "example.com",
+                "",
+                "NS",
+                3600,
+                &["ns1.desec.io.", "ns2.desec.org."],
+            ),
+        )])
+        .await;
+        let nameservers = client.domain().nameservers("example.com").await.unwrap();
+        assert_eq!(nameservers, ["ns1.desec.io", "ns2.desec.org"]);
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/domains/example.com/rrsets/@/NS/ "));
+    }
+
+    #[tokio::test]
+    async fn list_methods_follow_all_pages() {
+        let (client, server) = mock_client(vec![
+            page_response(&format!("[{}]", domain_json("a.example")), Some("c1")),
+            page_response("[]", Some("c2")),
+            page_response(&format!("[{}]", domain_json("b.example")), None),
+        ])
+        .await;
+        let domains = client.domain().get_domains().await.unwrap();
+        let requests = server.await.unwrap();
+        assert_eq!(
+            domains
+                .iter()
+                .map(|domain| domain.name.as_str())
+                .collect::<Vec<_>>(),
+            ["a.example", "b.example"]
+        );
+        assert!(requests[0].starts_with("GET /api/v1/domains/ "));
+        assert!(requests[1].starts_with("GET /api/v1/domains/?cursor=c1 "));
+        assert!(requests[2].starts_with("GET /api/v1/domains/?cursor=c2 "));
+    }
+
+    #[tokio::test]
+    async fn rejected_zonefile_yields_field_errors() {
+        let (client, server) = mock_client(vec![response(
+            400,
+            r#"{"zonefile": ["www.example.com. 3600 IN A 999.0.0.1: invalid IPv4 address"]}"#,
+        )])
+        .await;
+        let error = client
+            .domain()
+            .create_domain_with_zonefile("example.com", "www 3600 IN A 999.0.0.1\n")
+            .await
+            .unwrap_err();
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("POST /api/v1/domains/ "));
+        match error {
+            Error::ApiError(400, body) => assert_eq!(
+                body.field("zonefile"),
+                ["www.example.com. 3600 IN A 999.0.0.1: invalid IPv4 address"]
+            ),
+            _ => panic!("Should yield desec_api::Error::ApiError"),
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_domain_is_none_for_try_get() {
+        let (client, server) = mock_client(vec![response(404, ""), response(500, "")]).await;
+        let domain = client.domain().try_get_domain("example.com").await;
+        assert!(domain.unwrap().is_none());
+        let domain = client.domain().try_get_domain("example.com").await;
+        assert!(matches!(domain, Err(Error::UnexpectedStatusCode(500, _))));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn zonefile_stream_is_imported_in_chunks() {
+        let (client, server) = mock_client(vec![response(200, "[]"), response(200, "[]")]).await;
+        // The chunk is full after the first record of h99, which may still receive records
+        let mut zonefile = (0..IMPORT_CHUNK_RRSETS)
+            .map(|index| format!("h{index} 3600 IN A 192.0.2.1\n"))
+            .collect::<String>();
+        zonefile.push_str("h99 3600 IN A 192.0.2.2\nh100 3600 IN A 192.0.2.3\n");
+        let imported = client
+            .domain()
+            .import_zonefile_stream("example.com", zonefile.as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(imported, 101);
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("PATCH /api/v1/domains/example.com/rrsets/ "));
+        assert!(requests[1].starts_with("PATCH /api/v1/domains/example.com/rrsets/ "));
+        let first = request_body(&requests[0]);
+        let first = first.as_array().unwrap();
+        assert_eq!(first.len(), 99);
+        assert_eq!(first[98]["subname"], "h98");
+        assert!(first.iter().all(|rrset| rrset["subname"] != "h99"));
+        // The held back RRset is complete in the second chunk, no merge with a GET is needed
+        assert_eq!(
+            request_body(&requests[1]),
+            serde_json::json!([
+                {"subname": "h99", "type": "A", "ttl": 3600, "records": ["192.0.2.1", "192.0.2.2"]},
+                {"subname": "h100", "type": "A", "ttl": 3600, "records": ["192.0.2.3"]}
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn dnssec_keys_are_polled_until_available() {
+        let mut ready: serde_json::Value =
+            serde_json::from_str(&domain_json("example.com")).unwrap();
+        ready["keys"] = serde_json::json!([{
+            "dnskey": "257 3 13 key",
+            "ds": ["6006 13 2 digest"],
+            "flags": 257,
+            "keytype": "csk",
+            "managed": true
+        }]);
+        let (client, server) = mock_client_with(
+            Client::builder().max_wait_retry(0),
+            vec![
+                response(200, &domain_json("example.com")),
+                response(200, &ready.to_string()),
+            ],
+        )
+        .await;
+        let keys = client
+            .domain()
+            .wait_for_dnssec_keys("example.com", Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].ds, ["6006 13 2 digest"]);
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests
+            .iter()
+            .all(|request| request.starts_with("GET /api/v1/domains/example.com/ ")));
+    }
+
+    #[tokio::test]
+    async fn domain_is_only_deleted_without_user_records() {
+        let with_user_records = format!(
+            "[{}, {}]",
+            rrset_json("example.com", "", "NS", 3600, &["ns1.desec.io."]),
+            rrset_json("example.com", "www", "A", 3600, &["192.0.2.1"])
+        );
+        let managed_only = format!(
+            "[{}, {}]",
+            rrset_json("example.com", "", "NS", 3600, &["ns1.desec.io."]),
+            rrset_json(
+                "example.com",
+                "",
+                "SOA",
+                3600,
+                &["get.desec.io. get.desec.io. 1 86400 3600 2419200 3600"]
+            )
+        );
+        let (client, server) = mock_client(vec![
+            page_response(&with_user_records, None),
+            page_response(&managed_only, None),
+            response(204, ""),
+        ])
+        .await;
+        assert!(!client
+            .domain()
+            .delete_domain_if_empty("example.com")
+            .await
+            .unwrap());
+        assert!(client
+            .domain()
+            .delete_domain_if_empty("example.com")
+            .await
+            .unwrap());
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/domains/example.com/rrsets/"));
+        assert!(requests[1].starts_with("GET /api/v1/domains/example.com/rrsets/"));
+        assert!(requests[2].starts_with("DELETE /api/v1/domains/example.com/ "));
+    }
+
+    #[tokio::test]
+    async fn drift_against_baseline_is_detected() {
+        let baseline = "\
+example.com. 3600 IN SOA get.desec.io. get.desec.io. 1 86400 3600 2419200 3600
+www.example.com. 3600 IN CNAME web.example.com.
+mail.example.com. 3600 IN A 192.0.2.1
+old.example.com. 3600 IN A 192.0.2.2
+";
+        // The live SOA and DNSKEY differ from the baseline but are managed by deSEC
+        let live = format!(
+            "[{}, {}, {}, {}, {}]",
+            rrset_json(
+                "example.com",
+                "",
+                "SOA",
+                3600,
+                &["get.desec.io. get.desec.io. 2 86400 3600 2419200 3600"]
+            ),
+            rrset_json("example.com", "", "DNSKEY", 3600, &["257 3 13 key"]),
+            rrset_json("example.com", "www", "CNAME", 3600, &["web"]),
+            rrset_json("example.com", "mail", "A", 3600, &["192.0.2.3"]),
+            rrset_json("example.com", "new", "A", 3600, &["192.0.2.4"])
+        );
+        let (client, server) = mock_client(vec![page_response(&live, None)]).await;
+        let drift = client
+            .domain()
+            .detect_drift("example.com", baseline)
+            .await
+            .unwrap();
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/domains/example.com/rrsets/"));
+
+        let subnames = |rrsets: &[ResourceRecordSet]| {
+            rrsets
+                .iter()
+                .map(|rrset| rrset.subname.clone().unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(subnames(&drift.added), ["new"]);
+        assert_eq!(subnames(&drift.removed), ["old"]);
+        assert_eq!(drift.modified.len(), 1);
+        assert_eq!(drift.modified[0].before.records, ["192.0.2.1"]);
+        assert_eq!(drift.modified[0].after.records, ["192.0.2.3"]);
+        assert!(!drift.is_empty());
+    }
+
+    fn account_response(limit_domains: u64) -> String {
+        let account = serde_json::json!({
+            "created": "2024-05-04T10:00:00Z",
+            "email": "owner@example.com",
+            "id": "9ab16e5c-805d-4ab1-9030-af3f5a541d47",
+            "limit_domains": limit_domains,
+            "outreach_preference": false
+        });
+        response(200, &account.to_string())
+    }
+
+    #[tokio::test]
+    async fn created_domains_are_rolled_back_on_failure() {
+        let (client, server) = mock_client(vec![
+            account_response(5),
+            page_response(&format!("[{}]", domain_json("existing.example")), None),
+            response(201, &domain_json("a.example")),
+            response(400, r#"{"name": ["This domain name is unavailable."]}"#),
+            response(204, ""),
+        ])
+        .await;
+        let error = client
+            .domain()
+            .create_all_or_none(&["a.example", "b.example"])
+            .await
+            .unwrap_err();
+        match error {
+            Error::ApiError(400, body) => {
+                assert_eq!(body.field("name"), ["This domain name is unavailable."])
+            }
+            _ => panic!("Should yield desec_api::Error::ApiError"),
+        }
+        let requests = server.await.unwrap();
+        assert_eq!(request_body(&requests[2])["name"], "a.example");
+        assert_eq!(request_body(&requests[3])["name"], "b.example");
+        assert!(requests[4].starts_with("DELETE /api/v1/domains/a.example/ "));
+    }
+
+    #[tokio::test]
+    async fn domain_limit_is_checked_before_creating() {
+        let (client, server) = mock_client(vec![
+            account_response(2),
+            page_response(&format!("[{}]", domain_json("existing.example")), None),
+        ])
+        .await;
+        let error = client
+            .domain()
+            .create_all_or_none(&["a.example", "b.example"])
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::InvalidInput(_)));
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/auth/account/ "));
+        assert!(requests[1].starts_with("GET /api/v1/domains/"));
+    }
 }
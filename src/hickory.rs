@@ -0,0 +1,226 @@
+//! Conversion between RRsets and the records of [`hickory_proto`].
+//!
+//! Only A, AAAA, CNAME, MX, TXT and NS records are supported, converting RRsets of other types
+//! fails with [`Error::InvalidInput`].
+
+use crate::records::split_fields;
+use crate::rrset::{fully_qualify, qualify_target, RecordType, ResourceRecordSet};
+use crate::Error;
+use hickory_proto::rr::rdata::{A, AAAA, CNAME, MX, NS, TXT};
+use hickory_proto::rr::{Name, RData, Record};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Converts the records of an RRset into hickory-proto records.
+///
+/// The owner name is taken from `name`, or built from `subname` and `domain` if `name` is empty.
+/// Relative hostname targets are qualified with `domain`.
+impl TryFrom<&ResourceRecordSet> for Vec<Record> {
+    type Error = Error;
+
+    fn try_from(rrset: &ResourceRecordSet) -> Result<Self, Self::Error> {
+        let origin = fully_qualify(&rrset.domain, ".");
+        let owner = if rrset.name.is_empty() {
+            match rrset.subname.as_deref().unwrap_or_default() {
+                "" | "@" => origin.clone(),
+                subname => fully_qualify(subname, &origin),
+            }
+        } else {
+            rrset.name.clone()
+        };
+        let owner = parse_name(&owner)?;
+        let ttl = u32::try_from(rrset.ttl)
+            .map_err(|_| Error::InvalidInput(format!("TTL {} is out of range", rrset.ttl)))?;
+        let record_type = rrset.record_type();
+        rrset
+            .records
+            .iter()
+            .map(|record| {
+                let record = qualify_target(rrset.rrset_type.as_str(), record, &origin);
+                let rdata = to_rdata(&record_type, &record)?;
+                Ok(Record::from_rdata(owner.clone(), ttl, rdata))
+            })
+            .collect()
+    }
+}
+
+/// Converts hickory-proto records of the same name and type into an RRset.
+///
+/// Only `name`, `rrset_type`, `ttl` and `records` are set, as the zone of the records is unknown.
+/// Use [`ResourceRecordSet::from_hickory`] to set `domain` and `subname` as well.
+/// The TTL is taken from the first record.
+impl TryFrom<&[Record]> for ResourceRecordSet {
+    type Error = Error;
+
+    fn try_from(records: &[Record]) -> Result<Self, Self::Error> {
+        let first = records.first().ok_or_else(|| {
+            Error::InvalidInput("cannot convert an empty list of records".to_string())
+        })?;
+        if let Some(other) = records.iter().find(|record| {
+            record.name() != first.name() || record.record_type() != first.record_type()
+        }) {
+            return Err(Error::InvalidInput(format!(
+                "records of {} {} and {} {} do not form an RRset",
+                first.name(),
+                first.record_type(),
+                other.name(),
+                other.record_type()
+            )));
+        }
+        Ok(ResourceRecordSet {
+            name: first.name().to_ascii(),
+            rrset_type: RecordType::from(first.record_type().to_string()),
+            ttl: u64::from(first.ttl()),
+            records: records.iter().map(from_rdata).collect::<Result<_, _>>()?,
+            ..Default::default()
+        })
+    }
+}
+
+impl ResourceRecordSet {
+    /// Converts hickory-proto records of the same name and type into an RRset of the given domain.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if the records are of an unsupported type,
+    /// differ in name or type or are not within the domain.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn from_hickory(domain: &str, records: &[Record]) -> Result<Self, Error> {
+        let mut rrset = ResourceRecordSet::try_from(records)?;
+        let domain = domain.trim_end_matches('.');
+        let name = rrset.name.trim_end_matches('.');
+        let subname = if name.eq_ignore_ascii_case(domain) {
+            ""
+        } else {
+            name.len()
+                .checked_sub(domain.len() + 1)
+                .filter(|&end| {
+                    name.is_char_boundary(end)
+                        && name[end..].eq_ignore_ascii_case(&format!(".{domain}"))
+                })
+                .map(|end| &name[..end])
+                .ok_or_else(|| {
+                    Error::InvalidInput(format!("{} is not within the domain {domain}", rrset.name))
+                })?
+        };
+        rrset.subname = Some(subname.to_string());
+        rrset.domain = domain.to_string();
+        Ok(rrset)
+    }
+}
+
+fn parse_name(name: &str) -> Result<Name, Error> {
+    Name::from_ascii(name)
+        .map_err(|error| Error::InvalidInput(format!("invalid name {name}: {error}")))
+}
+
+// Parses a record in presentation format into the record data of the given type.
+fn to_rdata(record_type: &RecordType, record: &str) -> Result<RData, Error> {
+    let invalid = || Error::InvalidInput(format!("invalid {record_type} record: {record}"));
+    Ok(match record_type {
+        RecordType::A => RData::A(A(record
+            .trim()
+            .parse::<Ipv4Addr>()
+            .map_err(|_| invalid())?)),
+        RecordType::AAAA => RData::AAAA(AAAA(
+            record.trim().parse::<Ipv6Addr>().map_err(|_| invalid())?,
+        )),
+        RecordType::CNAME => RData::CNAME(CNAME(parse_name(record.trim())?)),
+        RecordType::NS => RData::NS(NS(parse_name(record.trim())?)),
+        RecordType::MX => match record.split_whitespace().collect::<Vec<_>>()[..] {
+            [preference, exchange] => RData::MX(MX::new(
+                preference.parse().map_err(|_| invalid())?,
+                parse_name(exchange)?,
+            )),
+            _ => return Err(invalid()),
+        },
+        RecordType::TXT => RData::TXT(TXT::new(split_fields(record)?)),
+        _ => return Err(unsupported(record_type)),
+    })
+}
+
+// Renders the record data of a record in presentation format.
+fn from_rdata(record: &Record) -> Result<String, Error> {
+    let rdata = record
+        .data()
+        .ok_or_else(|| Error::InvalidInput(format!("record of {} has no data", record.name())))?;
+    Ok(match rdata {
+        RData::A(address) => address.0.to_string(),
+        RData::AAAA(address) => address.0.to_string(),
+        RData::CNAME(target) => target.0.to_ascii(),
+        RData::NS(target) => target.0.to_ascii(),
+        RData::MX(mx) => format!("{} {}", mx.preference(), mx.exchange().to_ascii()),
+        RData::TXT(txt) => txt
+            .txt_data()
+            .iter()
+            .map(|data| {
+                let text = String::from_utf8_lossy(data);
+                format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => {
+            return Err(unsupported(&RecordType::from(
+                record.record_type().to_string().as_str(),
+            )))
+        }
+    })
+}
+
+fn unsupported(record_type: &RecordType) -> Error {
+    Error::InvalidInput(format!(
+        "conversion of {record_type} records is not supported, only A, AAAA, CNAME, MX, TXT and NS"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rrset(subname: &str, rrset_type: &str, records: &[&str]) -> ResourceRecordSet {
+        ResourceRecordSet {
+            domain: "example.com".to_string(),
+            subname: Some(subname.to_string()),
+            rrset_type: RecordType::from(rrset_type),
+            ttl: 3600,
+            records: records.iter().map(|record| record.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rrsets_are_converted_both_ways() {
+        let cases = [
+            rrset("", "A", &["192.0.2.1", "192.0.2.2"]),
+            rrset("www", "AAAA", &["2001:db8::1"]),
+            rrset("web", "CNAME", &["www.example.com."]),
+            rrset("", "MX", &["10 mail.example.com."]),
+            rrset("", "NS", &["ns1.desec.io."]),
+            rrset("", "TXT", &["\"v=spf1 -all\"", "\"two\" \"strings\""]),
+        ];
+        for rrset in cases {
+            let records = Vec::<Record>::try_from(&rrset).unwrap();
+            assert_eq!(records.len(), rrset.records.len());
+            let converted = ResourceRecordSet::from_hickory("example.com", &records).unwrap();
+            assert_eq!(converted.subname, rrset.subname);
+            assert_eq!(converted.rrset_type, rrset.rrset_type);
+            assert_eq!(converted.ttl, rrset.ttl);
+            assert_eq!(converted.records, rrset.records);
+        }
+    }
+
+    #[test]
+    fn relative_targets_are_qualified() {
+        let records = Vec::<Record>::try_from(&rrset("", "MX", &["10 mail"])).unwrap();
+        assert_eq!(records[0].name().to_ascii(), "example.com.");
+        assert_eq!(from_rdata(&records[0]).unwrap(), "10 mail.example.com.");
+    }
+
+    #[test]
+    fn unsupported_types_are_rejected() {
+        assert!(matches!(
+            Vec::<Record>::try_from(&rrset("", "SRV", &["0 5 443 example.com."])),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
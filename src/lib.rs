@@ -10,7 +10,7 @@
 //!   * Retrieve account information
 //!   * Modify account settings (only updating outreach_preference is supported by the API)
 //!   * Password reset (Request for password reset & confirmation, but handling of approval via mail needs to be handled)
-//!   * Password change
+//!   * Password change (through the password reset, deSEC has no separate endpoint to change the password)
 //!   * Change of email address
 //!   * Delete account
 //!
@@ -30,6 +30,7 @@
 //!   * Retrieving a Specific RRset
 //!   * Modifying an RRset
 //!   * Deleting an RRset
+//!   * Bulk operations when creating, modifying or deleting RRsets
 //!
 //! * Manage Tokens
 //!   * Create a token
@@ -44,11 +45,31 @@
 //!   * List all token policies
 //!   * Delete a token policy
 //!
-//! # Currently not supported
+//! # Feature flags
 //!
-//! * Pagination when over 500 items exist
-//! * Manage DNS records
-//!   * Bulk operations when modifying or deleting RRsets
+//! * `std` (default): The asynchronous HTTP client and everything depending on it, implies `core-types`
+//! * `core-types`: The data types, to be used alone with `default-features = false`. The crate is then
+//!   `no_std` (requiring `alloc`) and provides the serde models (e.g. [`Domain`][domain], [`ResourceRecordSet`][rrset],
+//!   [`Token`][token]) and the serializable [`ErrorKind`][error_kind] without pulling in reqwest or tokio.
+//! * `blocking`: Adds the [`blocking`][blocking] client for use without an async runtime, implies `std`
+//! * `hickory`: Adds conversions between [`ResourceRecordSet`][rrset] and the records of
+//!   [`hickory_proto`][hickory_proto] for A, AAAA, CNAME, MX, TXT and NS records, implies `std`
+//! * `signing`: Adds [`ClientBuilder::request_signer`][request_signer] to sign requests with an HMAC for signing gateways
+//! * `time`: Adds helpers to parse the timestamps returned by deSEC into [`time::OffsetDateTime`][offsetdatetime], implies `std`
+//! * `zeroize`: Overwrites the token of [`Token`][token] and [`Login`][login] with zeros when they are dropped, as well as
+//!   the copies of tokens and credentials made while building a client or parsing a response. Secrets handed over to the
+//!   http client, i.e. request bodies and the `Authorization` header, are out of reach.
+//!
+//! [domain]: domain/struct.Domain.html
+//! [rrset]: rrset/struct.ResourceRecordSet.html
+//! [token]: token/struct.Token.html
+//! [login]: account/struct.Login.html
+//! [error_kind]: enum.ErrorKind.html
+//! [hickory_proto]: https://docs.rs/hickory-proto/0.24/hickory_proto/rr/struct.Record.html
+//! [request_signer]: struct.ClientBuilder.html#method.request_signer
+//! [blocking]: blocking/index.html
+//!
+//! [offsetdatetime]: https://docs.rs/time/latest/time/struct.OffsetDateTime.html
 //!
 //! # General errors for all clients
 //!
@@ -57,19 +78,29 @@
 //! This method fails with:
 //! - [`Error::Reqwest`][error] if there was a problem in the underlying http client
 //! - [`Error::Unauthorized`][error] if the token of the client is invalid
-//! - [`Error::Forbidden`][error] if you are not allow to access a resource
+//! - [`Error::Forbidden`][error] if you are not allow to access a resource (includes the method and path of the request)
 //! - [`Error::RateLimitedMaxRetriesReached`][error] if a request has been throttled too many times
 //! - [`Error::ApiError`][error] if the deSEC response cannot be transformed in the expected type
-//! - [`Error::NotFound`][error] if the resource does not exist
+//! - [`Error::NotFound`][error] if the resource does not exist (includes the method and path of the request)
 //! - [`Error::InvalidAPIResponse`][error] if the response cannot be parsed into desec_api::rrset::ResourceRecordSet
 //! - [`Error::UnexpectedStatusCode`][error] if the API responds with an undocumented status code
+//! - [`Error::AmbiguousWrite`][error] if a non-idempotent request (POST, PATCH) timed out
+//! - [`Error::AsyncOperationTimeout`][error] if polling of asynchronous operations is enabled and an operation does not complete
+//! - [`Error::RetryBudgetExhausted`][error] if retrying a request would exceed the configured total retry duration
+//!
+//! Requests with idempotent methods (GET, PUT, DELETE) which time out are retried like throttled
+//! requests, while POST and PATCH requests are never repeated to avoid e.g. duplicate creations.
 //!
 //! # Usage example
 //!
 //! ## With existing API token
 //! ```no_run
+//!# #[cfg(feature = "std")]
 //!use desec_api::Client;
 //!
+//!# #[cfg(not(feature = "std"))]
+//!# fn main() {}
+//!# #[cfg(feature = "std")]
 //!#[tokio::main]
 //!async fn main() {
 //!
@@ -89,8 +120,12 @@
 //!
 //! ## With login credentials
 //! ```no_run
+//!# #[cfg(feature = "std")]
 //!use desec_api::Client;
 //!
+//!# #[cfg(not(feature = "std"))]
+//!# fn main() {}
+//!# #[cfg(feature = "std")]
 //!#[tokio::main]
 //!async fn main() {
 //!
@@ -111,18 +146,101 @@
 //!
 //! [error]: enum.Error.html
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+use cache::RrsetCache;
 use const_format::concatcp;
+#[cfg(feature = "std")]
 use log::debug;
+#[cfg(feature = "std")]
+use rate_limit::{RateLimitInfo, RateLimitStatus};
+#[cfg(feature = "std")]
 use reqwest::{header, Response, StatusCode};
+#[cfg(feature = "std")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "core-types")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use serde_json::{Map, Value};
+#[cfg(feature = "std")]
+use single_flight::SingleFlight;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+#[cfg(feature = "std")]
 use thiserror::Error;
-use tokio::time::{sleep, Duration};
+#[cfg(feature = "std")]
+use tokio::sync::Semaphore;
+#[cfg(feature = "std")]
+use tokio::time::{sleep, Duration, Instant};
 
+#[cfg(all(feature = "core-types", not(feature = "std")))]
+use alloc_prelude::*;
+
+#[cfg(feature = "core-types")]
 pub mod account;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "std")]
+pub mod diagnostics;
+#[cfg(feature = "core-types")]
 pub mod domain;
+#[cfg(feature = "core-types")]
+pub mod pagination;
+#[cfg(feature = "core-types")]
+pub mod prelude;
+#[cfg(feature = "core-types")]
+pub mod rate_limit;
+#[cfg(feature = "std")]
+pub mod records;
+#[cfg(feature = "core-types")]
 pub mod rrset;
+#[cfg(feature = "signing")]
+pub mod signing;
+#[cfg(feature = "core-types")]
 pub mod token;
 
-pub const API_URL: &str = "https://desec.io/api/v1";
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(feature = "hickory")]
+mod hickory;
+#[cfg(feature = "std")]
+mod single_flight;
+#[cfg(feature = "time")]
+mod timestamp;
+#[cfg(feature = "std")]
+mod zonefile;
+
+// Items of the std prelude which have to be imported from alloc in no_std builds
+#[cfg(all(feature = "core-types", not(feature = "std")))]
+mod alloc_prelude {
+    pub(crate) use alloc::format;
+    pub(crate) use alloc::string::{String, ToString};
+    pub(crate) use alloc::vec::Vec;
+}
+
+/// Root of the deSEC API without the version component.
+pub const API_ROOT: &str = "https://desec.io/api";
+
+/// Version of the deSEC API used by default.
+pub const API_VERSION: &str = "v1";
+
+pub const API_URL: &str = concatcp!(API_ROOT, "/", API_VERSION);
+
+/// Maximum number of concurrent requests of a client used by default.
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 64;
+
+/// Maximum size in bytes of the body of a bulk request used by default.
+pub const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Maximum number of polls while awaiting an asynchronous operation.
+pub const MAX_ASYNC_POLLS: usize = 30;
 
 // Build useragent at compile time
 pub const USERAGENT: &str = concatcp!(
@@ -131,22 +249,23 @@ pub const USERAGENT: &str = concatcp!(
     " (unoffical deSEC API client written in Rust)"
 );
 
+#[cfg(feature = "std")]
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("An error occurred during the request")]
     Reqwest(reqwest::Error),
-    #[error("You hit a rate limit and need to wait {0} seconds. Additional Info: {1}")]
-    RateLimited(u64, String),
+    #[error("You hit a rate limit and need to wait {} seconds. Additional Info: {}", .0.retry_after.as_secs(), .0.detail)]
+    RateLimited(RateLimitInfo),
     #[error("You hit a rate limit and need to wait. Additional Info: {0}")]
     RateLimitedWithoutRetry(String),
     #[error("The maximum count of retries has been reached")]
     RateLimitedMaxRetriesReached,
-    #[error("The requested resource does not exist or you are not the owner")]
-    NotFound,
-    #[error("The given credentials are not valid")]
-    Forbidden,
+    #[error("404 Not Found: {method} {path}")]
+    NotFound { method: String, path: String },
+    #[error("403 Forbidden: {method} {path}")]
+    Forbidden { method: String, path: String },
     #[error("API returned status code {0} with message '{1}'")]
-    ApiError(u16, String),
+    ApiError(u16, ApiErrorBody),
     #[error("API returned undocumented status code {0} with message '{1}'")]
     UnexpectedStatusCode(u16, String),
     #[error("API returned an invalid response. error: {0}, body: {1}")]
@@ -159,11 +278,204 @@ pub enum Error {
     Unauthorized(String),
     #[error("Client has not been logged in, so you cannot logout")]
     CannotLogout,
+    #[error("The {0} request timed out and may or may not have been applied by the API")]
+    AmbiguousWrite(String, reqwest::Error),
+    #[error("The authenticating token lacks a required permission: {0}")]
+    MissingPermission(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Asynchronous operation did not complete after {0} polls of {1}")]
+    AsyncOperationTimeout(usize, String),
+    #[error("Retrying would exceed the total retry duration of {0:?}")]
+    RetryBudgetExhausted(Duration),
+}
+
+/// Serializable projection of an [`Error`][error], e.g. to persist failed operations for a later retry.
+///
+/// Every variant of [`Error`][error] has a counterpart carrying the same status codes, messages
+/// and request context. Only the wrapped [`reqwest::Error`][reqwest] of [`Error::Reqwest`][error] and
+/// [`Error::AmbiguousWrite`][error] cannot be serialized, it is reduced to its message.
+///
+/// [error]: enum.Error.html
+/// [reqwest]: https://docs.rs/reqwest/latest/reqwest/struct.Error.html
+#[cfg(feature = "core-types")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ErrorKind {
+    Reqwest { message: String },
+    RateLimited { wait_seconds: u64, message: String },
+    RateLimitedWithoutRetry { message: String },
+    RateLimitedMaxRetriesReached,
+    NotFound { method: String, path: String },
+    Forbidden { method: String, path: String },
+    ApiError { status: u16, message: String },
+    UnexpectedStatusCode { status: u16, message: String },
+    InvalidAPIResponse { error: String, body: String },
+    Serialize { message: String },
+    ReqwestClientBuilder { message: String },
+    Unauthorized { message: String },
+    CannotLogout,
+    AmbiguousWrite { method: String, message: String },
+    MissingPermission { message: String },
+    InvalidInput { message: String },
+    AsyncOperationTimeout { polls: usize, location: String },
+    RetryBudgetExhausted { budget_millis: u64 },
+}
+
+#[cfg(feature = "std")]
+impl Error {
+    /// Returns the serializable [`ErrorKind`][kind] of this error.
+    ///
+    /// [kind]: enum.ErrorKind.html
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Reqwest(error) => ErrorKind::Reqwest {
+                message: error.to_string(),
+            },
+            Error::RateLimited(info) => ErrorKind::RateLimited {
+                wait_seconds: info.retry_after.as_secs(),
+                message: info.detail.clone(),
+            },
+            Error::RateLimitedWithoutRetry(message) => ErrorKind::RateLimitedWithoutRetry {
+                message: message.clone(),
+            },
+            Error::RateLimitedMaxRetriesReached => ErrorKind::RateLimitedMaxRetriesReached,
+            Error::NotFound { method, path } => ErrorKind::NotFound {
+                method: method.clone(),
+                path: path.clone(),
+            },
+            Error::Forbidden { method, path } => ErrorKind::Forbidden {
+                method: method.clone(),
+                path: path.clone(),
+            },
+            Error::ApiError(status, body) => ErrorKind::ApiError {
+                status: *status,
+                message: body.raw.clone(),
+            },
+            Error::UnexpectedStatusCode(status, message) => ErrorKind::UnexpectedStatusCode {
+                status: *status,
+                message: message.clone(),
+            },
+            Error::InvalidAPIResponse(error, body) => ErrorKind::InvalidAPIResponse {
+                error: error.clone(),
+                body: body.clone(),
+            },
+            Error::Serialize(message) => ErrorKind::Serialize {
+                message: message.clone(),
+            },
+            Error::ReqwestClientBuilder(message) => ErrorKind::ReqwestClientBuilder {
+                message: message.clone(),
+            },
+            Error::Unauthorized(message) => ErrorKind::Unauthorized {
+                message: message.clone(),
+            },
+            Error::CannotLogout => ErrorKind::CannotLogout,
+            Error::AmbiguousWrite(method, error) => ErrorKind::AmbiguousWrite {
+                method: method.clone(),
+                message: error.to_string(),
+            },
+            Error::MissingPermission(message) => ErrorKind::MissingPermission {
+                message: message.clone(),
+            },
+            Error::InvalidInput(message) => ErrorKind::InvalidInput {
+                message: message.clone(),
+            },
+            Error::AsyncOperationTimeout(polls, location) => ErrorKind::AsyncOperationTimeout {
+                polls: *polls,
+                location: location.clone(),
+            },
+            Error::RetryBudgetExhausted(budget) => ErrorKind::RetryBudgetExhausted {
+                budget_millis: budget.as_millis() as u64,
+            },
+        }
+    }
+}
+
+/// Body of an error response of the API, e.g. of a request failing validation.
+///
+/// deSEC reports errors as JSON objects with a `detail` message and/or a list of messages per
+/// invalid field, e.g. `{"name": ["This domain name is unavailable."]}`. For bulk requests, the
+/// errors of the items are reported in a list, their field errors are keyed by the index of the
+/// item, e.g. `0.records`. Bodies which are no such JSON object keep only the raw text.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiErrorBody {
+    /// General error message
+    pub detail: Option<String>,
+    /// Error messages per field, including `non_field_errors`
+    pub field_errors: HashMap<String, Vec<String>>,
+    /// Raw text of the body
+    pub raw: String,
+}
+
+#[cfg(feature = "std")]
+impl ApiErrorBody {
+    /// Parses the body of an error response, falling back to the raw text only.
+    pub fn parse(raw: String) -> Self {
+        let mut body = ApiErrorBody::default();
+        match serde_json::from_str::<Value>(&raw) {
+            Ok(Value::Object(object)) => body.collect(None, object),
+            Ok(Value::Array(items)) => {
+                for (index, item) in items.into_iter().enumerate() {
+                    if let Value::Object(object) = item {
+                        body.collect(Some(index), object);
+                    }
+                }
+            }
+            _ => {}
+        }
+        body.raw = raw;
+        body
+    }
+
+    /// Returns the error messages of the given field, empty if the field is valid.
+    pub fn field(&self, field: &str) -> &[String] {
+        self.field_errors.get(field).map_or(&[], Vec::as_slice)
+    }
+
+    fn collect(&mut self, index: Option<usize>, object: Map<String, Value>) {
+        for (field, value) in object {
+            let messages = match value {
+                Value::String(message) if field == "detail" && index.is_none() => {
+                    self.detail = Some(message);
+                    continue;
+                }
+                Value::String(message) => vec![message],
+                Value::Array(values) => values
+                    .into_iter()
+                    .map(|value| match value {
+                        Value::String(message) => message,
+                        other => other.to_string(),
+                    })
+                    .collect(),
+                other => vec![other.to_string()],
+            };
+            let field = match index {
+                Some(index) => format!("{index}.{field}"),
+                None => field,
+            };
+            self.field_errors.entry(field).or_default().extend(messages);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for ApiErrorBody {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.raw)
+    }
 }
 
+/// Asynchronous client of the deSEC API.
+///
+/// The `Debug` output never reveals credentials: the `Authorization` header and the values of
+/// extra headers are marked as sensitive and the key of a request signer is redacted.
+#[cfg(feature = "std")]
 #[derive(Debug, Clone)]
 pub struct Client {
     client: reqwest::Client,
+    /// Authorization header added to every request, marked as sensitive to keep it out of debug output
+    authorization: Option<header::HeaderValue>,
     /// Wheter to retry throttled requests based on the retry header
     retry: bool,
     /// Maximum waiting time to accept on a single retry
@@ -172,30 +484,113 @@ pub struct Client {
     max_retries: usize,
     /// Whether this client has been logged in before
     logged_in: bool,
+    /// Base URL all endpoints are relative to
+    base_url: String,
+    /// Whether the authenticating token may manage tokens, if known
+    perm_manage_tokens: Option<bool>,
+    /// Whether request bodies are serialized with sorted object keys
+    canonical_json: bool,
+    /// How long a retrieved RRset is served from the cache, caching is disabled if None
+    rrset_cache_ttl: Option<Duration>,
+    /// RRsets retrieved by get_rrset, shared across clones
+    rrset_cache: RrsetCache,
+    /// Maximum number of concurrent requests
+    max_concurrent_requests: usize,
+    /// Permits for requests in flight, shared across clones
+    in_flight: Arc<Semaphore>,
+    /// Whether 202 Accepted responses with a Location header are polled until completion
+    poll_async: bool,
+    /// Maximum size in bytes of the body of a single bulk request
+    max_body_bytes: usize,
+    /// Rate limit quota announced by the latest response, shared across clones
+    last_rate_limit: Arc<Mutex<Option<RateLimitStatus>>>,
+    /// Signs every request with an HMAC if set
+    #[cfg(feature = "signing")]
+    signer: Option<signing::RequestSigner>,
+    /// Whether identical concurrent GET requests share one request
+    deduplicate_gets: bool,
+    /// GET requests in flight, shared across clones
+    single_flight: SingleFlight,
+    /// Policy for following redirects, as configured on the underlying http client
+    redirect_policy: RedirectPolicy,
+    /// Timeout applied to each single request, no timeout if None
+    timeout: Option<Duration>,
+    /// Whether requests failing to connect are retried
+    retry_on_connection_error: bool,
+    /// Maximum time spent on a request including all retries, unlimited if None
+    max_total_retry_duration: Option<Duration>,
+    /// Additional headers sent with every request
+    extra_headers: header::HeaderMap,
+    /// Callback invoked before each retry
+    on_retry: Option<RetryHook>,
 }
 
+/// Snapshot of the effective configuration of a [`Client`][client], e.g. for logging it at startup.
+///
+/// [client]: struct.Client.html
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientConfig {
+    /// Base URL all endpoints are relative to
+    pub base_url: String,
+    /// Whether throttled requests are retried
+    pub retry: bool,
+    /// Maximum waiting time in seconds to accept on a single retry
+    pub max_wait_retry: u64,
+    /// Maximum number of retries
+    pub max_retries: usize,
+    /// Maximum number of concurrent requests
+    pub max_concurrent_requests: usize,
+    /// Whether request bodies are serialized with sorted object keys
+    pub canonical_json: bool,
+    /// How long a retrieved RRset is cached, `None` if caching is disabled
+    pub rrset_cache_ttl: Option<Duration>,
+    /// Whether 202 Accepted responses are polled until completion
+    pub poll_async: bool,
+    /// Maximum size in bytes of the body of a single bulk request
+    pub max_body_bytes: usize,
+    /// Whether identical concurrent GET requests share one request
+    pub deduplicate_gets: bool,
+    /// Policy for following redirects
+    pub redirect_policy: RedirectPolicy,
+    /// Timeout of each single request, `None` if requests never time out
+    pub timeout: Option<Duration>,
+    /// Whether requests failing to connect are retried
+    pub retry_on_connection_error: bool,
+    /// Maximum time spent on a request including all retries, `None` if unlimited
+    pub max_total_retry_duration: Option<Duration>,
+    /// Whether the client has been logged in with credentials
+    pub logged_in: bool,
+    /// Whether the authenticating token may manage tokens, if known
+    pub perm_manage_tokens: Option<bool>,
+    /// Whether requests are signed with an HMAC
+    #[cfg(feature = "signing")]
+    pub request_signing: bool,
+}
+
+#[cfg(feature = "std")]
 impl Client {
-    fn get_client(token: Option<String>, logged_in: Option<bool>) -> Result<Self, Error> {
-        let mut client = reqwest::ClientBuilder::new().user_agent(USERAGENT);
+    fn get_client(
+        token: Option<String>,
+        logged_in: Option<bool>,
+        base_url: Option<&str>,
+    ) -> Result<Self, Error> {
+        let mut builder = ClientBuilder::new();
         if let Some(token) = token {
-            let mut headers = header::HeaderMap::new();
-            headers.insert(
-                "Authorization",
-                header::HeaderValue::from_str(format!("Token {}", token.as_str()).as_str())
-                    .unwrap(),
-            );
-            client = client.default_headers(headers);
+            builder = builder.token(token);
         }
-        let client = client
-            .build()
-            .map_err(|error| Error::ReqwestClientBuilder(error.to_string()))?;
-        Ok(Client {
-            client,
-            retry: true,
-            max_wait_retry: 60,
-            max_retries: 3,
-            logged_in: logged_in.unwrap_or_default(),
-        })
+        if let Some(base_url) = base_url {
+            builder = builder.base_url(base_url);
+        }
+        builder.logged_in = logged_in.unwrap_or_default();
+        builder.build()
+    }
+
+    /// Returns a [`ClientBuilder`][builder] to configure a new client.
+    ///
+    /// [builder]: struct.ClientBuilder.html
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
     }
 
     /// Creates a new client using the given API token.
@@ -207,12 +602,46 @@ impl Client {
     /// [error]: enum.Error.html
     /// [builder]: https://docs.rs/reqwest/latest/reqwest/struct.ClientBuilder.html#method.build
     pub fn new(token: String) -> Result<Self, Error> {
-        let mut headers = header::HeaderMap::new();
-        headers.insert(
-            "Authorization",
-            header::HeaderValue::from_str(format!("Token {}", token.as_str()).as_str()).unwrap(),
-        );
-        Client::get_client(Some(token), None)
+        Client::get_client(Some(token), None, None)
+    }
+
+    /// Creates a new client using the given API token which sends its requests with the given http client,
+    /// e.g. to share a connection pool or a custom TLS configuration with the rest of an application.
+    ///
+    /// The `Authorization` header is added to each request, so the http client must not be
+    /// configured with it. Options of the [`ClientBuilder`][client_builder] which configure the
+    /// http client (proxy, verbose connection logging, redirect policy and user agent) do not apply,
+    /// use [`ClientBuilder::reqwest_client`][reqwest_client] to combine the other options with
+    /// an existing http client.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if the token is not a valid header value.
+    ///
+    /// [error]: enum.Error.html
+    /// [client_builder]: struct.ClientBuilder.html
+    /// [reqwest_client]: struct.ClientBuilder.html#method.reqwest_client
+    pub fn from_reqwest(client: reqwest::Client, token: String) -> Result<Self, Error> {
+        ClientBuilder::new()
+            .token(token)
+            .reqwest_client(client)
+            .build()
+    }
+
+    /// Creates a new client using the given API token, sending all requests to the given base URL,
+    /// e.g. of a self-hosted deSEC instance or a staging server.
+    ///
+    /// The base URL includes the API version, e.g. `https://desec.example/api/v1`,
+    /// a trailing slash is removed.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::ReqwestClientBuilder`][error] if the underlying [`reqwest::ClientBuilder`][builder] fails to build a http client.
+    ///
+    /// [error]: enum.Error.html
+    /// [builder]: https://docs.rs/reqwest/latest/reqwest/struct.ClientBuilder.html#method.build
+    pub fn new_with_url(token: String, base_url: &str) -> Result<Self, Error> {
+        Client::get_client(Some(token), None, Some(base_url))
     }
 
     /// Creates a new client using the given credentials.
@@ -224,11 +653,15 @@ impl Client {
     /// [error]: enum.Error.html
     /// [builder]: https://docs.rs/reqwest/latest/reqwest/struct.ClientBuilder.html#method.build
     pub async fn new_from_credentials(email: &str, password: &str) -> Result<Self, Error> {
-        let login = account::login(email, password).await?;
-        Client::get_client(Some(login.token), Some(true))
+        let mut login = account::login(email, password).await?;
+        let mut builder = ClientBuilder::new().token(core::mem::take(&mut login.token));
+        builder.logged_in = true;
+        builder.perm_manage_tokens = Some(login.perm_manage_tokens);
+        builder.build()
     }
 
-    /// Creates a new unauthenticated client for (captcha, register, login, e.g.).
+    /// Creates a new unauthenticated client, e.g. to [obtain a captcha][get_captcha], [register][register]
+    /// and [log in][login] sharing one connection pool.
     ///
     /// # Errors
     ///
@@ -236,8 +669,11 @@ impl Client {
     ///
     /// [error]: enum.Error.html
     /// [builder]: https://docs.rs/reqwest/latest/reqwest/struct.ClientBuilder.html#method.build
-    fn new_unauth() -> Result<Self, Error> {
-        Client::get_client(None, None)
+    /// [get_captcha]: #method.get_captcha
+    /// [register]: #method.register
+    /// [login]: #method.login
+    pub fn unauthenticated() -> Result<Self, Error> {
+        Client::get_client(None, None, None)
     }
 
     /// Consume and logout the authenticated client.
@@ -268,6 +704,36 @@ impl Client {
         }
     }
 
+    /// Shuts the client down gracefully.
+    ///
+    /// Waits until all requests in flight (of this client and its clones) have received the
+    /// headers of their response. Response bodies which are still being read at that time are
+    /// not waited for, so consume them before shutting down. Afterwards the client is logged out if it has been created from credentials.
+    /// For clients created from a token there is no session to log out, so unlike
+    /// [`logout`][logout] this does not fail with [`Error::CannotLogout`][error].
+    ///
+    /// # Errors
+    ///
+    /// This method fails with:
+    /// - [`Error::UnexpectedStatusCode`][error] if the API responds with an undocumented status code
+    /// - [`Error::Reqwest`][error] if the logout request failed
+    ///
+    /// [error]: enum.Error.html
+    /// [logout]: #method.logout
+    pub async fn shutdown(self) -> Result<(), Error> {
+        let permits = self
+            .in_flight
+            .acquire_many(self.max_concurrent_requests as u32)
+            .await
+            .expect("the semaphore is never closed");
+        drop(permits);
+        debug!("All requests in flight have completed");
+        if !self.logged_in {
+            return Ok(());
+        }
+        self.logout().await
+    }
+
     /// Sets whether retries are enabled.
     pub fn set_retry(&mut self, retry: bool) {
         self.retry = retry;
@@ -298,19 +764,391 @@ impl Client {
         &self.max_retries
     }
 
+    /// Sets the timeout of each single request, `None` (the default) waits indefinitely.
+    ///
+    /// The timeout covers the whole request from connecting until the response body has been read.
+    /// A request which timed out fails with [`Error::Reqwest`][error]. Requests with idempotent
+    /// methods are retried before, while POST and PATCH requests fail immediately with
+    /// [`Error::AmbiguousWrite`][error], as the API might have applied them.
+    ///
+    /// [error]: enum.Error.html
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Returns the timeout of each single request
+    pub fn get_timeout(&self) -> &Option<Duration> {
+        &self.timeout
+    }
+
+    /// Sets whether requests which fail to connect (e.g. connection refused or DNS resolution
+    /// failures) are retried, disabled by default.
+    ///
+    /// Retries count against the [maximum number of retries][max_retries] and wait with the same
+    /// exponential backoff as retries of timed out requests. As no connection was established,
+    /// such requests are retried regardless of their method. Permanent errors, e.g. an invalid URL,
+    /// are never retried.
+    ///
+    /// [max_retries]: #method.set_max_retries
+    pub fn set_retry_on_connection_error(&mut self, retry_on_connection_error: bool) {
+        self.retry_on_connection_error = retry_on_connection_error;
+    }
+
+    /// Returns whether requests which fail to connect are retried.
+    pub fn get_retry_on_connection_error(&self) -> &bool {
+        &self.retry_on_connection_error
+    }
+
+    /// Sets the maximum time to spend on a single request including all of its retries,
+    /// `None` (the default) only limits the [number of retries][max_retries].
+    ///
+    /// Before waiting for the next retry, the time elapsed since the first attempt plus the
+    /// upcoming wait is compared against this duration. If it would be exceeded, the request
+    /// fails immediately with [`Error::RetryBudgetExhausted`][error] instead of sleeping.
+    ///
+    /// [max_retries]: #method.set_max_retries
+    /// [error]: enum.Error.html
+    pub fn set_max_total_retry_duration(&mut self, max_total_retry_duration: Option<Duration>) {
+        self.max_total_retry_duration = max_total_retry_duration;
+    }
+
+    /// Returns the maximum time to spend on a single request including all of its retries.
+    pub fn get_max_total_retry_duration(&self) -> &Option<Duration> {
+        &self.max_total_retry_duration
+    }
+
+    /// Sets additional headers sent with every request, e.g. a key required by an API gateway.
+    ///
+    /// The headers are merged into each request without replacing headers set by the request
+    /// itself, e.g. `Content-Type`. The `Authorization` header of a client with a token always
+    /// takes precedence, an `Authorization` extra header is only sent by clients without a token.
+    /// All values are marked as sensitive, as they may hold credentials.
+    pub fn set_extra_headers(&mut self, mut extra_headers: header::HeaderMap) {
+        for value in extra_headers.values_mut() {
+            value.set_sensitive(true);
+        }
+        self.extra_headers = extra_headers;
+    }
+
+    /// Returns the additional headers sent with every request.
+    pub fn get_extra_headers(&self) -> &header::HeaderMap {
+        &self.extra_headers
+    }
+
+    /// Registers a callback invoked each time a request is about to be retried, e.g. to record
+    /// metrics on throttling. `None` (the default) removes a registered callback.
+    ///
+    /// The callback receives a [`RetryEvent`][event] with the number of the retry, the time
+    /// waited before it, the request and the reason. It is called right before waiting and
+    /// should return quickly, as it delays the retry otherwise.
+    ///
+    /// [event]: struct.RetryEvent.html
+    pub fn set_on_retry(&mut self, on_retry: Option<Box<dyn Fn(RetryEvent) + Send + Sync>>) {
+        self.on_retry = on_retry.map(|on_retry| RetryHook(Arc::from(on_retry)));
+    }
+
+    /// Sets whether request bodies are serialized as canonical JSON with sorted object keys.
+    pub fn set_canonical_json(&mut self, canonical_json: bool) {
+        self.canonical_json = canonical_json;
+    }
+
+    /// Returns whether request bodies are serialized as canonical JSON with sorted object keys.
+    pub fn get_canonical_json(&self) -> &bool {
+        &self.canonical_json
+    }
+
+    /// Sets whether asynchronous operations are awaited.
+    ///
+    /// If enabled and the API answers with `202 Accepted` and a `Location` header, the referenced
+    /// status resource is polled with exponential backoff (capped at the maximum waiting time of a retry)
+    /// until it no longer responds with `202 Accepted`. The final response is then returned instead.
+    /// At most [`MAX_ASYNC_POLLS`][max] polls are done before giving up with [`Error::AsyncOperationTimeout`][error].
+    /// A `Location` outside of the scheme, host and port of the base URL is not polled, as the token would be
+    /// sent along, and fails with [`Error::InvalidAPIResponse`][error] instead.
+    ///
+    /// [max]: constant.MAX_ASYNC_POLLS.html
+    /// [error]: enum.Error.html
+    pub fn set_poll_async(&mut self, poll_async: bool) {
+        self.poll_async = poll_async;
+    }
+
+    /// Returns whether asynchronous operations are awaited.
+    pub fn get_poll_async(&self) -> &bool {
+        &self.poll_async
+    }
+
+    /// Sets the maximum size in bytes of the body of a single bulk request (defaults to [`DEFAULT_MAX_BODY_BYTES`][default]).
+    ///
+    /// Zonefile imports are split into several bulk requests so that no request body exceeds this size,
+    /// unless a single RRset is larger on its own, which is then sent in a request of its own.
+    /// Bulk operations on given RRsets, e.g. [`RrsetClient::delete_rrsets`][delete_rrsets], are atomic
+    /// and therefore always sent in a single request.
+    /// Responses with a larger body are not shared between [deduplicated GET requests][deduplicate_gets].
+    ///
+    /// [default]: constant.DEFAULT_MAX_BODY_BYTES.html
+    /// [delete_rrsets]: rrset/struct.RrsetClient.html#method.delete_rrsets
+    /// [deduplicate_gets]: #method.set_deduplicate_gets
+    pub fn set_max_body_bytes(&mut self, max_body_bytes: usize) {
+        self.max_body_bytes = max_body_bytes;
+    }
+
+    /// Returns the maximum size in bytes of the body of a single bulk request.
+    pub fn get_max_body_bytes(&self) -> &usize {
+        &self.max_body_bytes
+    }
+
+    /// Sets whether identical GET requests which are in flight at the same time are deduplicated.
+    ///
+    /// If enabled, a GET request for a URL which is already being requested by this client (or one
+    /// of its clones) does not hit the API, but waits for the response of the request in flight and
+    /// receives a copy of it. If that request fails or its body exceeds the [maximum body size][max_body_bytes],
+    /// the waiting requests are sent on their own.
+    /// Only plain GET requests are deduplicated, all other methods are always sent.
+    ///
+    /// [max_body_bytes]: #method.set_max_body_bytes
+    pub fn set_deduplicate_gets(&mut self, deduplicate_gets: bool) {
+        self.deduplicate_gets = deduplicate_gets;
+    }
+
+    /// Returns whether identical GET requests in flight are deduplicated.
+    pub fn get_deduplicate_gets(&self) -> &bool {
+        &self.deduplicate_gets
+    }
+
+    /// Sets for how long RRsets retrieved by [`RrsetClient::get_rrset`][get_rrset] are cached, `None` disables caching.
+    ///
+    /// Within this duration, a cached RRset is returned without any request. Afterwards it is
+    /// revalidated with a conditional request if the API sent an `ETag`, so an unchanged RRset
+    /// does not need to be transferred again. The cache is shared by all clones of the client
+    /// and RRsets modified through this client are removed from it.
+    ///
+    /// [get_rrset]: rrset/struct.RrsetClient.html#method.get_rrset
+    pub fn set_rrset_cache_ttl(&mut self, rrset_cache_ttl: Option<Duration>) {
+        self.rrset_cache_ttl = rrset_cache_ttl;
+    }
+
+    /// Returns for how long retrieved RRsets are cached
+    pub fn get_rrset_cache_ttl(&self) -> &Option<Duration> {
+        &self.rrset_cache_ttl
+    }
+
+    /// Removes all RRsets from the cache.
+    pub fn invalidate_rrset_cache(&self) {
+        self.rrset_cache.clear();
+    }
+
+    /// Returns the base URL all requests are sent to
+    pub fn get_base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns a snapshot of the effective configuration of this client.
+    pub fn config(&self) -> ClientConfig {
+        ClientConfig {
+            base_url: self.base_url.clone(),
+            retry: self.retry,
+            max_wait_retry: self.max_wait_retry,
+            max_retries: self.max_retries,
+            max_concurrent_requests: self.max_concurrent_requests,
+            canonical_json: self.canonical_json,
+            rrset_cache_ttl: self.rrset_cache_ttl,
+            poll_async: self.poll_async,
+            max_body_bytes: self.max_body_bytes,
+            deduplicate_gets: self.deduplicate_gets,
+            redirect_policy: self.redirect_policy,
+            timeout: self.timeout,
+            retry_on_connection_error: self.retry_on_connection_error,
+            max_total_retry_duration: self.max_total_retry_duration,
+            logged_in: self.logged_in,
+            perm_manage_tokens: self.perm_manage_tokens,
+            #[cfg(feature = "signing")]
+            request_signing: self.signer.is_some(),
+        }
+    }
+
+    /// Serializes the items into JSON arrays, cutting a new array whenever adding
+    /// the next item would exceed the maximum body size.
+    pub(crate) fn serialize_bulk_bodies<T: Serialize>(
+        &self,
+        items: &[T],
+    ) -> Result<Vec<String>, Error> {
+        let mut bodies = Vec::new();
+        let mut body = String::from("[");
+        for item in items {
+            let item = self.serialize_body(item)?;
+            // Account for the separating comma and the closing bracket
+            if body.len() > 1 && body.len() + item.len() + 2 > self.max_body_bytes {
+                body.push(']');
+                bodies.push(body);
+                body = String::from("[");
+            }
+            if body.len() > 1 {
+                body.push(',');
+            }
+            body.push_str(&item);
+        }
+        if body.len() > 1 {
+            body.push(']');
+            bodies.push(body);
+        }
+        Ok(bodies)
+    }
+
+    /// Establishes a connection to the API ahead of time, so the first real request
+    /// does not have to wait for the TLS handshake.
+    ///
+    /// A `HEAD` request is sent to the base URL. Any HTTP response counts as success, as the
+    /// connection is pooled regardless of the status code. The request is never retried,
+    /// so services can call this at startup and ignore a failure, which only means
+    /// that the first real request has to connect itself.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::Reqwest`][error] if no connection could be established.
+    ///
+    /// [error]: enum.Error.html
+    pub async fn warm_up(&self) -> Result<(), Error> {
+        self.client
+            .head(format!("{}/", self.base_url))
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(Error::Reqwest)
+    }
+
+    /// Discovers the HTTP methods permitted on a resource.
+    ///
+    /// Sends an `OPTIONS` request to `path` (relative to the base URL, e.g. `/domains/`)
+    /// and returns the methods listed in the `Allow` header of the response.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if the response contains no valid `Allow` header.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: enum.Error.html
+    /// [general_errors]: index.html#general-errors-for-all-clients
+    pub async fn options(&self, path: &str) -> Result<Vec<String>, Error> {
+        let request = self
+            .client
+            .request(
+                reqwest::Method::OPTIONS,
+                format!("{}{}", self.base_url, path),
+            )
+            .build()
+            .map_err(Error::Reqwest)?;
+        let response = self.process_request(request).await?;
+        let allow = response
+            .headers()
+            .get(header::ALLOW)
+            .ok_or_else(|| {
+                Error::InvalidAPIResponse(
+                    "Response contains no Allow header".to_string(),
+                    String::new(),
+                )
+            })?
+            .to_str()
+            .map_err(|error| {
+                Error::InvalidAPIResponse(
+                    format!("Allow header is not valid: {error}"),
+                    String::new(),
+                )
+            })?;
+        Ok(parse_allow(allow))
+    }
+
+    /// Serializes a request body, using canonical JSON if enabled.
+    pub(crate) fn serialize_body<T: Serialize>(&self, body: &T) -> Result<String, Error> {
+        let result = if self.canonical_json {
+            serde_json::to_value(body).and_then(|value| serde_json::to_string(&canonicalize(value)))
+        } else {
+            serde_json::to_string(body)
+        };
+        result.map_err(|error| Error::Serialize(error.to_string()))
+    }
+
     /// Sends the request and processes the response.
-    /// If a status code 429 is encountered, depending on the configuration, retries are done.
+    /// If the API accepted the request for asynchronous processing and polling is enabled,
+    /// the status resource is polled until the operation completed.
     async fn process_request(&self, request: reqwest::Request) -> Result<Response, Error> {
+        let mut response = self.execute_with_retries(request).await?;
+        if !self.poll_async {
+            return Ok(response);
+        }
+        let mut polls: usize = 0;
+        while response.status() == StatusCode::ACCEPTED {
+            let location = match response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .and_then(|location| response.url().join(location).ok())
+            {
+                Some(location) => location,
+                None => break,
+            };
+            // The token is sent along with every poll, so never leave the origin of the API
+            if !self.is_same_origin(&location) {
+                return Err(Error::InvalidAPIResponse(
+                    format!("status resource {location} is outside of the API origin"),
+                    String::new(),
+                ));
+            }
+            if polls >= MAX_ASYNC_POLLS {
+                return Err(Error::AsyncOperationTimeout(polls, location.to_string()));
+            }
+            let ttw = transient_backoff(polls, self.max_wait_retry);
+            debug!(
+                "Operation is pending, polling {} in {} seconds",
+                location, ttw
+            );
+            sleep(Duration::from_secs(ttw)).await;
+            let request = self.client.get(location).build().map_err(Error::Reqwest)?;
+            response = self.execute_with_retries(request).await?;
+            polls += 1;
+        }
+        Ok(response)
+    }
+
+    /// Returns whether the URL has the same scheme, host and port as the base URL.
+    fn is_same_origin(&self, url: &reqwest::Url) -> bool {
+        reqwest::Url::parse(&self.base_url)
+            .map(|base_url| base_url.origin() == url.origin())
+            .unwrap_or(false)
+    }
+
+    /// Sends the request and processes the response.
+    /// If a status code 429 is encountered, depending on the configuration, retries are done.
+    async fn execute_with_retries(&self, mut request: reqwest::Request) -> Result<Response, Error> {
+        let headers = request.headers_mut();
+        for name in self.extra_headers.keys() {
+            if !headers.contains_key(name) {
+                for value in self.extra_headers.get_all(name) {
+                    headers.append(name.clone(), value.clone());
+                }
+            }
+        }
+        if let Some(authorization) = &self.authorization {
+            request
+                .headers_mut()
+                .insert(header::AUTHORIZATION, authorization.clone());
+        }
+        if self.timeout.is_some() {
+            *request.timeout_mut() = self.timeout;
+        }
+        #[cfg(feature = "signing")]
+        let request = self.sign_request(request)?;
+        let started = Instant::now();
         let mut retries: usize = 0;
         loop {
-            // We reached max retry limit, so we abort
-            if retries > self.max_retries {
-                debug!("Giving up after {} retries", self.max_retries);
-                return Err(Error::RateLimitedMaxRetriesReached);
-            }
             // Clone and execute the request.
             // Cloning should never fail because we have to streamed body or
             // other surprises.
+            let permit = self
+                .in_flight
+                .acquire()
+                .await
+                .expect("the semaphore is never closed");
             let result = self
                 .client
                 .execute(
@@ -319,15 +1157,27 @@ impl Client {
                         .expect("this request should always be clonable"),
                 )
                 .await;
+            drop(permit);
+            if let Ok(response) = &result {
+                self.record_rate_limit(response.headers());
+            }
             match result {
                 Ok(response) => match response.status() {
                     StatusCode::OK
                     | StatusCode::CREATED
                     | StatusCode::NO_CONTENT
-                    | StatusCode::ACCEPTED => return Ok(response),
+                    | StatusCode::ACCEPTED
+                    | StatusCode::NOT_MODIFIED => return Ok(response),
                     StatusCode::TOO_MANY_REQUESTS => {
                         let ttw =
                             parse_time_to_wait(response, self.max_wait_retry, self.retry).await?;
+                        // We reached max retry limit, so we abort
+                        if retries >= self.max_retries {
+                            debug!("Giving up after {} retries", self.max_retries);
+                            return Err(Error::RateLimitedMaxRetriesReached);
+                        }
+                        self.check_retry_budget(started, ttw)?;
+                        self.notify_retry(&request, retries, ttw, RetryReason::Throttled);
                         debug!("Request has been throttled, we wait {} seconds", ttw);
                         sleep(Duration::from_secs(ttw)).await;
                         retries += 1;
@@ -337,14 +1187,24 @@ impl Client {
                             response.text().await.unwrap_or_default(),
                         ))
                     }
-                    StatusCode::FORBIDDEN => return Err(Error::Forbidden),
+                    StatusCode::FORBIDDEN => {
+                        return Err(Error::Forbidden {
+                            method: request.method().to_string(),
+                            path: self.relative_path(request.url()),
+                        })
+                    }
                     StatusCode::BAD_REQUEST => {
                         return Err(Error::ApiError(
                             response.status().as_u16(),
-                            response.text().await.unwrap_or_default(),
+                            ApiErrorBody::parse(response.text().await.unwrap_or_default()),
                         ))
                     }
-                    StatusCode::NOT_FOUND => return Err(Error::NotFound),
+                    StatusCode::NOT_FOUND => {
+                        return Err(Error::NotFound {
+                            method: request.method().to_string(),
+                            path: self.relative_path(request.url()),
+                        })
+                    }
                     _ => {
                         return Err(Error::UnexpectedStatusCode(
                             response.status().into(),
@@ -352,17 +1212,131 @@ impl Client {
                         ))
                     }
                 },
-                // Maybe retry on reqwest errors too?
+                // Timed out requests are only retried if the method is idempotent,
+                // otherwise we cannot know whether the server applied the request.
+                Err(error) if error.is_timeout() => {
+                    if !is_idempotent(request.method()) {
+                        debug!(
+                            "{} request timed out, not retrying as it is not idempotent",
+                            request.method()
+                        );
+                        return Err(Error::AmbiguousWrite(request.method().to_string(), error));
+                    }
+                    if !self.retry || retries >= self.max_retries {
+                        return Err(Error::Reqwest(error));
+                    }
+                    let ttw = transient_backoff(retries, self.max_wait_retry);
+                    self.check_retry_budget(started, ttw)?;
+                    self.notify_retry(&request, retries, ttw, RetryReason::Timeout);
+                    debug!("Request timed out, we wait {} seconds", ttw);
+                    sleep(Duration::from_secs(ttw)).await;
+                    retries += 1;
+                }
+                // Without a connection the server cannot have seen the request,
+                // so it is safe to retry regardless of the method.
+                Err(error) if error.is_connect() && self.retry_on_connection_error => {
+                    if retries >= self.max_retries {
+                        return Err(Error::Reqwest(error));
+                    }
+                    let ttw = transient_backoff(retries, self.max_wait_retry);
+                    self.check_retry_budget(started, ttw)?;
+                    self.notify_retry(&request, retries, ttw, RetryReason::ConnectionError);
+                    debug!("Request failed to connect, we wait {} seconds", ttw);
+                    sleep(Duration::from_secs(ttw)).await;
+                    retries += 1;
+                }
                 Err(error) => return Err(Error::Reqwest(error)),
             }
         }
     }
 
+    /// Fails if waiting `ttw` seconds for the next retry would exceed the total retry duration.
+    fn check_retry_budget(&self, started: Instant, ttw: u64) -> Result<(), Error> {
+        match self.max_total_retry_duration {
+            Some(budget) if started.elapsed() + Duration::from_secs(ttw) > budget => {
+                debug!(
+                    "Giving up as the total retry duration of {:?} would be exceeded",
+                    budget
+                );
+                Err(Error::RetryBudgetExhausted(budget))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Invokes the retry callback, if any, before the retry following `retries` previous ones.
+    fn notify_retry(
+        &self,
+        request: &reqwest::Request,
+        retries: usize,
+        ttw: u64,
+        reason: RetryReason,
+    ) {
+        if let Some(on_retry) = &self.on_retry {
+            (on_retry.0)(RetryEvent {
+                attempt: retries + 1,
+                wait: Duration::from_secs(ttw),
+                method: request.method().to_string(),
+                endpoint: self.relative_path(request.url()),
+                reason,
+            });
+        }
+    }
+
+    /// Adds the signature header to the request if a signer is configured.
+    #[cfg(feature = "signing")]
+    fn sign_request(&self, mut request: reqwest::Request) -> Result<reqwest::Request, Error> {
+        if let Some(signer) = &self.signer {
+            let path = match request.url().query() {
+                Some(query) => format!("{}?{}", request.url().path(), query),
+                None => request.url().path().to_string(),
+            };
+            let body = request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .unwrap_or_default();
+            let signature = signer.sign(request.method().as_str(), &path, body);
+            let name =
+                header::HeaderName::from_bytes(signer.header.as_bytes()).map_err(|error| {
+                    Error::InvalidInput(format!("invalid signature header: {error}"))
+                })?;
+            let value = header::HeaderValue::from_str(&signature)
+                .expect("a hex encoded signature is a valid header value");
+            request.headers_mut().insert(name, value);
+        }
+        Ok(request)
+    }
+
+    /// Returns the path of the URL relative to the base URL, or the full path for foreign URLs.
+    fn relative_path(&self, url: &reqwest::Url) -> String {
+        match url.as_str().strip_prefix(self.base_url.as_str()) {
+            Some(path) => path.to_string(),
+            None => url.path().to_string(),
+        }
+    }
+
     /// Process get requests
     async fn get(&self, endpoint: &str) -> Result<Response, Error> {
+        let url = format!("{}{}", self.base_url, endpoint);
+        if self.deduplicate_gets {
+            self.get_single_flight(url).await
+        } else {
+            self.send_get(&url).await
+        }
+    }
+
+    /// Sends a get request to the given URL
+    async fn send_get(&self, url: &str) -> Result<Response, Error> {
+        let request = self.client.get(url).build().map_err(Error::Reqwest)?;
+        self.process_request(request).await
+    }
+
+    /// Process conditional get requests, the API responds with 304 if the resource still matches the ETag
+    async fn get_if_none_match(&self, endpoint: &str, etag: &str) -> Result<Response, Error> {
         let request = self
             .client
-            .get(format!("{}{}", API_URL, endpoint))
+            .get(format!("{}{}", self.base_url, endpoint))
+            .header(header::IF_NONE_MATCH, etag)
             .build()
             .map_err(Error::Reqwest)?;
         self.process_request(request).await
@@ -372,7 +1346,7 @@ impl Client {
     async fn post(&self, endpoint: &str, body: Option<String>) -> Result<Response, Error> {
         let request = self
             .client
-            .post(format!("{}{}", API_URL, endpoint).as_str())
+            .post(format!("{}{}", self.base_url, endpoint).as_str())
             .header("Content-Type", "application/json")
             .body(body.unwrap_or_default()) // body is optional, so we send empty string when None
             .build()
@@ -384,7 +1358,19 @@ impl Client {
     async fn patch(&self, endpoint: &str, body: String) -> Result<Response, Error> {
         let request = self
             .client
-            .patch(format!("{}{}", API_URL, endpoint).as_str())
+            .patch(format!("{}{}", self.base_url, endpoint).as_str())
+            .header("Content-Type", "application/json")
+            .body(body)
+            .build()
+            .map_err(Error::Reqwest)?;
+        self.process_request(request).await
+    }
+
+    /// Process put requests
+    async fn put(&self, endpoint: &str, body: String) -> Result<Response, Error> {
+        let request = self
+            .client
+            .put(format!("{}{}", self.base_url, endpoint).as_str())
             .header("Content-Type", "application/json")
             .body(body)
             .build()
@@ -396,47 +1382,712 @@ impl Client {
     async fn delete(&self, endpoint: &str) -> Result<Response, Error> {
         let request = self
             .client
-            .delete(format!("{}{}", API_URL, endpoint).as_str())
+            .delete(format!("{}{}", self.base_url, endpoint).as_str())
             .build()
             .map_err(Error::Reqwest)?;
         self.process_request(request).await
     }
 }
 
-// Parsing the time we have to wait till next retry.
-// Error out if we cannot parse, retry is disabled, or accepted max wait time will be exceeded.
-async fn parse_time_to_wait(
-    response: Response,
-    max_wait_retry: u64,
-    should_retry: bool,
-) -> Result<u64, Error> {
-    let time_to_wait = match response.headers().get("retry-after") {
-        Some(header) => match header.to_str() {
-            Ok(header) => header.parse().map_err(|_| {
-                Error::RateLimitedWithoutRetry(format!(
-                    "Request was throttled and cannot parse retry after {:?}",
-                    header
-                ))
-            })?,
-            Err(_) => return Err(Error::RateLimitedWithoutRetry(
-                "Request got throttled with retry-after header containing non-visible ASCII chars"
-                    .to_string(),
-            )),
-        },
-        None => {
-            return Err(Error::RateLimitedWithoutRetry(
-                "Request got throttled without retry-after header".to_string(),
-            ))
-        }
-    };
-    // Abort if we are not interested in retries
-    if !should_retry {
-        let msg = String::from("Request has been throttled, but retries are disabled");
+/// Builder to configure and create a [`Client`][client].
+///
+/// By default the underlying http client honors the proxy environment variables
+/// (`HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY`), like [`reqwest`][reqwest] does.
+/// Use [`ClientBuilder::no_proxy`][no_proxy] to guarantee that no proxy from the environment is used,
+/// or [`ClientBuilder::proxy`][proxy] to configure a proxy explicitly.
+///
+/// [client]: struct.Client.html
+/// [reqwest]: https://docs.rs/reqwest/latest/reqwest/struct.ClientBuilder.html#method.no_proxy
+/// [no_proxy]: struct.ClientBuilder.html#method.no_proxy
+/// [proxy]: struct.ClientBuilder.html#method.proxy
+#[cfg(feature = "std")]
+#[derive(Clone, Default)]
+pub struct ClientBuilder {
+    token: Option<String>,
+    no_proxy: bool,
+    connection_verbose: bool,
+    api_version: Option<String>,
+    base_url: Option<String>,
+    retry: Option<bool>,
+    max_wait_retry: Option<u64>,
+    max_retries: Option<usize>,
+    logged_in: bool,
+    perm_manage_tokens: Option<bool>,
+    canonical_json: bool,
+    rrset_cache_ttl: Option<Duration>,
+    max_concurrent_requests: Option<usize>,
+    redirect_policy: RedirectPolicy,
+    poll_async: bool,
+    max_body_bytes: Option<usize>,
+    #[cfg(feature = "signing")]
+    signer: Option<(Vec<u8>, signing::SigningAlgorithm)>,
+    #[cfg(feature = "signing")]
+    signature_header: Option<String>,
+    deduplicate_gets: bool,
+    reqwest_client: Option<reqwest::Client>,
+    timeout: Option<Duration>,
+    retry_on_connection_error: bool,
+    max_total_retry_duration: Option<Duration>,
+    default_headers: Vec<(String, String)>,
+    on_retry: Option<RetryHook>,
+    user_agent: Option<String>,
+    /// Proxies in the order they were added, a proxy URL which failed to parse carries the error message
+    proxies: Vec<Result<reqwest::Proxy, String>>,
+}
+
+#[cfg(feature = "std")]
+impl ClientBuilder {
+    /// Creates a new builder using the default configuration.
+    pub fn new() -> Self {
+        ClientBuilder::default()
+    }
+
+    /// Sets the API token used to authenticate requests.
+    pub fn token(mut self, token: String) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Disables the detection of proxies from the environment.
+    ///
+    /// Without this, a proxy configured via `HTTP_PROXY`/`HTTPS_PROXY` is used
+    /// and would see every request including the API token.
+    pub fn no_proxy(mut self) -> Self {
+        self.no_proxy = true;
+        self
+    }
+
+    /// Sends requests through the given proxy, e.g. `reqwest::Proxy::https("http://proxy.example:3128")`.
+    ///
+    /// Several proxies can be added with this method or [`proxy_url`][proxy_url] and are tried
+    /// in the order they were added. Once a proxy is configured explicitly, proxies from the
+    /// environment are not used anymore.
+    ///
+    /// [proxy_url]: #method.proxy_url
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxies.push(Ok(proxy));
+        self
+    }
+
+    /// Sends all requests through the proxy with the given URL, e.g. `http://proxy.example:3128`,
+    /// see [`proxy`][proxy]. An invalid URL makes [`build`][build] fail.
+    ///
+    /// [proxy]: #method.proxy
+    /// [build]: #method.build
+    pub fn proxy_url(mut self, url: &str) -> Self {
+        self.proxies.push(
+            reqwest::Proxy::all(url).map_err(|error| format!("invalid proxy URL {url}: {error}")),
+        );
+        self
+    }
+
+    /// Enables verbose logging of the connection (e.g. TLS handshakes) via the [`log`][log] crate.
+    ///
+    /// This is noisy and therefore disabled by default, but helps to diagnose connection issues.
+    ///
+    /// [log]: https://docs.rs/log
+    pub fn connection_verbose(mut self, connection_verbose: bool) -> Self {
+        self.connection_verbose = connection_verbose;
+        self
+    }
+
+    /// Sets the version of the deSEC API to use, e.g. `v1` (the default).
+    ///
+    /// All requests are sent to `https://desec.io/api/<version>`.
+    /// The version is ignored if a [base URL][base_url] is set.
+    ///
+    /// [base_url]: #method.base_url
+    pub fn api_version(mut self, api_version: &str) -> Self {
+        self.api_version = Some(api_version.to_string());
+        self
+    }
+
+    /// Sets the base URL all requests are sent to instead of [`API_URL`][api_url],
+    /// e.g. of a self-hosted deSEC instance, a staging server or a mock server for testing.
+    ///
+    /// The base URL includes the API version, e.g. `https://desec.example/api/v1`,
+    /// a trailing slash is removed.
+    ///
+    /// [api_url]: constant.API_URL.html
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Sets whether request bodies are serialized as canonical JSON with sorted object keys.
+    ///
+    /// This makes request bodies byte-for-byte reproducible, e.g. for hashing or caching them.
+    pub fn canonical_json(mut self, canonical_json: bool) -> Self {
+        self.canonical_json = canonical_json;
+        self
+    }
+
+    /// Enables caching of RRsets retrieved by [`RrsetClient::get_rrset`][get_rrset] for the given duration.
+    ///
+    /// See [`Client::set_rrset_cache_ttl`][set_rrset_cache_ttl] for details.
+    ///
+    /// [get_rrset]: rrset/struct.RrsetClient.html#method.get_rrset
+    /// [set_rrset_cache_ttl]: struct.Client.html#method.set_rrset_cache_ttl
+    pub fn rrset_cache_ttl(mut self, rrset_cache_ttl: Duration) -> Self {
+        self.rrset_cache_ttl = Some(rrset_cache_ttl);
+        self
+    }
+
+    /// Sets whether asynchronous operations are awaited, see [`Client::set_poll_async`][set_poll_async].
+    ///
+    /// [set_poll_async]: struct.Client.html#method.set_poll_async
+    pub fn poll_async(mut self, poll_async: bool) -> Self {
+        self.poll_async = poll_async;
+        self
+    }
+
+    /// Sets the maximum size in bytes of the body of a single bulk request, see [`Client::set_max_body_bytes`][set_max_body_bytes].
+    ///
+    /// [set_max_body_bytes]: struct.Client.html#method.set_max_body_bytes
+    pub fn max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = Some(max_body_bytes);
+        self
+    }
+
+    /// Signs every request with an HMAC over its canonical form using the shared secret `key`.
+    ///
+    /// See the [signing module][signing] for the exact canonicalization, so a gateway can verify the signature.
+    ///
+    /// [signing]: signing/index.html
+    #[cfg(feature = "signing")]
+    pub fn request_signer(mut self, key: &[u8], algorithm: signing::SigningAlgorithm) -> Self {
+        self.signer = Some((key.to_vec(), algorithm));
+        self
+    }
+
+    /// Sets the name of the header carrying the request signature
+    /// (defaults to [`DEFAULT_SIGNATURE_HEADER`][default]).
+    ///
+    /// [default]: signing/constant.DEFAULT_SIGNATURE_HEADER.html
+    #[cfg(feature = "signing")]
+    pub fn signature_header(mut self, signature_header: &str) -> Self {
+        self.signature_header = Some(signature_header.to_string());
+        self
+    }
+
+    /// Sets whether identical GET requests in flight are deduplicated, see [`Client::set_deduplicate_gets`][set_deduplicate_gets].
+    ///
+    /// [set_deduplicate_gets]: struct.Client.html#method.set_deduplicate_gets
+    pub fn deduplicate_gets(mut self, deduplicate_gets: bool) -> Self {
+        self.deduplicate_gets = deduplicate_gets;
+        self
+    }
+
+    /// Sets whether throttled requests are retried (enabled by default), see [`Client::set_retry`][set_retry].
+    ///
+    /// [set_retry]: struct.Client.html#method.set_retry
+    pub fn retry(mut self, retry: bool) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Sets the maximum wait time in seconds for a single retry (defaults to 60),
+    /// see [`Client::set_max_wait_retry`][set_max_wait_retry].
+    ///
+    /// [set_max_wait_retry]: struct.Client.html#method.set_max_wait_retry
+    pub fn max_wait_retry(mut self, max_wait_retry: u64) -> Self {
+        self.max_wait_retry = Some(max_wait_retry);
+        self
+    }
+
+    /// Sets the maximum number of retries (defaults to 3), see [`Client::set_max_retries`][set_max_retries].
+    ///
+    /// [set_max_retries]: struct.Client.html#method.set_max_retries
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Sets whether requests which fail to connect are retried,
+    /// see [`Client::set_retry_on_connection_error`][set_retry_on_connection_error].
+    ///
+    /// [set_retry_on_connection_error]: struct.Client.html#method.set_retry_on_connection_error
+    pub fn retry_on_connection_error(mut self, retry_on_connection_error: bool) -> Self {
+        self.retry_on_connection_error = retry_on_connection_error;
+        self
+    }
+
+    /// Sets the maximum time to spend on a single request including all of its retries,
+    /// see [`Client::set_max_total_retry_duration`][set_max_total_retry_duration].
+    ///
+    /// [set_max_total_retry_duration]: struct.Client.html#method.set_max_total_retry_duration
+    pub fn max_total_retry_duration(mut self, max_total_retry_duration: Duration) -> Self {
+        self.max_total_retry_duration = Some(max_total_retry_duration);
+        self
+    }
+
+    /// Sets the timeout of each single request, see [`Client::set_timeout`][set_timeout].
+    ///
+    /// [set_timeout]: struct.Client.html#method.set_timeout
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Adds a header sent with every request, see [`Client::set_extra_headers`][set_extra_headers].
+    ///
+    /// Calling this method several times with the same name sends all of the values.
+    ///
+    /// [set_extra_headers]: struct.Client.html#method.set_extra_headers
+    pub fn default_header(mut self, name: &str, value: &str) -> Self {
+        self.default_headers
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Registers a callback invoked each time a request is about to be retried,
+    /// see [`Client::set_on_retry`][set_on_retry].
+    ///
+    /// [set_on_retry]: struct.Client.html#method.set_on_retry
+    pub fn on_retry<F: Fn(RetryEvent) + Send + Sync + 'static>(mut self, on_retry: F) -> Self {
+        self.on_retry = Some(RetryHook(Arc::new(on_retry)));
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request, replacing [`USERAGENT`][useragent].
+    ///
+    /// To keep identifying this crate while adding the name of your tool, append to the default,
+    /// e.g. `format!("{} my-tool/1.0", desec_api::USERAGENT)`.
+    ///
+    /// [useragent]: constant.USERAGENT.html
+    pub fn user_agent(mut self, user_agent: String) -> Self {
+        self.user_agent = Some(user_agent);
+        self
+    }
+
+    /// Sets an existing http client to send the requests with instead of building a new one.
+    ///
+    /// The options configuring the http client ([`no_proxy`][no_proxy], [`proxy`][proxy],
+    /// [`connection_verbose`][connection_verbose], [`redirect_policy`][redirect_policy] and
+    /// [`user_agent`][user_agent]) are ignored in this case, see [`Client::from_reqwest`][from_reqwest].
+    ///
+    /// [no_proxy]: #method.no_proxy
+    /// [proxy]: #method.proxy
+    /// [connection_verbose]: #method.connection_verbose
+    /// [redirect_policy]: #method.redirect_policy
+    /// [user_agent]: #method.user_agent
+    /// [from_reqwest]: struct.Client.html#method.from_reqwest
+    pub fn reqwest_client(mut self, client: reqwest::Client) -> Self {
+        self.reqwest_client = Some(client);
+        self
+    }
+
+    /// Sets the maximum number of concurrent requests (defaults to [`DEFAULT_MAX_CONCURRENT_REQUESTS`][default]).
+    ///
+    /// The limit is shared by all clones of the client, further requests wait for a free slot.
+    ///
+    /// [default]: constant.DEFAULT_MAX_CONCURRENT_REQUESTS.html
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.max_concurrent_requests = Some(max_concurrent_requests);
+        self
+    }
+
+    /// Sets how redirects are handled (defaults to [`RedirectPolicy::Follow`][policy] with at most 10 redirects).
+    ///
+    /// Regardless of the policy, the `Authorization` header is never forwarded to another host or port.
+    ///
+    /// [policy]: enum.RedirectPolicy.html
+    pub fn redirect_policy(mut self, redirect_policy: RedirectPolicy) -> Self {
+        self.redirect_policy = redirect_policy;
+        self
+    }
+
+    /// Builds the client.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with:
+    /// - [`Error::ReqwestClientBuilder`][error] if the underlying [`reqwest::ClientBuilder`][builder] fails to build a http client
+    /// - [`Error::InvalidInput`][error] if the token or a [default header][default_header] is not a valid header
+    ///
+    /// [error]: enum.Error.html
+    /// [builder]: https://docs.rs/reqwest/latest/reqwest/struct.ClientBuilder.html#method.build
+    /// [default_header]: #method.default_header
+    pub fn build(mut self) -> Result<Client, Error> {
+        let mut extra_headers = header::HeaderMap::new();
+        for (name, value) in &self.default_headers {
+            let name = header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| Error::InvalidInput(format!("{name} is not a valid header name")))?;
+            let mut value = header::HeaderValue::from_str(value).map_err(|_| {
+                Error::InvalidInput(format!(
+                    "value of header {name} is not a valid header value"
+                ))
+            })?;
+            value.set_sensitive(true);
+            extra_headers.append(name, value);
+        }
+        let authorization = match self.token.take() {
+            Some(mut token) => {
+                let mut header_text = format!("Token {token}");
+                scrub(&mut token);
+                let value = header::HeaderValue::from_str(&header_text);
+                scrub(&mut header_text);
+                let mut value = value.map_err(|_| {
+                    Error::InvalidInput("token is not a valid header value".to_string())
+                })?;
+                value.set_sensitive(true);
+                Some(value)
+            }
+            None => None,
+        };
+        let client = match self.reqwest_client {
+            Some(client) => client,
+            None => {
+                let mut client = reqwest::ClientBuilder::new()
+                    .user_agent(self.user_agent.as_deref().unwrap_or(USERAGENT));
+                if self.no_proxy {
+                    client = client.no_proxy();
+                }
+                for proxy in self.proxies {
+                    client = client.proxy(proxy.map_err(Error::ReqwestClientBuilder)?);
+                }
+                client = client.connection_verbose(self.connection_verbose);
+                client = client.redirect(self.redirect_policy.into_reqwest());
+                client
+                    .build()
+                    .map_err(|error| Error::ReqwestClientBuilder(error.to_string()))?
+            }
+        };
+        // At least one request has to be possible and all permits must be acquirable at once
+        let max_concurrent_requests = self
+            .max_concurrent_requests
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_REQUESTS)
+            .clamp(1, u32::MAX as usize);
+        Ok(Client {
+            client,
+            authorization,
+            retry: self.retry.unwrap_or(true),
+            max_wait_retry: self.max_wait_retry.unwrap_or(60),
+            max_retries: self.max_retries.unwrap_or(3),
+            logged_in: self.logged_in,
+            base_url: self.base_url.unwrap_or_else(|| {
+                format!(
+                    "{}/{}",
+                    API_ROOT,
+                    self.api_version.as_deref().unwrap_or(API_VERSION)
+                )
+            }),
+            perm_manage_tokens: self.perm_manage_tokens,
+            canonical_json: self.canonical_json,
+            rrset_cache_ttl: self.rrset_cache_ttl,
+            rrset_cache: RrsetCache::default(),
+            max_concurrent_requests,
+            in_flight: Arc::new(Semaphore::new(max_concurrent_requests)),
+            poll_async: self.poll_async,
+            max_body_bytes: self.max_body_bytes.unwrap_or(DEFAULT_MAX_BODY_BYTES),
+            last_rate_limit: Arc::new(Mutex::new(None)),
+            deduplicate_gets: self.deduplicate_gets,
+            single_flight: SingleFlight::default(),
+            redirect_policy: self.redirect_policy,
+            timeout: self.timeout,
+            retry_on_connection_error: self.retry_on_connection_error,
+            max_total_retry_duration: self.max_total_retry_duration,
+            extra_headers,
+            on_retry: self.on_retry,
+            #[cfg(feature = "signing")]
+            signer: self.signer.map(|(key, algorithm)| {
+                signing::RequestSigner::new(
+                    &key,
+                    algorithm,
+                    self.signature_header
+                        .as_deref()
+                        .unwrap_or(signing::DEFAULT_SIGNATURE_HEADER),
+                )
+            }),
+        })
+    }
+}
+
+/// Policy for handling HTTP redirects returned by the API.
+///
+/// The `Authorization` header is stripped by the underlying http client whenever
+/// a redirect points to another host or port, so the token is never leaked to a third party.
+/// A redirect which is not followed yields [`Error::UnexpectedStatusCode`][error].
+///
+/// [error]: enum.Error.html
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectPolicy {
+    /// Follow up to the given number of redirects
+    Follow(usize),
+    /// Follow up to the given number of redirects as long as they stay on the same host and port
+    SameHost(usize),
+    /// Never follow redirects
+    None,
+}
+
+#[cfg(feature = "std")]
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        RedirectPolicy::Follow(10)
+    }
+}
+
+#[cfg(feature = "std")]
+impl RedirectPolicy {
+    fn into_reqwest(self) -> reqwest::redirect::Policy {
+        match self {
+            RedirectPolicy::Follow(max) => reqwest::redirect::Policy::limited(max),
+            RedirectPolicy::SameHost(max) => reqwest::redirect::Policy::custom(move |attempt| {
+                let same_host = attempt.previous().first().map_or(true, |origin| {
+                    origin.host_str() == attempt.url().host_str()
+                        && origin.port_or_known_default() == attempt.url().port_or_known_default()
+                });
+                if !same_host {
+                    attempt.stop()
+                } else if attempt.previous().len() > max {
+                    attempt.error("too many redirects")
+                } else {
+                    attempt.follow()
+                }
+            }),
+            RedirectPolicy::None => reqwest::redirect::Policy::none(),
+        }
+    }
+}
+
+/// Reason a request is retried, see [`RetryEvent`][event].
+///
+/// [event]: struct.RetryEvent.html
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryReason {
+    /// The request has been throttled with status code 429
+    Throttled,
+    /// The request timed out
+    Timeout,
+    /// The request failed to connect
+    ConnectionError,
+}
+
+/// Passed to the callback registered with [`Client::set_on_retry`][set_on_retry]
+/// each time a request is about to be retried.
+///
+/// [set_on_retry]: struct.Client.html#method.set_on_retry
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryEvent {
+    /// Number of the upcoming retry, starting at 1
+    pub attempt: usize,
+    /// Time waited before the retry
+    pub wait: Duration,
+    /// HTTP method of the request
+    pub method: String,
+    /// Endpoint of the request relative to the base URL, e.g. `/domains/`
+    pub endpoint: String,
+    /// Why the request is retried
+    pub reason: RetryReason,
+}
+
+/// Callback invoked before each retry, shared across clones.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+struct RetryHook(Arc<dyn Fn(RetryEvent) + Send + Sync>);
+
+#[cfg(feature = "std")]
+impl core::fmt::Debug for RetryHook {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("RetryHook")
+    }
+}
+
+// Parses the comma separated methods of an Allow header.
+#[cfg(feature = "std")]
+fn parse_allow(allow: &str) -> Vec<String> {
+    allow
+        .split(',')
+        .map(|method| method.trim().to_ascii_uppercase())
+        .filter(|method| !method.is_empty())
+        .collect()
+}
+
+// Reads the body of a successful response as text.
+// Invalid UTF-8 (e.g. caused by a broken proxy) is reported instead of being replaced silently.
+#[cfg(feature = "std")]
+pub(crate) async fn response_text(response: Response) -> Result<String, Error> {
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("unknown")
+        .to_string();
+    let body = response.bytes().await.map_err(Error::Reqwest)?;
+    decode_body(&body, &content_type)
+}
+
+// Decodes a response body as UTF-8, the error carries the lossy decoded body for diagnosis.
+#[cfg(feature = "std")]
+fn decode_body(body: &[u8], content_type: &str) -> Result<String, Error> {
+    core::str::from_utf8(body)
+        .map(str::to_string)
+        .map_err(|error| {
+            Error::InvalidAPIResponse(
+                format!("non-UTF-8 response body (content type {content_type}): {error}"),
+                String::from_utf8_lossy(body).into_owned(),
+            )
+        })
+}
+
+// Deserializes the body of a list response.
+// An empty body (as sent by some proxies) is treated as an empty list.
+#[cfg(feature = "std")]
+pub(crate) fn parse_list<T: DeserializeOwned>(response_text: String) -> Result<Vec<T>, Error> {
+    if response_text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&response_text)
+        .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+}
+
+// Deserializes the body of a response for a single resource.
+// An empty body is rejected with a clear message instead of a confusing serde error.
+#[cfg(feature = "std")]
+pub(crate) fn parse_single<T: DeserializeOwned>(response_text: String) -> Result<T, Error> {
+    if response_text.trim().is_empty() {
+        return Err(Error::InvalidAPIResponse(
+            "API returned an empty body where a resource was expected".to_string(),
+            response_text,
+        ));
+    }
+    serde_json::from_str(&response_text)
+        .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+}
+
+// Maps Error::NotFound to None, e.g. for the try_get_* methods.
+#[cfg(feature = "std")]
+pub(crate) fn not_found_as_none<T>(result: Result<T, Error>) -> Result<Option<T>, Error> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(Error::NotFound { .. }) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+// Deserializes the body of a response carrying a secret, e.g. a token,
+// overwriting the body afterwards if the zeroize feature is enabled.
+#[cfg(feature = "std")]
+pub(crate) fn parse_secret<T: DeserializeOwned>(mut response_text: String) -> Result<T, Error> {
+    match serde_json::from_str(&response_text) {
+        Ok(value) => {
+            scrub(&mut response_text);
+            Ok(value)
+        }
+        Err(error) => Err(Error::InvalidAPIResponse(error.to_string(), response_text)),
+    }
+}
+
+// Overwrites a secret with zeros if the zeroize feature is enabled.
+#[cfg(feature = "core-types")]
+pub(crate) fn scrub(secret: &mut String) {
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(secret);
+    #[cfg(not(feature = "zeroize"))]
+    let _ = secret;
+}
+
+// Recursively sorts the keys of all objects in the value.
+// serde_json only keeps keys sorted as long as its preserve_order feature is not enabled,
+// which might happen through any other crate in the dependency tree.
+#[cfg(feature = "std")]
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonicalize(value)))
+                    .collect::<Map<String, Value>>(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonicalize).collect()),
+        value => value,
+    }
+}
+
+// Whether repeating a request with the given method has the same effect as sending it once.
+// Deleting is idempotent for deSEC as deleting a missing resource also yields 204.
+#[cfg(feature = "std")]
+fn is_idempotent(method: &reqwest::Method) -> bool {
+    matches!(
+        *method,
+        reqwest::Method::GET
+            | reqwest::Method::HEAD
+            | reqwest::Method::OPTIONS
+            | reqwest::Method::PUT
+            | reqwest::Method::DELETE
+    )
+}
+
+// Exponential backoff in seconds for retrying failed requests, capped by max_wait_retry.
+#[cfg(feature = "std")]
+pub(crate) fn transient_backoff(retries: usize, max_wait_retry: u64) -> u64 {
+    2u64.saturating_pow(retries as u32).min(max_wait_retry)
+}
+
+// Parses a Retry-After header into the seconds to wait, which is either a number of seconds or
+// an HTTP-date (RFC 7231, section 7.1.3). Dates in the past result in no wait time,
+// fractions of a second are rounded up.
+#[cfg(feature = "std")]
+fn parse_retry_after(header: &str, now: SystemTime) -> Option<u64> {
+    let header = header.trim();
+    if let Ok(seconds) = header.parse() {
+        return Some(seconds);
+    }
+    let date = httpdate::parse_http_date(header).ok()?;
+    Some(match date.duration_since(now) {
+        Ok(wait) => wait.as_secs() + u64::from(wait.subsec_nanos() > 0),
+        Err(_) => 0,
+    })
+}
+
+// Parsing the time we have to wait till next retry.
+// Error out if we cannot parse, retry is disabled, or accepted max wait time will be exceeded.
+#[cfg(feature = "std")]
+async fn parse_time_to_wait(
+    response: Response,
+    max_wait_retry: u64,
+    should_retry: bool,
+) -> Result<u64, Error> {
+    let (time_to_wait, header) = match response.headers().get("retry-after") {
+        Some(header) => match header.to_str() {
+            Ok(header) => (
+                parse_retry_after(header, SystemTime::now()).ok_or_else(|| {
+                    Error::RateLimitedWithoutRetry(format!(
+                        "Request was throttled and cannot parse retry after {:?}",
+                        header
+                    ))
+                })?,
+                header.to_string(),
+            ),
+            Err(_) => return Err(Error::RateLimitedWithoutRetry(
+                "Request got throttled with retry-after header containing non-visible ASCII chars"
+                    .to_string(),
+            )),
+        },
+        None => {
+            return Err(Error::RateLimitedWithoutRetry(
+                "Request got throttled without retry-after header".to_string(),
+            ))
+        }
+    };
+    // Abort if we are not interested in retries
+    if !should_retry {
+        let msg = String::from("Request has been throttled, but retries are disabled");
         debug!("{}", msg);
-        return Err(Error::RateLimited(
+        let body = response.text().await.unwrap_or_default();
+        return Err(Error::RateLimited(RateLimitInfo::new(
             time_to_wait,
-            response.text().await.unwrap_or(msg),
-        ));
+            &header,
+            &body,
+            msg,
+        )));
     }
     if time_to_wait > max_wait_retry {
         let msg = format!(
@@ -444,7 +2095,888 @@ async fn parse_time_to_wait(
             time_to_wait, max_wait_retry
         );
         debug!("{}", msg);
-        return Err(Error::RateLimited(time_to_wait, msg));
+        let body = response.text().await.unwrap_or_default();
+        return Err(Error::RateLimited(RateLimitInfo::new(
+            time_to_wait,
+            &header,
+            &body,
+            msg,
+        )));
     }
     Ok(time_to_wait)
 }
+
+// The data types without the HTTP client, as used in no_std + alloc environments
+#[cfg(all(test, feature = "core-types", not(feature = "std")))]
+mod core_types_tests {
+    use super::*;
+    use crate::rrset::{RecordType, ResourceRecordSet};
+    use alloc::vec;
+
+    fn rrset(subname: &str, rrset_type: &str, records: &[&str]) -> ResourceRecordSet {
+        ResourceRecordSet {
+            domain: "example.com".to_string(),
+            subname: Some(subname.to_string()),
+            rrset_type: RecordType::from(rrset_type),
+            ttl: 3600,
+            records: records.iter().map(|record| record.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn zones_are_diffed_and_rendered_without_std() {
+        let current = vec![rrset("www", "A", &["192.0.2.1"]), rrset("old", "TXT", &[])];
+        let desired = vec![rrset("www", "a", &["192.0.2.2"]), rrset("mail", "MX", &[])];
+        let diff = rrset::diff(&current, &desired);
+        assert_eq!(diff.to_create[0].subname.as_deref(), Some("mail"));
+        assert_eq!(diff.to_update[0].after.records, ["192.0.2.2"]);
+        assert_eq!(diff.to_delete[0].subname.as_deref(), Some("old"));
+        assert_eq!(
+            rrset::to_zonefile("example.com", &desired[..1]),
+            "$ORIGIN example.com.\nwww.example.com.\t3600\tIN\tA\t192.0.2.2\n"
+        );
+        assert_eq!(RecordType::from("svcb").to_string(), "SVCB");
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+
+    #[test]
+    fn api_error_body_is_parsed() {
+        let body = ApiErrorBody::parse(
+            r#"{"detail": "Invalid input.", "name": ["This domain name is unavailable."]}"#
+                .to_string(),
+        );
+        assert_eq!(body.detail.as_deref(), Some("Invalid input."));
+        assert_eq!(body.field("name"), ["This domain name is unavailable."]);
+        assert!(body.field("records").is_empty());
+        let body = ApiErrorBody::parse(r#"[{}, {"records": ["Invalid IPv4."]}]"#.to_string());
+        assert_eq!(body.detail, None);
+        assert_eq!(body.field("1.records"), ["Invalid IPv4."]);
+        let body = ApiErrorBody::parse("Bad Request".to_string());
+        assert_eq!(body.detail, None);
+        assert!(body.field_errors.is_empty());
+        assert_eq!(body.to_string(), "Bad Request");
+    }
+
+    #[test]
+    fn canonical_json_sorts_nested_keys_and_keeps_array_order() {
+        let value = serde_json::json!({
+            "type": "TXT",
+            "records": ["\"b\"", "\"a\""],
+            "nested": {"z": 1, "a": [{"y": true, "b": null}, 2]},
+        });
+        assert_eq!(
+            serde_json::to_string(&canonicalize(value)).unwrap(),
+            r#"{"nested":{"a":[{"b":null,"y":true},2],"z":1},"records":["\"b\"","\"a\""],"type":"TXT"}"#
+        );
+    }
+
+    #[test]
+    fn api_version_selects_base_url() {
+        let config = Client::builder().build().unwrap().config();
+        assert_eq!(config.base_url, API_URL);
+        let config = Client::builder()
+            .api_version("v2")
+            .build()
+            .unwrap()
+            .config();
+        assert_eq!(config.base_url, "https://desec.io/api/v2");
+        let config = Client::builder()
+            .api_version("v2")
+            .base_url("https://desec.example/api/v1/")
+            .build()
+            .unwrap()
+            .config();
+        assert_eq!(config.base_url, "https://desec.example/api/v1");
+    }
+
+    #[test]
+    fn secrets_are_parsed_and_scrubbed() {
+        let body = r#"{"created": "", "id": "t1", "last_used": null, "name": "ci", "perm_manage_tokens": false, "allowed_subnets": [], "token": "4pnk7u-NHvrEkFzrhFDRTjGFyX_S"}"#;
+        let token: crate::token::Token = parse_secret(body.to_string()).unwrap();
+        assert_eq!(token.token.as_deref(), Some("4pnk7u-NHvrEkFzrhFDRTjGFyX_S"));
+        let round_trip = serde_json::to_value(&token).unwrap();
+        assert_eq!(round_trip["token"], "4pnk7u-NHvrEkFzrhFDRTjGFyX_S");
+
+        let mut secret = "4pnk7u-NHvrEkFzrhFDRTjGFyX_S".to_string();
+        scrub(&mut secret);
+        #[cfg(feature = "zeroize")]
+        assert!(secret.is_empty());
+        #[cfg(not(feature = "zeroize"))]
+        assert_eq!(secret, "4pnk7u-NHvrEkFzrhFDRTjGFyX_S");
+    }
+
+    #[test]
+    fn empty_body_on_list_is_empty_vec() {
+        let domains: Vec<Domain> = parse_list(String::new()).expect("empty list should be ok");
+        assert!(domains.is_empty());
+        let domains: Vec<Domain> = parse_list("  \n".to_string()).expect("empty list should be ok");
+        assert!(domains.is_empty());
+        let domains: Vec<Domain> = parse_list("[]".to_string()).expect("empty list should be ok");
+        assert!(domains.is_empty());
+    }
+
+    #[test]
+    fn empty_body_on_detail_is_error() {
+        match parse_single::<Domain>(String::new()) {
+            Err(Error::InvalidAPIResponse(message, body)) => {
+                assert!(message.contains("empty body"));
+                assert!(body.is_empty());
+            }
+            _ => panic!("Should yield desec_api::Error::InvalidAPIResponse"),
+        }
+    }
+
+    #[test]
+    fn invalid_utf8_body_is_error() {
+        let body = b"[{\"name\": \"caf\xe9.example\"}]";
+        match decode_body(body, "application/json") {
+            Err(Error::InvalidAPIResponse(message, body)) => {
+                assert!(message.contains("non-UTF-8 response body"));
+                assert!(message.contains("application/json"));
+                assert!(body.contains('\u{FFFD}'));
+            }
+            _ => panic!("Should yield desec_api::Error::InvalidAPIResponse"),
+        }
+        let text = decode_body("[]".as_bytes(), "application/json").unwrap();
+        assert!(parse_list::<Domain>(text).unwrap().is_empty());
+    }
+
+    #[test]
+    fn config_reflects_settings() {
+        let mut client = Client::builder()
+            .max_concurrent_requests(4)
+            .redirect_policy(RedirectPolicy::None)
+            .retry(false)
+            .max_wait_retry(5)
+            .build()
+            .unwrap();
+        client.set_max_retries(7);
+        let config = client.config();
+        assert!(!config.retry);
+        assert_eq!(config.max_wait_retry, 5);
+        assert_eq!(config.max_concurrent_requests, 4);
+        assert_eq!(config.redirect_policy, RedirectPolicy::None);
+        assert_eq!(config.max_retries, 7);
+        assert_eq!(config.base_url, client.get_base_url());
+    }
+
+    #[test]
+    fn retry_after_in_seconds() {
+        let now = SystemTime::now();
+        assert_eq!(parse_retry_after("5", now), Some(5));
+        assert_eq!(parse_retry_after(" 120 ", now), Some(120));
+        assert_eq!(parse_retry_after("-1", now), None);
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn retry_after_as_http_date() {
+        let now = httpdate::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:50:07 GMT", now),
+            Some(30)
+        );
+        // Fractions of a second are rounded up
+        let now = now - Duration::from_millis(500);
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:38 GMT", now),
+            Some(2)
+        );
+        // Dates in the past are clamped to no wait time
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:00 GMT", now),
+            Some(0)
+        );
+        // The obsolete formats of RFC 850 and asctime are accepted as well
+        assert!(parse_retry_after("Sunday, 06-Nov-94 08:49:37 GMT", now).is_some());
+    }
+
+    #[test]
+    fn allow_header_is_split_into_methods() {
+        assert_eq!(
+            parse_allow("GET, post,PATCH ,, OPTIONS"),
+            vec!["GET", "POST", "PATCH", "OPTIONS"]
+        );
+        assert!(parse_allow("").is_empty());
+    }
+
+    // Accepts a single connection, answers it with `response` and returns the raw request head.
+    pub(crate) async fn serve_once(listener: tokio::net::TcpListener, response: String) -> String {
+        let (stream, _) = listener.accept().await.unwrap();
+        serve_connection(stream, response).await
+    }
+
+    // Serves one response per connection in the given order and returns the requests.
+    async fn serve_sequence(
+        listener: tokio::net::TcpListener,
+        responses: Vec<String>,
+    ) -> Vec<String> {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (stream, _) = listener.accept().await.unwrap();
+            requests.push(serve_connection(stream, response).await);
+        }
+        requests
+    }
+
+    // Reads the request including its body, if announced by a Content-Length header.
+    pub(crate) async fn serve_connection(
+        mut stream: tokio::net::TcpStream,
+        response: String,
+    ) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 1024];
+        let complete = |request: &[u8]| {
+            let head_end = match request.windows(4).position(|window| window == b"\r\n\r\n") {
+                Some(position) => position + 4,
+                None => return false,
+            };
+            let content_length = String::from_utf8_lossy(&request[..head_end])
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            request.len() >= head_end + content_length
+        };
+        while !complete(&request) {
+            let read = stream.read(&mut buffer).await.unwrap();
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buffer[..read]);
+        }
+        stream.write_all(response.as_bytes()).await.unwrap();
+        stream.shutdown().await.unwrap();
+        String::from_utf8(request).unwrap()
+    }
+
+    // Starts a server answering one connection per response in the given order and returns
+    // a client for it, together with the handle yielding the received requests.
+    pub(crate) async fn mock_client(
+        responses: Vec<String>,
+    ) -> (Client, tokio::task::JoinHandle<Vec<String>>) {
+        mock_client_with(Client::builder(), responses).await
+    }
+
+    // Like `mock_client`, but builds the client from the given builder.
+    pub(crate) async fn mock_client_with(
+        builder: ClientBuilder,
+        responses: Vec<String>,
+    ) -> (Client, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/api/v1", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_sequence(listener, responses));
+        let client = builder.no_proxy().base_url(&base_url).build().unwrap();
+        (client, server)
+    }
+
+    // Builds a raw HTTP response with the given status and body.
+    pub(crate) fn response(status: u16, body: &str) -> String {
+        response_with_headers(status, &[], body)
+    }
+
+    // Builds a raw HTTP response with the given status, additional headers and body.
+    pub(crate) fn response_with_headers(
+        status: u16,
+        headers: &[(&str, &str)],
+        body: &str,
+    ) -> String {
+        let status = StatusCode::from_u16(status).unwrap();
+        let headers = headers
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}\r\n"))
+            .collect::<String>();
+        format!(
+            "HTTP/1.1 {} {}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or_default(),
+            body.len()
+        )
+    }
+
+    // Builds a page of a list endpoint, announcing the next page if a cursor is given.
+    pub(crate) fn page_response(body: &str, next_cursor: Option<&str>) -> String {
+        match next_cursor {
+            Some(cursor) => {
+                let link = format!(
+                    "<https://desec.io/api/v1/domains/?cursor=>; rel=\"first\", <https://desec.io/api/v1/domains/?cursor={cursor}>; rel=\"next\""
+                );
+                response_with_headers(200, &[("Link", &link)], body)
+            }
+            None => response(200, body),
+        }
+    }
+
+    // Parses the JSON body of a request read by serve_connection.
+    pub(crate) fn request_body(request: &str) -> Value {
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        serde_json::from_str(body).unwrap()
+    }
+
+    // A domain as returned by the API.
+    pub(crate) fn domain_json(name: &str) -> String {
+        serde_json::json!({
+            "created": "2024-05-04T10:00:00Z",
+            "published": null,
+            "name": name,
+            "minimum_ttl": 3600,
+            "touched": "2024-05-04T10:00:00Z"
+        })
+        .to_string()
+    }
+
+    // An RRset as returned by the API.
+    pub(crate) fn rrset_json(
+        domain: &str,
+        subname: &str,
+        rrset_type: &str,
+        ttl: u64,
+        records: &[&str],
+    ) -> String {
+        let name = match subname {
+            "" => format!("{domain}."),
+            subname => format!("{subname}.{domain}."),
+        };
+        serde_json::json!({
+            "created": "2024-05-04T10:00:00Z",
+            "domain": domain,
+            "subname": subname,
+            "name": name,
+            "type": rrset_type,
+            "ttl": ttl,
+            "records": records,
+            "touched": "2024-05-04T10:00:00Z"
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn warm_up_accepts_any_response_without_retrying() {
+        let (client, server) = mock_client(vec![response_with_headers(
+            429,
+            &[("Retry-After", "0")],
+            "",
+        )])
+        .await;
+        client.warm_up().await.unwrap();
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("HEAD /api/v1/ "));
+
+        let (client, server) = mock_client(vec![]).await;
+        server.await.unwrap();
+        assert!(matches!(client.warm_up().await, Err(Error::Reqwest(_))));
+    }
+
+    #[tokio::test]
+    async fn authorization_is_not_forwarded_on_cross_host_redirect() {
+        let origin = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let other = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let origin_url = format!("http://{}", origin.local_addr().unwrap());
+        let other_url = format!("http://{}", other.local_addr().unwrap());
+
+        let location = format!("{other_url}/elsewhere/");
+        let redirect = tokio::spawn(serve_once(
+            origin,
+            response_with_headers(302, &[("Location", &location)], ""),
+        ));
+        let target = tokio::spawn(serve_once(other, response(200, "[]")));
+
+        let client = Client::builder()
+            .token("secret-token".to_string())
+            .no_proxy()
+            .base_url(&origin_url)
+            .build()
+            .unwrap();
+        client.get("/domains/").await.unwrap();
+
+        let first = redirect.await.unwrap().to_ascii_lowercase();
+        let second = target.await.unwrap().to_ascii_lowercase();
+        assert!(first.contains("authorization: token secret-token"));
+        assert!(second.starts_with("get /elsewhere/"));
+        assert!(!second.contains("authorization"));
+        assert!(!second.contains("secret-token"));
+    }
+
+    #[tokio::test]
+    async fn cross_origin_status_resource_is_not_polled() {
+        let other = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let location = format!("http://{}/status/1/", other.local_addr().unwrap());
+        let (client, server) = mock_client_with(
+            Client::builder()
+                .token("secret-token".to_string())
+                .poll_async(true),
+            vec![response_with_headers(202, &[("Location", &location)], "")],
+        )
+        .await;
+        let error = client.get("/domains/").await.unwrap_err();
+        assert!(matches!(error, Error::InvalidAPIResponse(..)));
+        server.await.unwrap();
+        // The other host is never contacted
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), other.accept())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn not_found_includes_request_context() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/api/v1", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_once(listener, response(404, "")));
+        // The trailing slash is removed from the base URL
+        let client = Client::builder()
+            .no_proxy()
+            .base_url(&format!("{base_url}/"))
+            .build()
+            .unwrap();
+        let error = client
+            .get("/domains/example.com/rrsets/www/A/")
+            .await
+            .unwrap_err();
+        server.await.unwrap();
+        assert_eq!(
+            error.to_string(),
+            "404 Not Found: GET /domains/example.com/rrsets/www/A/"
+        );
+    }
+
+    #[tokio::test]
+    async fn injected_client_sends_authorization_per_request() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/api/v1", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_once(listener, response(200, "[]")));
+        let reqwest_client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let client = Client::builder()
+            .token("secret-token".to_string())
+            .reqwest_client(reqwest_client)
+            .base_url(&base_url)
+            .build()
+            .unwrap();
+        assert!(!format!("{client:?}").contains("secret-token"));
+        client.get("/domains/").await.unwrap();
+        let request = server.await.unwrap().to_ascii_lowercase();
+        assert!(request.contains("authorization: token secret-token"));
+    }
+
+    #[tokio::test]
+    async fn request_timeout_surfaces_as_reqwest_error() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/api/v1", listener.local_addr().unwrap());
+        // Accept the connection but never respond
+        let server = tokio::spawn(async move { listener.accept().await.unwrap() });
+        let client = Client::builder()
+            .no_proxy()
+            .base_url(&base_url)
+            .timeout(Duration::from_millis(100))
+            .retry(false)
+            .build()
+            .unwrap();
+        match client.get("/domains/").await {
+            Err(Error::Reqwest(error)) => assert!(error.is_timeout()),
+            _ => panic!("Should yield desec_api::Error::Reqwest"),
+        }
+        drop(server.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn extra_headers_are_merged_into_requests() {
+        let (client, server) = mock_client_with(
+            Client::builder()
+                .token("secret".to_string())
+                .default_header("X-Api-Gateway-Key", "gateway")
+                .default_header("Authorization", "Bearer other"),
+            vec![response(200, "[]")],
+        )
+        .await;
+        assert!(!format!("{client:?}").contains("\"gateway\""));
+        client.get("/domains/").await.unwrap();
+        let request = &server.await.unwrap()[0];
+        assert!(request.contains("x-api-gateway-key: gateway\r\n"));
+        assert!(request.contains("authorization: Token secret\r\n"));
+        assert!(!request.contains("Bearer other"));
+        assert!(matches!(
+            Client::builder().default_header("X Key", "value").build(),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let mut client = Client::builder().no_proxy().build().unwrap();
+        let mut extra_headers = header::HeaderMap::new();
+        extra_headers.insert("x-api-gateway-key", "gateway".parse().unwrap());
+        client.set_extra_headers(extra_headers);
+        assert!(!format!("{client:?}").contains("\"gateway\""));
+    }
+
+    #[tokio::test]
+    async fn verify_token_reports_rejected_credentials() {
+        for (status, valid) in [(200, true), (403, true), (401, false)] {
+            let (client, server) = mock_client_with(
+                Client::builder().token("secret".to_string()),
+                vec![response(status, "[]")],
+            )
+            .await;
+            assert_eq!(client.verify_token().await.unwrap(), valid);
+            let requests = server.await.unwrap();
+            assert!(requests[0].starts_with("GET /api/v1/auth/tokens/ "));
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_are_reported_to_callback() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let (client, server) = mock_client_with(
+            Client::builder()
+                .max_retries(1)
+                .on_retry(move |event| recorded.lock().unwrap().push(event)),
+            vec![response_with_headers(429, &[("Retry-After", "0")], ""); 2],
+        )
+        .await;
+        let error = client.get("/domains/?owner=me").await.unwrap_err();
+        server.await.unwrap();
+        assert!(matches!(error, Error::RateLimitedMaxRetriesReached));
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![RetryEvent {
+                attempt: 1,
+                wait: Duration::from_secs(0),
+                method: "GET".to_string(),
+                endpoint: "/domains/?owner=me".to_string(),
+                reason: RetryReason::Throttled,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_stop_at_total_retry_duration() {
+        let (client, server) = mock_client_with(
+            Client::builder().max_total_retry_duration(Duration::from_secs(2)),
+            vec![response_with_headers(429, &[("Retry-After", "5")], "")],
+        )
+        .await;
+        let error = client.get("/domains/").await.unwrap_err();
+        server.await.unwrap();
+        assert!(
+            matches!(error, Error::RetryBudgetExhausted(budget) if budget == Duration::from_secs(2))
+        );
+        assert_eq!(
+            error.kind(),
+            ErrorKind::RetryBudgetExhausted {
+                budget_millis: 2000
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn throttled_request_carries_rate_limit_info() {
+        let (client, server) = mock_client_with(
+            Client::builder().retry(false),
+            vec![response_with_headers(
+                429,
+                &[("Retry-After", "5")],
+                r#"{"detail": "Request was throttled. Expected available in 5 seconds."}"#,
+            )],
+        )
+        .await;
+        let error = client.get("/domains/").await.unwrap_err();
+        server.await.unwrap();
+        match error {
+            Error::RateLimited(info) => {
+                assert_eq!(info.retry_after, Duration::from_secs(5));
+                assert_eq!(info.retry_after_header, "5");
+                assert_eq!(
+                    info.detail,
+                    "Request was throttled. Expected available in 5 seconds."
+                );
+            }
+            _ => panic!("Should yield desec_api::Error::RateLimited"),
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_user_agent_is_sent() {
+        let (client, server) = mock_client_with(
+            Client::builder().user_agent(format!("{USERAGENT} my-tool/1.0")),
+            vec![response(200, "[]")],
+        )
+        .await;
+        client.get("/domains/").await.unwrap();
+        let requests = server.await.unwrap();
+        assert!(requests[0].contains(&format!("user-agent: {USERAGENT} my-tool/1.0\r\n")));
+    }
+
+    #[tokio::test]
+    async fn requests_are_sent_through_proxy() {
+        let proxy = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_url = format!("http://{}", proxy.local_addr().unwrap());
+        let server = tokio::spawn(serve_once(proxy, response(200, "[]")));
+        let client = Client::builder()
+            .no_proxy()
+            .proxy_url(&proxy_url)
+            .base_url("http://desec.invalid/api/v1")
+            .build()
+            .unwrap();
+        client.get("/domains/").await.unwrap();
+        let request = server.await.unwrap();
+        assert!(request.starts_with("GET http://desec.invalid/api/v1/domains/ "));
+    }
+
+    #[tokio::test]
+    async fn proxies_are_tried_in_the_order_they_were_added() {
+        let proxy = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_url = format!("http://{}", proxy.local_addr().unwrap());
+        let server = tokio::spawn(serve_once(proxy, response(200, "[]")));
+        // Nobody listens on the second proxy
+        let unused = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let unused_url = format!("http://{}", unused.local_addr().unwrap());
+        drop(unused);
+        let client = Client::builder()
+            .no_proxy()
+            .proxy(reqwest::Proxy::all(&proxy_url).unwrap())
+            .proxy_url(&unused_url)
+            .base_url("http://desec.invalid/api/v1")
+            .build()
+            .unwrap();
+        client.get("/domains/").await.unwrap();
+        let request = server.await.unwrap();
+        assert!(request.starts_with("GET http://desec.invalid/api/v1/domains/ "));
+        assert!(matches!(
+            Client::builder().proxy_url("not a url").build(),
+            Err(Error::ReqwestClientBuilder(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn connection_errors_are_retried_if_enabled() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+        let client = Client::builder()
+            .no_proxy()
+            .base_url(&format!("http://{address}/api/v1"))
+            .retry_on_connection_error(true)
+            .max_wait_retry(1)
+            .build()
+            .unwrap();
+        // Nobody listens on the port until the server is started after the first attempt
+        let server = async move {
+            sleep(Duration::from_millis(50)).await;
+            let listener = tokio::net::TcpListener::bind(address).await.unwrap();
+            serve_once(listener, response(200, "[]")).await
+        };
+        let server = tokio::spawn(server);
+        let response = client.post("/domains/", None).await;
+        assert_eq!(response.unwrap().status(), StatusCode::OK);
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn bulk_bodies_are_split_by_size() {
+        let mut client = Client::builder().build().unwrap();
+        let items = vec!["a".repeat(10), "b".repeat(10), "c".repeat(30)];
+        assert_eq!(
+            client.serialize_bulk_bodies(&items).unwrap(),
+            vec![format!(r#"["{}","{}","{}"]"#, items[0], items[1], items[2])]
+        );
+        client.set_max_body_bytes(30);
+        assert_eq!(
+            client.serialize_bulk_bodies(&items).unwrap(),
+            vec![
+                format!(r#"["{}","{}"]"#, items[0], items[1]),
+                format!(r#"["{}"]"#, items[2])
+            ]
+        );
+        assert!(client
+            .serialize_bulk_bodies::<String>(&[])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn error_kind_roundtrips_through_json() {
+        let kind = Error::NotFound {
+            method: "GET".to_string(),
+            path: "/domains/example.com/".to_string(),
+        }
+        .kind();
+        let json = serde_json::to_string(&kind).unwrap();
+        assert_eq!(
+            json,
+            r#"{"kind":"not_found","method":"GET","path":"/domains/example.com/"}"#
+        );
+        assert_eq!(serde_json::from_str::<ErrorKind>(&json).unwrap(), kind);
+    }
+
+    #[tokio::test]
+    async fn identical_gets_in_flight_share_one_request() {
+        // The server answers a single request only, a second one would be refused
+        let (client, server) = mock_client_with(
+            Client::builder().deduplicate_gets(true),
+            vec![response(200, "[]")],
+        )
+        .await;
+        let (first, second) = tokio::join!(client.get("/domains/"), client.get("/domains/"));
+        server.await.unwrap();
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert_eq!(second.url(), first.url());
+        assert_eq!(
+            second.url().as_str(),
+            format!("{}/domains/", client.get_base_url())
+        );
+        assert_eq!(first.text().await.unwrap(), "[]");
+        assert_eq!(second.text().await.unwrap(), "[]");
+    }
+
+    #[tokio::test]
+    async fn large_bodies_are_not_shared_between_gets_in_flight() {
+        let (client, server) = mock_client_with(
+            Client::builder().deduplicate_gets(true).max_body_bytes(1),
+            vec![response(200, "[]"), response(200, "[]")],
+        )
+        .await;
+        let (first, second) = tokio::join!(client.get("/domains/"), client.get("/domains/"));
+        assert_eq!(server.await.unwrap().len(), 2);
+        assert_eq!(first.unwrap().text().await.unwrap(), "[]");
+        assert_eq!(second.unwrap().text().await.unwrap(), "[]");
+    }
+
+    #[tokio::test]
+    async fn timed_out_writes_are_ambiguous_and_reads_are_retried() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/api/v1", listener.local_addr().unwrap());
+        // The first two connections are never answered, the third one is
+        let server = tokio::spawn(async move {
+            let (post, _) = listener.accept().await.unwrap();
+            let (get, _) = listener.accept().await.unwrap();
+            let (stream, _) = listener.accept().await.unwrap();
+            let request = serve_connection(stream, response(200, "[]")).await;
+            drop((post, get));
+            request
+        });
+        let client = Client::builder()
+            .no_proxy()
+            .base_url(&base_url)
+            .timeout(Duration::from_millis(100))
+            .max_wait_retry(0)
+            .build()
+            .unwrap();
+        match client.post("/domains/", Some("{}".to_string())).await {
+            Err(Error::AmbiguousWrite(method, error)) => {
+                assert_eq!(method, "POST");
+                assert!(error.is_timeout());
+            }
+            _ => panic!("Should yield desec_api::Error::AmbiguousWrite"),
+        }
+        let response = client.get("/domains/").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(server.await.unwrap().starts_with("GET /api/v1/domains/ "));
+    }
+
+    #[tokio::test]
+    async fn every_retry_path_sends_max_retries_plus_one_requests() {
+        let retry_count = |builder: ClientBuilder| {
+            let retries = Arc::new(Mutex::new(0));
+            let counter = retries.clone();
+            let builder = builder
+                .max_retries(2)
+                .max_wait_retry(0)
+                .on_retry(move |_| *counter.lock().unwrap() += 1);
+            (builder, retries)
+        };
+
+        // Throttled
+        let throttled = response_with_headers(429, &[("Retry-After", "0")], "");
+        let (builder, retries) = retry_count(Client::builder());
+        let (client, server) = mock_client_with(builder, vec![throttled; 3]).await;
+        assert!(matches!(
+            client.get("/domains/").await,
+            Err(Error::RateLimitedMaxRetriesReached)
+        ));
+        assert_eq!(server.await.unwrap().len(), 3);
+        assert_eq!(*retries.lock().unwrap(), 2);
+
+        // Timed out, the connections are accepted but never answered
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/api/v1", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok(Ok((stream, _))) =
+                tokio::time::timeout(Duration::from_millis(500), listener.accept()).await
+            {
+                connections.push(stream);
+            }
+            connections.len()
+        });
+        let (builder, retries) = retry_count(Client::builder().timeout(Duration::from_millis(50)));
+        let client = builder.no_proxy().base_url(&base_url).build().unwrap();
+        assert!(
+            matches!(client.get("/domains/").await, Err(Error::Reqwest(error)) if error.is_timeout())
+        );
+        assert_eq!(server.await.unwrap(), 3);
+        assert_eq!(*retries.lock().unwrap(), 2);
+
+        // Failed to connect, as the server is gone without accepting any connection
+        let (builder, retries) = retry_count(Client::builder().retry_on_connection_error(true));
+        let (client, server) = mock_client_with(builder, vec![]).await;
+        server.await.unwrap();
+        assert!(
+            matches!(client.get("/domains/").await, Err(Error::Reqwest(error)) if error.is_connect())
+        );
+        assert_eq!(*retries.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn shutdown_waits_for_requests_in_flight() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/api/v1", listener.local_addr().unwrap());
+        let (accepted, connected) = tokio::sync::oneshot::channel();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            accepted.send(()).unwrap();
+            sleep(Duration::from_millis(200)).await;
+            serve_connection(stream, response(200, "[]")).await
+        });
+        let client = Client::builder()
+            .token("secret".to_string())
+            .no_proxy()
+            .base_url(&base_url)
+            .build()
+            .unwrap();
+        let clone = client.clone();
+        let request = tokio::spawn(async move { clone.get("/domains/").await.unwrap().status() });
+        connected.await.unwrap();
+        let started = Instant::now();
+        // No logout is sent for a token client, the server would not answer it
+        client.shutdown().await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert_eq!(request.await.unwrap(), StatusCode::OK);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn shutdown_logs_out_credential_sessions_only() {
+        let client = Client::builder().no_proxy().build().unwrap();
+        assert!(matches!(
+            client.clone().logout().await,
+            Err(Error::CannotLogout)
+        ));
+        client.shutdown().await.unwrap();
+
+        let mut builder = Client::builder().token("secret".to_string());
+        builder.logged_in = true;
+        let (client, server) = mock_client_with(builder, vec![response(204, "")]).await;
+        client.shutdown().await.unwrap();
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("POST /api/v1/auth/logout/ "));
+        assert!(requests[0].contains("authorization: Token secret\r\n"));
+    }
+}
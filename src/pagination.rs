@@ -0,0 +1,256 @@
+//! Support for the cursor based [`pagination`][reference] of deSEC list endpoints.
+//!
+//! deSEC returns at most 500 items per request. Larger listings are retrieved page by page,
+//! where the cursor of the next page is announced in the `Link` header.
+//!
+//! deSEC currently does not send a header with the total count of items. [`Page::total`] is
+//! only set if such a header (`X-Pagination-Total` or `X-Total-Count`) is present, e.g. when
+//! a proxy adds it. The `count_*` methods therefore fall back to retrieving all pages.
+//!
+//! [reference]: https://desec.readthedocs.io/en/latest/dns/rrsets.html#pagination
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+#[cfg(feature = "std")]
+use crate::{Client, Error};
+#[cfg(feature = "std")]
+use futures_util::stream::{self, Stream};
+#[cfg(feature = "std")]
+use reqwest::header::HeaderMap;
+#[cfg(feature = "std")]
+use reqwest::StatusCode;
+#[cfg(feature = "std")]
+use serde::de::DeserializeOwned;
+
+/// Headers which may carry the total count of items of a list endpoint.
+#[cfg(feature = "std")]
+const TOTAL_COUNT_HEADERS: [&str; 2] = ["x-pagination-total", "x-total-count"];
+
+/// A single page of a list endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    /// The items of this page
+    pub items: Vec<T>,
+    /// Opaque cursor of the next page, `None` if this is the last page
+    pub next_cursor: Option<String>,
+    /// Total count of items across all pages, if announced by the API
+    pub total: Option<u64>,
+}
+
+#[cfg(feature = "std")]
+impl Client {
+    /// Retrieves a single page of the given list endpoint.
+    /// Without a cursor, the first page is requested. deSEC rejects listings of more than
+    /// 500 items without a cursor, in which case the first page is requested with an empty cursor.
+    pub(crate) async fn get_page<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        cursor: Option<&str>,
+    ) -> Result<Page<T>, Error> {
+        let separator = if endpoint.contains('?') { '&' } else { '?' };
+        let response = match cursor {
+            Some(cursor) => {
+                self.get(format!("{endpoint}{separator}cursor={cursor}").as_str())
+                    .await?
+            }
+            None => match self.get(endpoint).await {
+                Err(Error::ApiError(400, _)) => {
+                    self.get(format!("{endpoint}{separator}cursor=").as_str())
+                        .await?
+                }
+                response => response?,
+            },
+        };
+        match response.status() {
+            StatusCode::OK => {
+                let next_cursor = parse_next_cursor(response.headers());
+                let total = parse_total(response.headers());
+                let response_text = crate::response_text(response).await?;
+                Ok(Page {
+                    items: crate::parse_list(response_text)?,
+                    next_cursor,
+                    total,
+                })
+            }
+            _ => Err(Error::UnexpectedStatusCode(
+                response.status().into(),
+                response.text().await.unwrap_or_default(),
+            )),
+        }
+    }
+
+    /// Retrieves the items of all pages of the given list endpoint, following the cursor
+    /// of each page until the last one. Empty pages are skipped.
+    pub(crate) async fn get_all_pages<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+    ) -> Result<Vec<T>, Error> {
+        let mut page = self.get_page::<T>(endpoint, None).await?;
+        let mut items = page.items;
+        while let Some(cursor) = page.next_cursor {
+            page = self.get_page::<T>(endpoint, Some(&cursor)).await?;
+            items.extend(page.items);
+        }
+        Ok(items)
+    }
+
+    /// Counts the items of the given list endpoint.
+    /// Uses the announced total if available, otherwise all pages are retrieved.
+    pub(crate) async fn count_items<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+    ) -> Result<u64, Error> {
+        let mut page = self.get_page::<T>(endpoint, None).await?;
+        if let Some(total) = page.total {
+            return Ok(total);
+        }
+        let mut count = page.items.len() as u64;
+        while let Some(cursor) = page.next_cursor {
+            page = self.get_page::<T>(endpoint, Some(&cursor)).await?;
+            count += page.items.len() as u64;
+        }
+        Ok(count)
+    }
+
+    /// Lazily yields the items of the given list endpoint, requesting the next page
+    /// only once all items of the current page have been consumed.
+    /// The stream ends after the first error.
+    pub(crate) fn stream_items<'a, T: DeserializeOwned + 'a>(
+        &'a self,
+        endpoint: String,
+    ) -> impl Stream<Item = Result<T, Error>> + 'a {
+        let state = StreamState {
+            endpoint,
+            items: Vec::new().into_iter(),
+            cursor: None,
+            done: false,
+        };
+        stream::unfold(state, move |mut state| async move {
+            loop {
+                if let Some(item) = state.items.next() {
+                    return Some((Ok(item), state));
+                }
+                if state.done {
+                    return None;
+                }
+                match self
+                    .get_page::<T>(&state.endpoint, state.cursor.as_deref())
+                    .await
+                {
+                    Ok(page) => {
+                        state.items = page.items.into_iter();
+                        state.done = page.next_cursor.is_none();
+                        state.cursor = page.next_cursor;
+                    }
+                    Err(error) => {
+                        state.done = true;
+                        return Some((Err(error), state));
+                    }
+                }
+            }
+        })
+    }
+}
+
+// Progress of a stream over the pages of a list endpoint.
+#[cfg(feature = "std")]
+struct StreamState<T> {
+    endpoint: String,
+    items: std::vec::IntoIter<T>,
+    cursor: Option<String>,
+    done: bool,
+}
+
+// Extracts the cursor of the next page from a header like
+// `<https://desec.io/api/v1/domains/?cursor=abc>; rel="next", <...>; rel="first"`
+#[cfg(feature = "std")]
+fn parse_next_cursor(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get("link")?.to_str().ok()?;
+    link.split(',')
+        .find(|link| link.contains("rel=\"next\""))
+        .and_then(|link| {
+            let url = &link[link.find('<')? + 1..link.find('>')?];
+            let query = &url[url.find('?')? + 1..];
+            query
+                .split('&')
+                .find_map(|parameter| parameter.strip_prefix("cursor="))
+                .map(str::to_string)
+        })
+}
+
+// Extracts the total count of items if announced by any of the known headers.
+#[cfg(feature = "std")]
+fn parse_total(headers: &HeaderMap) -> Option<u64> {
+    TOTAL_COUNT_HEADERS.iter().find_map(|name| {
+        headers
+            .get(*name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+    })
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::tests::{domain_json, mock_client, page_response, response, response_with_headers};
+
+    #[tokio::test]
+    async fn page_total_is_read_from_either_header() {
+        let body = format!("[{}]", domain_json("a.example"));
+        let (client, server) = mock_client(vec![
+            response_with_headers(200, &[("X-Pagination-Total", "1203")], &body),
+            response_with_headers(200, &[("X-Total-Count", " 7 ")], &body),
+            response(200, &body),
+        ])
+        .await;
+        let page = client.domain().get_domains_page(None).await.unwrap();
+        assert_eq!(page.total, Some(1203));
+        assert_eq!(page.items.len(), 1);
+        let page = client.domain().get_domains_page(Some("c1")).await.unwrap();
+        assert_eq!(page.total, Some(7));
+        let page = client.domain().get_domains_page(None).await.unwrap();
+        assert_eq!(page.total, None);
+        assert_eq!(page.next_cursor, None);
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/domains/ "));
+        assert!(requests[1].starts_with("GET /api/v1/domains/?cursor=c1 "));
+    }
+
+    #[tokio::test]
+    async fn count_uses_total_or_walks_all_pages() {
+        let (client, server) = mock_client(vec![
+            response_with_headers(200, &[("X-Total-Count", "1203")], "[]"),
+            page_response(&format!("[{}]", domain_json("a.example")), Some("c1")),
+            page_response(
+                &format!(
+                    "[{}, {}]",
+                    domain_json("b.example"),
+                    domain_json("c.example")
+                ),
+                None,
+            ),
+        ])
+        .await;
+        assert_eq!(client.domain().count_domains().await.unwrap(), 1203);
+        assert_eq!(client.domain().count_domains().await.unwrap(), 3);
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[2].starts_with("GET /api/v1/domains/?cursor=c1 "));
+    }
+
+    #[tokio::test]
+    async fn first_page_is_requested_with_cursor_if_listing_requires_it() {
+        let (client, server) = mock_client(vec![
+            response(
+                400,
+                r#"{"detail": "Pagination required. You can query up to 500 items at a time."}"#,
+            ),
+            page_response(&format!("[{}]", domain_json("a.example")), None),
+        ])
+        .await;
+        let page = client.domain().get_domains_page(None).await.unwrap();
+        assert_eq!(page.items[0].name, "a.example");
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/domains/ "));
+        assert!(requests[1].starts_with("GET /api/v1/domains/?cursor= "));
+    }
+}
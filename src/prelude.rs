@@ -0,0 +1,28 @@
+//! Re-exports of the commonly used types.
+//!
+//! ```
+//! use desec_api::prelude::*;
+//! ```
+
+#[cfg(feature = "std")]
+pub use crate::account::AccountClient;
+pub use crate::account::{AccountInformation, Captcha, CaptchaKind, Login};
+#[cfg(feature = "std")]
+pub use crate::domain::DomainClient;
+pub use crate::domain::{DNSSECKeyInfo, Domain, DsRecord, ZoneDrift};
+pub use crate::pagination::Page;
+pub use crate::rate_limit::{RateLimitInfo, RateLimitStatus};
+pub use crate::rrset::{
+    ModifiedRrset, NewRrset, RecordType, RecordValue, ResourceRecordSet, TtlPolicy, ZoneDiff,
+};
+#[cfg(feature = "std")]
+pub use crate::rrset::{ResourceRecordSetBuilder, RrsetClient, RrsetQuery};
+pub use crate::token::{Token, TokenPolicy};
+#[cfg(feature = "std")]
+pub use crate::token::{TokenBuilder, TokenClient, TokenPolicyBuilder};
+pub use crate::ErrorKind;
+#[cfg(feature = "std")]
+pub use crate::{
+    ApiErrorBody, Client, ClientBuilder, ClientConfig, Error, RedirectPolicy, RetryEvent,
+    RetryReason,
+};
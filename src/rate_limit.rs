@@ -0,0 +1,166 @@
+//! Introspection of the rate limit headers sent along with API responses.
+//!
+//! deSEC [throttles][reference] requests and answers with `429 Too Many Requests` once a limit is
+//! exceeded. If the API (or a proxy in front of it) also announces the current quota in the
+//! `X-RateLimit-*` or `RateLimit-*` headers, the values of the latest response are available via
+//! [`Client::last_rate_limit`][last_rate_limit], so bulk workflows can pace themselves before being throttled.
+//!
+//! [reference]: https://desec.readthedocs.io/en/latest/rate-limits.html
+//! [last_rate_limit]: ../struct.Client.html#method.last_rate_limit
+
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+#[cfg(feature = "std")]
+use crate::Client;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use reqwest::header::HeaderMap;
+
+/// Rate limit quota announced in the headers of a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Maximum number of requests in the current window
+    pub limit: Option<u64>,
+    /// Number of requests left in the current window
+    pub remaining: Option<u64>,
+    /// Reset of the current window as sent by the server, usually in seconds
+    pub reset: Option<u64>,
+}
+
+/// Details of a throttled request, carried by [`Error::RateLimited`][error].
+///
+/// [error]: ../enum.Error.html#variant.RateLimited
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// Time to wait before the request may be repeated
+    pub retry_after: Duration,
+    /// Raw value of the `Retry-After` header
+    pub retry_after_header: String,
+    /// Explanation of the API, e.g. `Request was throttled. Expected available in 5 seconds.`,
+    /// or the reason the client did not retry the request if the API sent none
+    pub detail: String,
+}
+
+#[cfg(feature = "std")]
+impl RateLimitInfo {
+    /// Creates the info from the wait time, the raw header value and the body of the response.
+    ///
+    /// The detail is taken from the `detail` field of a JSON body, falling back to the whole body
+    /// and to the given fallback for an empty body.
+    pub(crate) fn new(wait_seconds: u64, header: &str, body: &str, fallback: String) -> Self {
+        let detail = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|value| value.get("detail")?.as_str().map(str::to_string))
+            .or_else(|| Some(body.trim().to_string()).filter(|body| !body.is_empty()))
+            .unwrap_or(fallback);
+        RateLimitInfo {
+            retry_after: Duration::from_secs(wait_seconds),
+            retry_after_header: header.to_string(),
+            detail,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Client {
+    /// Returns the rate limit quota announced by the latest response which carried rate limit headers,
+    /// `None` if no response did so far.
+    ///
+    /// The status is shared by all clones of the client.
+    pub fn last_rate_limit(&self) -> Option<RateLimitStatus> {
+        *self
+            .last_rate_limit
+            .lock()
+            .expect("the rate limit lock is never poisoned")
+    }
+
+    /// Remembers the rate limit quota if the response headers announce one.
+    pub(crate) fn record_rate_limit(&self, headers: &HeaderMap) {
+        if let Some(status) = parse_rate_limit(headers) {
+            *self
+                .last_rate_limit
+                .lock()
+                .expect("the rate limit lock is never poisoned") = Some(status);
+        }
+    }
+}
+
+// Parses the rate limit headers, preferring the widespread X-RateLimit-* names over the
+// RateLimit-* names of the IETF draft.
+#[cfg(feature = "std")]
+fn parse_rate_limit(headers: &HeaderMap) -> Option<RateLimitStatus> {
+    let value = |name: &str| {
+        [format!("x-ratelimit-{name}"), format!("ratelimit-{name}")]
+            .iter()
+            .find_map(|header| {
+                headers
+                    .get(header.as_str())
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.trim().parse().ok())
+            })
+    };
+    let status = RateLimitStatus {
+        limit: value("limit"),
+        remaining: value("remaining"),
+        reset: value("reset"),
+    };
+    if status.limit.is_none() && status.remaining.is_none() && status.reset.is_none() {
+        None
+    } else {
+        Some(status)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::tests::{domain_json, mock_client, response, response_with_headers, rrset_json};
+
+    #[tokio::test]
+    async fn quota_of_latest_announcing_response_is_kept() {
+        let rrset = rrset_json("example.com", "www", "A", 3600, &["192.0.2.1"]);
+        let (client, server) = mock_client(vec![
+            response_with_headers(
+                201,
+                &[
+                    ("X-RateLimit-Limit", "10"),
+                    ("X-RateLimit-Remaining", "9"),
+                    ("RateLimit-Remaining", "1"),
+                ],
+                &rrset,
+            ),
+            response(200, &domain_json("example.com")),
+            response_with_headers(200, &[("RateLimit-Reset", " 30 ")], &rrset),
+        ])
+        .await;
+        assert_eq!(client.last_rate_limit(), None);
+        let records = ["192.0.2.1".to_string()];
+        client
+            .rrset()
+            .create_rrset("example.com", Some("www"), "A", 3600, &records)
+            .await
+            .unwrap();
+        let announced = RateLimitStatus {
+            limit: Some(10),
+            remaining: Some(9),
+            reset: None,
+        };
+        assert_eq!(client.last_rate_limit(), Some(announced));
+        client.domain().get_domain("example.com").await.unwrap();
+        assert_eq!(client.clone().last_rate_limit(), Some(announced));
+        client
+            .rrset()
+            .get_rrset("example.com", Some("www"), "A")
+            .await
+            .unwrap();
+        assert_eq!(
+            client.last_rate_limit(),
+            Some(RateLimitStatus {
+                limit: None,
+                remaining: None,
+                reset: Some(30),
+            })
+        );
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,380 @@
+//! Typed record contents for record types which are error-prone to write by hand.
+//!
+//! The types validate their fields on construction and convert from and to the
+//! presentation format used in the `records` of a [`ResourceRecordSet`][rrset].
+//!
+//! ```
+//! use desec_api::records::Sshfp;
+//!
+//! let sshfp = Sshfp::new(4, 2, "123456789abcdef67890123456789abcdef67890123456789abcdef123456789").unwrap();
+//! assert_eq!(sshfp.to_string(), "4 2 123456789abcdef67890123456789abcdef67890123456789abcdef123456789");
+//! ```
+//!
+//! [rrset]: ../rrset/struct.ResourceRecordSet.html
+
+use crate::Error;
+use core::fmt;
+use core::str::FromStr;
+
+/// Content of an [`SSHFP`][reference] record.
+///
+/// [reference]: https://www.rfc-editor.org/rfc/rfc4255
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sshfp {
+    algorithm: u8,
+    fp_type: u8,
+    fingerprint: String,
+}
+
+impl Sshfp {
+    /// Creates an SSHFP record content.
+    ///
+    /// * `algorithm`: 1 (RSA), 2 (DSA), 3 (ECDSA), 4 (Ed25519) or 6 (Ed448)
+    /// * `fp_type`: 1 (SHA-1) or 2 (SHA-256)
+    /// * `fingerprint`: hex encoded fingerprint matching the length of `fp_type`
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if any of the fields is invalid.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn new(algorithm: u8, fp_type: u8, fingerprint: &str) -> Result<Self, Error> {
+        if !matches!(algorithm, 1..=4 | 6) {
+            return Err(Error::InvalidInput(format!(
+                "unknown SSHFP algorithm {algorithm}"
+            )));
+        }
+        let digest_length = match fp_type {
+            1 => 20,
+            2 => 32,
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "unknown SSHFP fingerprint type {fp_type}"
+                )))
+            }
+        };
+        if fingerprint.len() != digest_length * 2
+            || !fingerprint.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(Error::InvalidInput(format!(
+                "SSHFP fingerprint of type {fp_type} must be {digest_length} hex encoded bytes"
+            )));
+        }
+        Ok(Sshfp {
+            algorithm,
+            fp_type,
+            fingerprint: fingerprint.to_ascii_lowercase(),
+        })
+    }
+
+    /// Returns the algorithm of the key.
+    pub fn algorithm(&self) -> u8 {
+        self.algorithm
+    }
+
+    /// Returns the type of the fingerprint.
+    pub fn fp_type(&self) -> u8 {
+        self.fp_type
+    }
+
+    /// Returns the hex encoded fingerprint in lowercase.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+}
+
+impl FromStr for Sshfp {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        match fields.as_slice() {
+            [algorithm, fp_type, fingerprint] => Sshfp::new(
+                parse_number(algorithm, "SSHFP algorithm")?,
+                parse_number(fp_type, "SSHFP fingerprint type")?,
+                fingerprint,
+            ),
+            _ => Err(Error::InvalidInput(format!(
+                "SSHFP record must have 3 fields: {s}"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Sshfp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.algorithm, self.fp_type, self.fingerprint
+        )
+    }
+}
+
+/// Content of a [`NAPTR`][reference] record.
+///
+/// [reference]: https://www.rfc-editor.org/rfc/rfc3403
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Naptr {
+    order: u16,
+    preference: u16,
+    flags: String,
+    service: String,
+    regexp: String,
+    replacement: String,
+}
+
+impl Naptr {
+    /// Creates a NAPTR record content.
+    ///
+    /// Either `regexp` must be empty or `replacement` must be `.`, as only one of them may be used.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if
+    /// - `flags` contains other characters than ASCII letters and digits
+    /// - `replacement` is not a fully qualified domain name ending with a dot
+    /// - both `regexp` and `replacement` are given
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn new(
+        order: u16,
+        preference: u16,
+        flags: &str,
+        service: &str,
+        regexp: &str,
+        replacement: &str,
+    ) -> Result<Self, Error> {
+        if !flags.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(Error::InvalidInput(format!(
+                "NAPTR flags must be alphanumeric: {flags}"
+            )));
+        }
+        if !replacement.ends_with('.') {
+            return Err(Error::InvalidInput(format!(
+                "NAPTR replacement must be a fully qualified domain name ending with a dot: {replacement}"
+            )));
+        }
+        if !regexp.is_empty() && replacement != "." {
+            return Err(Error::InvalidInput(
+                "NAPTR record must not have both a regexp and a replacement".to_string(),
+            ));
+        }
+        Ok(Naptr {
+            order,
+            preference,
+            flags: flags.to_string(),
+            service: service.to_string(),
+            regexp: regexp.to_string(),
+            replacement: replacement.to_string(),
+        })
+    }
+
+    /// Returns the order in which the records must be processed.
+    pub fn order(&self) -> u16 {
+        self.order
+    }
+
+    /// Returns the preference among records with equal order.
+    pub fn preference(&self) -> u16 {
+        self.preference
+    }
+
+    /// Returns the flags.
+    pub fn flags(&self) -> &str {
+        &self.flags
+    }
+
+    /// Returns the service parameters.
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+
+    /// Returns the substitution expression.
+    pub fn regexp(&self) -> &str {
+        &self.regexp
+    }
+
+    /// Returns the replacement domain name.
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+}
+
+impl FromStr for Naptr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = split_fields(s)?;
+        match fields.as_slice() {
+            [order, preference, flags, service, regexp, replacement] => Naptr::new(
+                parse_number(order, "NAPTR order")?,
+                parse_number(preference, "NAPTR preference")?,
+                flags,
+                service,
+                regexp,
+                replacement,
+            ),
+            _ => Err(Error::InvalidInput(format!(
+                "NAPTR record must have 6 fields: {s}"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Naptr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.order,
+            self.preference,
+            quote(&self.flags),
+            quote(&self.service),
+            quote(&self.regexp),
+            self.replacement
+        )
+    }
+}
+
+fn parse_number<T: FromStr>(value: &str, field: &str) -> Result<T, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::InvalidInput(format!("invalid {field}: {value}")))
+}
+
+// Quotes a character-string, escaping backslashes and double-quotes.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Splits record content in presentation format into its fields.
+// Quoted character-strings are unquoted and unescaped.
+pub(crate) fn split_fields(content: &str) -> Result<Vec<String>, Error> {
+    let unterminated = || Error::InvalidInput(format!("unterminated quoted string in {content}"));
+    let mut fields = Vec::new();
+    let mut chars = content.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut field = String::new();
+            loop {
+                match chars.next().ok_or_else(unterminated)? {
+                    '"' => break,
+                    '\\' => field.push(chars.next().ok_or_else(unterminated)?),
+                    c => field.push(c),
+                }
+            }
+            fields.push(field);
+        } else {
+            let mut field = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                field.push(c);
+                chars.next();
+            }
+            fields.push(field);
+        }
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA1: &str = "0123456789abcdef0123456789abcdef01234567";
+    const SHA256: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn sshfp_fields_are_validated() {
+        for algorithm in [1, 2, 3, 4, 6] {
+            assert!(Sshfp::new(algorithm, 1, SHA1).is_ok());
+        }
+        for algorithm in [0, 5, 7] {
+            assert!(matches!(
+                Sshfp::new(algorithm, 1, SHA1),
+                Err(Error::InvalidInput(_))
+            ));
+        }
+        for fp_type in [0, 3] {
+            assert!(matches!(
+                Sshfp::new(4, fp_type, SHA256),
+                Err(Error::InvalidInput(_))
+            ));
+        }
+        // The fingerprint length must match the fingerprint type
+        assert!(Sshfp::new(4, 1, SHA1).is_ok());
+        assert!(Sshfp::new(4, 2, SHA256).is_ok());
+        assert!(Sshfp::new(4, 1, SHA256).is_err());
+        assert!(Sshfp::new(4, 2, SHA1).is_err());
+        assert!(Sshfp::new(4, 1, &SHA1.replace('a', "g")).is_err());
+        assert_eq!(
+            Sshfp::new(4, 1, &SHA1.to_ascii_uppercase())
+                .unwrap()
+                .fingerprint(),
+            SHA1
+        );
+    }
+
+    #[test]
+    fn sshfp_roundtrips_through_presentation_format() {
+        let sshfp: Sshfp = format!("3  2 {SHA256}").parse().unwrap();
+        assert_eq!((sshfp.algorithm(), sshfp.fp_type()), (3, 2));
+        assert_eq!(sshfp.to_string().parse::<Sshfp>().unwrap(), sshfp);
+        assert!("3 2".parse::<Sshfp>().is_err());
+        assert!(format!("x 2 {SHA256}").parse::<Sshfp>().is_err());
+    }
+
+    #[test]
+    fn naptr_fields_are_validated() {
+        assert!(Naptr::new(100, 10, "U", "E2U+sip", "!^.*$!sip:info@example.com!", ".").is_ok());
+        assert!(Naptr::new(100, 10, "S", "SIP+D2U", "", "_sip._udp.example.com.").is_ok());
+        // Only one of regexp and replacement may be used
+        assert!(matches!(
+            Naptr::new(
+                100,
+                10,
+                "U",
+                "E2U+sip",
+                "!^.*$!sip:info@example.com!",
+                "example.com."
+            ),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            Naptr::new(100, 10, "S", "SIP+D2U", "", "_sip._udp.example.com"),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            Naptr::new(100, 10, "U+", "E2U+sip", "", "."),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn naptr_roundtrips_through_presentation_format() {
+        let naptr = Naptr::new(100, 10, "U", "E2U+sip", "!^\\\\(.*)$!\"quoted\"!", ".").unwrap();
+        let content = naptr.to_string();
+        assert_eq!(
+            content,
+            "100 10 \"U\" \"E2U+sip\" \"!^\\\\\\\\(.*)$!\\\"quoted\\\"!\" ."
+        );
+        assert_eq!(content.parse::<Naptr>().unwrap(), naptr);
+        assert!("100 10 \"U\" \"E2U+sip\" \"\" .  extra"
+            .parse::<Naptr>()
+            .is_err());
+    }
+
+    #[test]
+    fn unterminated_quoted_strings_are_rejected() {
+        assert_eq!(split_fields("a \"b c\" \"\"").unwrap(), ["a", "b c", ""]);
+        for content in ["\"abc", "\"abc\\", "\"abc\\\""] {
+            assert!(matches!(split_fields(content), Err(Error::InvalidInput(_))));
+        }
+    }
+}
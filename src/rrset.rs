@@ -1,13 +1,35 @@
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+#[cfg(feature = "std")]
+use crate::domain::Domain;
+#[cfg(feature = "std")]
+use crate::pagination::Page;
+#[cfg(feature = "std")]
 use crate::{Client, Error};
+use alloc::collections::btree_map::{BTreeMap, Entry};
+use core::convert::Infallible;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use futures_util::future;
+#[cfg(feature = "std")]
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
+#[cfg(feature = "std")]
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use serde_json::json;
+#[cfg(feature = "std")]
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 /// An asynchronous client to create, update or delete so-called Resource Record Sets (RRsets).
+#[cfg(feature = "std")]
 pub struct RrsetClient<'a> {
     pub(crate) client: &'a crate::Client,
 }
 
+#[cfg(feature = "std")]
 impl<'a> Client {
     /// Returns a wrapping client for the Resource Record Sets (RRsets) API.
     pub fn rrset(&'a self) -> RrsetClient<'a> {
@@ -24,82 +46,1416 @@ pub struct ResourceRecordSet {
     pub subname: Option<String>,
     pub name: String,
     #[serde(rename = "type")]
-    pub rrset_type: String,
+    pub rrset_type: RecordType,
     pub ttl: u64,
     pub records: Vec<String>,
     pub touched: String,
 }
 
+#[cfg(feature = "time")]
+impl ResourceRecordSet {
+    /// Returns the time the RRset has been created.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if `created` is not a valid timestamp.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn created_at(&self) -> Result<time::OffsetDateTime, Error> {
+        crate::timestamp::parse_timestamp(&self.created)
+    }
+
+    /// Returns the time of the last modification of the RRset.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if `touched` is not a valid timestamp.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn touched_at(&self) -> Result<time::OffsetDateTime, Error> {
+        crate::timestamp::parse_timestamp(&self.touched)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ResourceRecordSet {
+    /// Returns the type of the RRset.
+    pub fn record_type(&self) -> RecordType {
+        self.rrset_type.clone()
+    }
+
+    /// Returns a [`ResourceRecordSetBuilder`][builder] to assemble an RRset.
+    ///
+    /// [builder]: struct.ResourceRecordSetBuilder.html
+    pub fn builder() -> ResourceRecordSetBuilder {
+        ResourceRecordSetBuilder::new()
+    }
+
+    /// Returns an A RRset with the given addresses, `None` as subname selects the zone apex.
+    ///
+    /// Only subname, type, TTL and records are set, so the RRset is meant to be passed to
+    /// [`RrsetClient::patch_rrsets`][patch_rrsets] or to set the `domain` before using it otherwise.
+    ///
+    /// [patch_rrsets]: struct.RrsetClient.html#method.patch_rrsets
+    pub fn a(subname: Option<&str>, ttl: u64, addresses: &[Ipv4Addr]) -> ResourceRecordSet {
+        Self::with_addresses(subname, RecordType::A, ttl, addresses)
+    }
+
+    /// Returns an AAAA RRset with the given addresses, `None` as subname selects the zone apex.
+    ///
+    /// Only subname, type, TTL and records are set, see [`ResourceRecordSet::a`].
+    pub fn aaaa(subname: Option<&str>, ttl: u64, addresses: &[Ipv6Addr]) -> ResourceRecordSet {
+        Self::with_addresses(subname, RecordType::AAAA, ttl, addresses)
+    }
+
+    fn with_addresses<A: fmt::Display>(
+        subname: Option<&str>,
+        rrset_type: RecordType,
+        ttl: u64,
+        addresses: &[A],
+    ) -> ResourceRecordSet {
+        ResourceRecordSet {
+            subname: Some(subname.unwrap_or_default().to_string()),
+            rrset_type,
+            ttl,
+            records: addresses.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Checks that the TTL of the RRset is not below the minimum TTL of the given domain.
+    ///
+    /// deSEC rejects lower TTLs, e.g. below 3600 seconds for domains without an increased limit.
+    /// This opt-in check allows to fail with a clear message before sending a request.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if the TTL is below `minimum_ttl` of the domain.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn validate_ttl(&self, domain: &Domain) -> Result<(), Error> {
+        if self.ttl < u64::from(domain.minimum_ttl) {
+            return Err(Error::InvalidInput(format!(
+                "TTL {} of RRset {}/{} is below the minimum TTL {} of domain {}",
+                self.ttl,
+                self.subname.as_deref().unwrap_or_default(),
+                self.rrset_type,
+                domain.minimum_ttl,
+                domain.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Parses the records of an A or AAAA RRset into IP addresses.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if a record is not a valid IP address,
+    /// or an IPv6 address in an A or an IPv4 address in an AAAA RRset.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn parsed_addresses(&self) -> Result<Vec<IpAddr>, Error> {
+        let record_type = self.record_type();
+        self.records
+            .iter()
+            .map(|record| {
+                let address: IpAddr = record.trim().parse().map_err(|_| {
+                    Error::InvalidInput(format!("record {record:?} is not an IP address"))
+                })?;
+                match (&record_type, address) {
+                    (RecordType::A, IpAddr::V6(_)) | (RecordType::AAAA, IpAddr::V4(_)) => {
+                        Err(Error::InvalidInput(format!(
+                            "record {record:?} does not match the type {record_type}"
+                        )))
+                    }
+                    _ => Ok(address),
+                }
+            })
+            .collect()
+    }
+
+    /// Merges the records of two RRsets of the same subname and type into a new RRset.
+    ///
+    /// The result is a copy of `self` containing the union of the records of both RRsets,
+    /// with the TTL chosen by `ttl_policy`. Records are deduplicated by exact textual comparison,
+    /// keeping the first occurrence: all records of `self` come first (in their original order),
+    /// followed by the records of `other` which are not contained in `self`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if the subnames or types of both RRsets differ.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn merge(&self, other: &Self, ttl_policy: TtlPolicy) -> Result<ResourceRecordSet, Error> {
+        let subname = self.subname.as_deref().unwrap_or_default();
+        let other_subname = other.subname.as_deref().unwrap_or_default();
+        if subname != other_subname || self.rrset_type != other.rrset_type {
+            return Err(Error::InvalidInput(format!(
+                "cannot merge RRset {}/{} with {}/{}",
+                subname, self.rrset_type, other_subname, other.rrset_type
+            )));
+        }
+        let mut records: Vec<String> = Vec::with_capacity(self.records.len() + other.records.len());
+        for record in self.records.iter().chain(other.records.iter()) {
+            if !records.contains(record) {
+                records.push(record.clone());
+            }
+        }
+        let ttl = match ttl_policy {
+            TtlPolicy::Min => self.ttl.min(other.ttl),
+            TtlPolicy::Max => self.ttl.max(other.ttl),
+            TtlPolicy::Explicit(ttl) => ttl,
+        };
+        Ok(ResourceRecordSet {
+            ttl,
+            records,
+            ..self.clone()
+        })
+    }
+}
+
+impl ResourceRecordSet {
+    /// Returns the type of the RRset as string, as `rrset_type` was a `String` before.
+    #[deprecated(note = "use the `rrset_type` field, which is a `RecordType`")]
+    pub fn rrset_type_str(&self) -> &str {
+        self.rrset_type.as_str()
+    }
+
+    /// Renders the RRset in RFC 1035 presentation format relative to the given origin, one line per record.
+    ///
+    /// The owner name is built from the subname and `origin`, so the zone apex renders as the origin itself.
+    /// Hostname targets of CNAME, DNAME, NS, PTR, MX and SRV records are normalized to fully qualified names
+    /// with a trailing dot, relative targets are qualified with the origin.
+    ///
+    /// ```
+    /// use desec_api::rrset::{RecordType, ResourceRecordSet};
+    ///
+    /// let rrset = ResourceRecordSet {
+    ///     subname: Some("www".to_string()),
+    ///     rrset_type: RecordType::CNAME,
+    ///     ttl: 3600,
+    ///     records: vec!["web".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     rrset.to_presentation("example.com"),
+    ///     "www.example.com.\t3600\tIN\tCNAME\tweb.example.com.\n"
+    /// );
+    /// ```
+    pub fn to_presentation(&self, origin: &str) -> String {
+        let origin = fully_qualify(origin, ".");
+        let owner = match self.subname.as_deref().unwrap_or_default() {
+            "" | "@" => origin.clone(),
+            subname => fully_qualify(subname, &origin),
+        };
+        let rrset_type = self.rrset_type.to_string();
+        self.records
+            .iter()
+            .map(|record| {
+                let record = qualify_target(&rrset_type, record, &origin);
+                format!("{owner}\t{}\tIN\t{rrset_type}\t{record}\n", self.ttl)
+            })
+            .collect()
+    }
+}
+
+/// An RRset to be created, e.g. with [`RrsetClient::create_rrsets`][create_rrsets].
+///
+/// Serializes to the representation expected by the API.
+///
+/// [create_rrsets]: struct.RrsetClient.html#method.create_rrsets
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewRrset {
+    /// Subname of the RRset, empty for the zone apex
+    pub subname: String,
+    #[serde(rename = "type")]
+    pub rrset_type: RecordType,
+    pub ttl: u64,
+    /// Records in presentation format, the content of TXT records needs to be wrapped in double-quotes
+    pub records: Vec<String>,
+}
+
+impl NewRrset {
+    /// Creates a new RRset, `None` as subname selects the zone apex.
+    pub fn new(
+        subname: Option<&str>,
+        rrset_type: impl Into<RecordType>,
+        ttl: u64,
+        records: Vec<String>,
+    ) -> Self {
+        NewRrset {
+            subname: subname.unwrap_or_default().to_string(),
+            rrset_type: rrset_type.into(),
+            ttl,
+            records,
+        }
+    }
+}
+
+/// Builder to assemble a [`ResourceRecordSet`][rrset], e.g. to pass it to
+/// [`RrsetClient::patch_rrset_from`][patch_rrset_from].
+///
+/// Domain, type and TTL are required, the subname defaults to the zone apex.
+///
+/// ```
+/// use desec_api::rrset::ResourceRecordSet;
+///
+/// let rrset = ResourceRecordSet::builder()
+///     .domain("example.com")
+///     .subname("www")
+///     .rrset_type("A")
+///     .ttl(3600)
+///     .add_record("192.0.2.1".to_string())
+///     .build()
+///     .unwrap();
+/// assert_eq!(rrset.name, "www.example.com.");
+/// ```
+///
+/// [rrset]: struct.ResourceRecordSet.html
+/// [patch_rrset_from]: struct.RrsetClient.html#method.patch_rrset_from
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct ResourceRecordSetBuilder {
+    domain: Option<String>,
+    subname: String,
+    rrset_type: Option<RecordType>,
+    ttl: Option<u64>,
+    records: Vec<String>,
+}
+
+#[cfg(feature = "std")]
+impl ResourceRecordSetBuilder {
+    /// Creates a new builder for an RRset at the zone apex without records.
+    pub fn new() -> Self {
+        ResourceRecordSetBuilder::default()
+    }
+
+    /// Sets the domain (zone) of the RRset.
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domain = Some(domain.to_string());
+        self
+    }
+
+    /// Sets the subname of the RRset, an empty subname or `@` select the zone apex.
+    pub fn subname(mut self, subname: &str) -> Self {
+        self.subname = bulk_subname(Some(subname)).to_string();
+        self
+    }
+
+    /// Sets the type of the RRset.
+    pub fn rrset_type(mut self, rrset_type: impl Into<RecordType>) -> Self {
+        self.rrset_type = Some(rrset_type.into());
+        self
+    }
+
+    /// Sets the TTL of the RRset.
+    pub fn ttl(mut self, ttl: u64) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the records of the RRset, replacing previously added records.
+    pub fn records(mut self, records: Vec<String>) -> Self {
+        self.records = records;
+        self
+    }
+
+    /// Adds a record to the RRset, the content of TXT records needs to be wrapped in double-quotes.
+    pub fn add_record(mut self, record: String) -> Self {
+        self.records.push(record);
+        self
+    }
+
+    /// Returns the assembled RRset.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if the domain, type or TTL is not set.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn build(self) -> Result<ResourceRecordSet, Error> {
+        let missing = |field: &str| Error::InvalidInput(format!("RRset is missing the {field}"));
+        let domain = self.domain.ok_or_else(|| missing("domain"))?;
+        let rrset_type = self.rrset_type.ok_or_else(|| missing("type"))?;
+        let ttl = self.ttl.ok_or_else(|| missing("TTL"))?;
+        let name = if self.subname.is_empty() {
+            format!("{}.", domain.trim_end_matches('.'))
+        } else {
+            format!("{}.{}.", self.subname, domain.trim_end_matches('.'))
+        };
+        Ok(ResourceRecordSet {
+            domain,
+            subname: Some(self.subname),
+            name,
+            rrset_type,
+            ttl,
+            records: self.records,
+            ..Default::default()
+        })
+    }
+}
+
+impl fmt::Display for ResourceRecordSet {
+    /// Renders the RRset in presentation format using the fully qualified name returned by the API,
+    /// one line per record with the records as stored.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.records {
+            writeln!(
+                f,
+                "{}\t{}\tIN\t{}\t{}",
+                self.name, self.ttl, self.rrset_type, record
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// An RRset whose TTL or records differ between two states of a zone.
+#[derive(Debug, Clone)]
+pub struct ModifiedRrset {
+    /// The RRset in the former state
+    pub before: ResourceRecordSet,
+    /// The RRset in the latter state
+    pub after: ResourceRecordSet,
+}
+
+/// Changes to bring a zone to a desired state, see [`diff`] and [`RrsetClient::sync_zone`][sync_zone].
+///
+/// [sync_zone]: struct.RrsetClient.html#method.sync_zone
+#[derive(Debug, Clone, Default)]
+pub struct ZoneDiff {
+    /// RRsets which only exist in the desired state
+    pub to_create: Vec<ResourceRecordSet>,
+    /// RRsets whose TTL or records differ, `before` is the current and `after` the desired state
+    pub to_update: Vec<ModifiedRrset>,
+    /// RRsets which only exist in the current state
+    pub to_delete: Vec<ResourceRecordSet>,
+}
+
+impl ZoneDiff {
+    /// Returns whether the zone already is in the desired state.
+    pub fn is_empty(&self) -> bool {
+        self.to_create.is_empty() && self.to_update.is_empty() && self.to_delete.is_empty()
+    }
+}
+
+// Plans the changes from the current to the desired RRsets of the zone `domain`.
+// Subnames and hostname targets of both sides are normalized and desired TTLs are raised to the
+// minimum TTL of the zone. Records managed by deSEC are left alone and the apex NS RRset is only
+// changed if it is part of the desired state.
+#[cfg(feature = "std")]
+fn plan_sync(
+    domain: &str,
+    minimum_ttl: u64,
+    current: &[ResourceRecordSet],
+    desired: &[ResourceRecordSet],
+) -> ZoneDiff {
+    let origin = format!("{}.", domain.trim_end_matches('.'));
+    let normalize = |rrset: &ResourceRecordSet| {
+        let subname = bulk_subname(rrset.subname.as_deref()).to_string();
+        let records = rrset
+            .records
+            .iter()
+            .map(|record| qualify_target(rrset.rrset_type.as_str(), record, &origin))
+            .collect();
+        ResourceRecordSet {
+            domain: domain.to_string(),
+            subname: Some(subname),
+            records,
+            ..rrset.clone()
+        }
+    };
+    let is_apex_ns = |rrset: &ResourceRecordSet| {
+        rrset.subname.as_deref() == Some("") && rrset.rrset_type == RecordType::NS
+    };
+    let desired: Vec<_> = desired
+        .iter()
+        .map(normalize)
+        .map(|rrset| ResourceRecordSet {
+            ttl: rrset.ttl.max(minimum_ttl),
+            ..rrset
+        })
+        .collect();
+    let keep_apex_ns = !desired.iter().any(is_apex_ns);
+    let current: Vec<_> = current
+        .iter()
+        .map(normalize)
+        .filter(|rrset| {
+            !crate::domain::is_managed(&rrset.rrset_type) && (!keep_apex_ns || !is_apex_ns(rrset))
+        })
+        .collect();
+    diff(&current, &desired)
+}
+
+/// Compares the current and the desired RRsets of a zone, e.g. to preview changes.
+///
+/// RRsets are keyed by subname and type, where `None`, `""` and `"@"` all denote the zone apex and
+/// types are compared case-insensitively. RRsets are compared by TTL and their sorted set of records,
+/// ignoring whitespace outside of quoted character-strings. Server-assigned fields like `created`
+/// and `touched` are ignored.
+///
+/// RRsets of the same subname and type given more than once on either side are merged into one,
+/// containing the records of all of them with the lowest of their TTLs.
+///
+/// ```
+/// use desec_api::rrset::{diff, RecordType, ResourceRecordSet};
+///
+/// let rrset = |ttl| ResourceRecordSet {
+///     subname: Some("www".to_string()),
+///     rrset_type: RecordType::A,
+///     ttl,
+///     records: vec!["192.0.2.1".to_string()],
+///     ..Default::default()
+/// };
+/// let current = [rrset(3600)];
+/// let desired = [rrset(300)];
+/// let diff = diff(&current, &desired);
+/// assert_eq!(diff.to_update.len(), 1);
+/// assert!(diff.to_create.is_empty() && diff.to_delete.is_empty());
+/// ```
+pub fn diff(current: &[ResourceRecordSet], desired: &[ResourceRecordSet]) -> ZoneDiff {
+    let records = |rrset: &ResourceRecordSet| {
+        let mut records: Vec<String> = rrset
+            .records
+            .iter()
+            .map(|record| normalize_whitespace(record))
+            .collect();
+        records.sort();
+        records.dedup();
+        records
+    };
+    let (current, current_index) = index_rrsets(current);
+    let (desired, desired_index) = index_rrsets(desired);
+    let mut to_create = Vec::new();
+    let mut to_update = Vec::new();
+    for (key, new) in &desired {
+        match current_index.get(key) {
+            Some(&index) => {
+                let old = &current[index].1;
+                if old.ttl != new.ttl || records(old) != records(new) {
+                    to_update.push(ModifiedRrset {
+                        before: old.clone(),
+                        after: new.clone(),
+                    });
+                }
+            }
+            None => to_create.push(new.clone()),
+        }
+    }
+    let to_delete = current
+        .into_iter()
+        .filter(|(key, _)| !desired_index.contains_key(key))
+        .map(|(_, old)| old)
+        .collect();
+    ZoneDiff {
+        to_create,
+        to_update,
+        to_delete,
+    }
+}
+
+// Subname and uppercase type identifying an RRset within a zone.
+type DiffKey = (String, String);
+
+// Merges RRsets of the same subname and type in the order of their first occurrence
+// and indexes them by their key.
+fn index_rrsets(
+    rrsets: &[ResourceRecordSet],
+) -> (Vec<(DiffKey, ResourceRecordSet)>, BTreeMap<DiffKey, usize>) {
+    let mut merged: Vec<(DiffKey, ResourceRecordSet)> = Vec::with_capacity(rrsets.len());
+    let mut index: BTreeMap<DiffKey, usize> = BTreeMap::new();
+    for rrset in rrsets {
+        let key = (
+            bulk_subname(rrset.subname.as_deref()).to_string(),
+            rrset.rrset_type.to_string(),
+        );
+        match index.entry(key) {
+            Entry::Occupied(entry) => {
+                let existing = &mut merged[*entry.get()].1;
+                existing.ttl = existing.ttl.min(rrset.ttl);
+                for record in &rrset.records {
+                    if !existing.records.contains(record) {
+                        existing.records.push(record.clone());
+                    }
+                }
+            }
+            Entry::Vacant(entry) => {
+                merged.push((entry.key().clone(), rrset.clone()));
+                entry.insert(merged.len() - 1);
+            }
+        }
+    }
+    (merged, index)
+}
+
+// Collapses runs of whitespace to a single space and trims the record,
+// leaving quoted character-strings untouched.
+fn normalize_whitespace(record: &str) -> String {
+    let mut normalized = String::with_capacity(record.len());
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut space = false;
+    for c in record.chars() {
+        if quoted {
+            normalized.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                quoted = false;
+            }
+        } else if c.is_whitespace() {
+            space = !normalized.is_empty();
+        } else {
+            if space {
+                normalized.push(' ');
+                space = false;
+            }
+            normalized.push(c);
+            quoted = c == '"';
+        }
+    }
+    normalized
+}
+
+// Qualifies the hostname target of a record with the fully qualified origin,
+// records of types without a hostname target are returned unchanged.
+pub(crate) fn qualify_target(rrset_type: &str, record: &str, origin: &str) -> String {
+    // Index of the field holding a hostname target, if any
+    let target_field = match rrset_type.to_ascii_uppercase().as_str() {
+        "CNAME" | "DNAME" | "NS" | "PTR" => 0,
+        "MX" => 1,
+        "SRV" => 3,
+        _ => return record.to_string(),
+    };
+    record
+        .split_whitespace()
+        .enumerate()
+        .map(|(i, field)| {
+            if i == target_field {
+                fully_qualify(field, origin)
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Qualifies a (possibly relative) name with the origin, which must be fully qualified itself.
+pub(crate) fn fully_qualify(name: &str, origin: &str) -> String {
+    if name == "@" {
+        origin.to_string()
+    } else if name.ends_with('.') {
+        name.to_string()
+    } else if origin == "." {
+        format!("{name}.")
+    } else {
+        format!("{name}.{origin}")
+    }
+}
+
+/// Determines the TTL of the RRset resulting from [`ResourceRecordSet::merge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TtlPolicy {
+    /// Use the lower TTL of both RRsets
+    Min,
+    /// Use the higher TTL of both RRsets
+    Max,
+    /// Use the given TTL
+    Explicit(u64),
+}
+
+/// Type of a DNS resource record set.
+///
+/// Parsing via [`FromStr`][fromstr] is case-insensitive, so `"a"`, `"Aaaa"` and `"txt"` are accepted.
+/// [`Display`][display] always renders the canonical uppercase name.
+/// Types without a dedicated variant are kept as [`RecordType::Other`] with the name as given.
+/// Types compare equal regardless of their spelling, so `Other("svcb")` equals `Other("SVCB")`.
+///
+/// [fromstr]: https://doc.rust-lang.org/std/str/trait.FromStr.html
+/// [display]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone)]
+pub enum RecordType {
+    A,
+    AAAA,
+    CNAME,
+    MX,
+    TXT,
+    NS,
+    SRV,
+    CAA,
+    TLSA,
+    SSHFP,
+    PTR,
+    Other(String),
+}
+
+/// Record types which can be managed via the deSEC API, see the [list of supported types][reference].
+///
+/// [reference]: https://desec.readthedocs.io/en/latest/dns/rrsets.html#supported-types
+pub const DESEC_SUPPORTED_TYPES: [&str; 37] = [
+    "A",
+    "AAAA",
+    "AFSDB",
+    "APL",
+    "CAA",
+    "CDNSKEY",
+    "CDS",
+    "CERT",
+    "CNAME",
+    "DHCID",
+    "DNAME",
+    "DNSKEY",
+    "DS",
+    "EUI48",
+    "EUI64",
+    "HINFO",
+    "HTTPS",
+    "KX",
+    "L32",
+    "L64",
+    "LOC",
+    "LP",
+    "MX",
+    "NAPTR",
+    "NID",
+    "NS",
+    "OPENPGPKEY",
+    "PTR",
+    "RP",
+    "SMIMEA",
+    "SPF",
+    "SRV",
+    "SSHFP",
+    "SVCB",
+    "TLSA",
+    "TXT",
+    "URI",
+];
+
+/// Record types which are known to be rejected by deSEC, as they are managed by deSEC itself
+/// (SOA, DNSSEC signatures), are obsolete or are no record types at all but query types.
+const DESEC_UNSUPPORTED_TYPES: [&str; 21] = [
+    "SOA",
+    "RRSIG",
+    "NSEC",
+    "NSEC3",
+    "NSEC3PARAM",
+    "ALIAS",
+    "ANAME",
+    "DLV",
+    "OPT",
+    "TSIG",
+    "TKEY",
+    "TA",
+    "AXFR",
+    "IXFR",
+    "ANY",
+    "MAILA",
+    "MAILB",
+    "MD",
+    "MF",
+    "NULL",
+    "WKS",
+];
+
+/// Parses a zonefile in BIND format, e.g. as exported by [`DomainClient::get_zonefile`][get_zonefile],
+/// into RRsets.
+///
+/// The zone is taken from the first `$ORIGIN` directive or else the owner of the SOA record.
+/// `$TTL` directives, multi-line records in parentheses, comments and the apex `@` are supported.
+/// Only `domain`, `subname`, `name`, `rrset_type`, `ttl` and `records` are set, the fields
+/// assigned by the server like `created` and `touched` are left empty. RRsets of the types
+/// managed by deSEC, e.g. SOA and DNSKEY, are kept.
+///
+/// # Errors
+///
+/// This function fails with [`Error::InvalidInput`][error] if the zone cannot be determined or
+/// a line is malformed, naming the line.
+///
+/// [get_zonefile]: ../domain/struct.DomainClient.html#method.get_zonefile
+/// [error]: ../enum.Error.html
+#[cfg(feature = "std")]
+pub fn parse_zonefile(zonefile: &str) -> Result<Vec<ResourceRecordSet>, Error> {
+    let zone = crate::zonefile::detect_zone(zonefile).ok_or_else(|| {
+        Error::InvalidInput(
+            "cannot determine the zone, the zonefile has neither $ORIGIN nor an SOA record"
+                .to_string(),
+        )
+    })?;
+    crate::zonefile::parse(&zone, zonefile)
+}
+
+/// Renders RRsets as a zonefile of the given domain, e.g. for a backup or a review.
+///
+/// The zonefile starts with an `$ORIGIN` directive followed by one line per record in
+/// presentation format, see [`ResourceRecordSet::to_presentation`]. The apex is rendered as the
+/// origin itself. Parsing the result with [`parse_zonefile`] yields the same RRsets.
+pub fn to_zonefile(domain: &str, rrsets: &[ResourceRecordSet]) -> String {
+    let origin = fully_qualify(domain, ".");
+    let mut zonefile = format!("$ORIGIN {origin}\n");
+    for rrset in rrsets {
+        zonefile.push_str(&rrset.to_presentation(&origin));
+    }
+    zonefile
+}
+
+// Type of the RRset holding the given address
+#[cfg(feature = "std")]
+fn address_type(address: IpAddr) -> String {
+    match address {
+        IpAddr::V4(_) => RecordType::A.to_string(),
+        IpAddr::V6(_) => RecordType::AAAA.to_string(),
+    }
+}
+
+// Subname of an RRset within a bulk request, where the apex is identified by an empty subname
+fn bulk_subname(subname: Option<&str>) -> &str {
+    match subname {
+        None | Some("@") => "",
+        Some(subname) => subname,
+    }
+}
+
+// Rejects RRsets to be created which deSEC is known to refuse.
+#[cfg(feature = "std")]
+fn check_new_rrset(rrset_type: &str, record_count: usize) -> Result<(), Error> {
+    let record_type = RecordType::from(rrset_type);
+    if record_type.is_known_unsupported_by_desec() {
+        return Err(Error::InvalidInput(format!(
+            "deSEC does not support type {record_type}"
+        )));
+    }
+    if record_count > MAX_RECORDS_PER_RRSET {
+        return Err(Error::InvalidInput(format!(
+            "RRset has {record_count} records, exceeding MAX_RECORDS_PER_RRSET ({MAX_RECORDS_PER_RRSET})"
+        )));
+    }
+    Ok(())
+}
+
+/// Maximum number of records in a single RRset accepted by [`validate_rrset_limits`].
+///
+/// Adjust if deSEC changes its limits, the API remains the authority on what it accepts.
+pub const MAX_RECORDS_PER_RRSET: usize = 4096;
+
+/// Maximum total length in bytes of the records of a single RRset accepted by [`validate_rrset_limits`],
+/// bounded by the maximum size of a DNS message.
+pub const MAX_RRSET_RECORDS_BYTES: usize = 64_000;
+
+/// Maximum number of RRsets sent for a single domain accepted by [`validate_rrset_limits`].
+pub const MAX_RRSETS_PER_DOMAIN: usize = 10_000;
+
+/// Checks the given RRsets against the limits of deSEC before sending them, e.g. for bulk seeding.
+///
+/// # Errors
+///
+/// This function fails with [`Error::InvalidInput`][error] naming the exceeded limit if
+/// - more than [`MAX_RRSETS_PER_DOMAIN`] RRsets are given for a domain
+/// - an RRset has more than [`MAX_RECORDS_PER_RRSET`] records
+/// - the records of an RRset exceed [`MAX_RRSET_RECORDS_BYTES`] in total
+///
+/// [error]: ../enum.Error.html
+#[cfg(feature = "std")]
+pub fn validate_rrset_limits(rrsets: &[ResourceRecordSet]) -> Result<(), Error> {
+    let mut per_domain: BTreeMap<&str, usize> = BTreeMap::new();
+    for rrset in rrsets {
+        let count = per_domain.entry(rrset.domain.as_str()).or_default();
+        *count += 1;
+        if *count > MAX_RRSETS_PER_DOMAIN {
+            return Err(Error::InvalidInput(format!(
+                "more than {MAX_RRSETS_PER_DOMAIN} RRsets (MAX_RRSETS_PER_DOMAIN) for domain {}",
+                rrset.domain
+            )));
+        }
+        let name = format!(
+            "{}/{}",
+            rrset.subname.as_deref().unwrap_or_default(),
+            rrset.rrset_type
+        );
+        if rrset.records.len() > MAX_RECORDS_PER_RRSET {
+            return Err(Error::InvalidInput(format!(
+                "RRset {name} has {} records, exceeding MAX_RECORDS_PER_RRSET ({MAX_RECORDS_PER_RRSET})",
+                rrset.records.len()
+            )));
+        }
+        let bytes: usize = rrset.records.iter().map(String::len).sum();
+        if bytes > MAX_RRSET_RECORDS_BYTES {
+            return Err(Error::InvalidInput(format!(
+                "records of RRset {name} have {bytes} bytes, exceeding MAX_RRSET_RECORDS_BYTES ({MAX_RRSET_RECORDS_BYTES})"
+            )));
+        }
+    }
+    Ok(())
+}
+
+impl RecordType {
+    /// Returns the name of the type.
+    ///
+    /// This is the canonical uppercase name for types with a dedicated variant and the name as given
+    /// for [`RecordType::Other`]. Use [`Display`][display] for the canonical uppercase name of any type.
+    ///
+    /// [display]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+    pub fn as_str(&self) -> &str {
+        match self {
+            RecordType::A => "A",
+            RecordType::AAAA => "AAAA",
+            RecordType::CNAME => "CNAME",
+            RecordType::MX => "MX",
+            RecordType::TXT => "TXT",
+            RecordType::NS => "NS",
+            RecordType::SRV => "SRV",
+            RecordType::CAA => "CAA",
+            RecordType::TLSA => "TLSA",
+            RecordType::SSHFP => "SSHFP",
+            RecordType::PTR => "PTR",
+            RecordType::Other(other) => other,
+        }
+    }
+
+    /// Returns whether the type is on deSEC's [list of supported types][supported].
+    ///
+    /// [supported]: constant.DESEC_SUPPORTED_TYPES.html
+    pub fn is_supported_by_desec(&self) -> bool {
+        DESEC_SUPPORTED_TYPES.contains(&self.to_string().as_str())
+    }
+
+    /// Returns whether the type is known to be rejected by deSEC.
+    ///
+    /// Unlike [`RecordType::is_supported_by_desec`], unknown types (e.g. types introduced after
+    /// this list was compiled) are not considered unsupported.
+    pub fn is_known_unsupported_by_desec(&self) -> bool {
+        DESEC_UNSUPPORTED_TYPES.contains(&self.to_string().as_str())
+    }
+
+    /// Returns a reasonable default TTL in seconds for records of this type,
+    /// but never less than the given minimum TTL of the zone.
+    ///
+    /// Rarely changing delegation and mail records (NS, MX) get a day, records which are often
+    /// updated dynamically or used for challenges (A, AAAA, TXT) get five minutes, all others an hour.
+    pub fn recommended_ttl(&self, minimum_ttl: u16) -> u64 {
+        let ttl = match self {
+            RecordType::NS | RecordType::MX => 86400,
+            RecordType::A | RecordType::AAAA | RecordType::TXT => 300,
+            _ => 3600,
+        };
+        ttl.max(u64::from(minimum_ttl))
+    }
+}
+
+impl FromStr for RecordType {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_uppercase().as_str() {
+            "A" => RecordType::A,
+            "AAAA" => RecordType::AAAA,
+            "CNAME" => RecordType::CNAME,
+            "MX" => RecordType::MX,
+            "TXT" => RecordType::TXT,
+            "NS" => RecordType::NS,
+            "SRV" => RecordType::SRV,
+            "CAA" => RecordType::CAA,
+            "TLSA" => RecordType::TLSA,
+            "SSHFP" => RecordType::SSHFP,
+            "PTR" => RecordType::PTR,
+            _ => RecordType::Other(s.to_string()),
+        })
+    }
+}
+
+/// An empty [`RecordType::Other`], the type of a default RRset.
+impl Default for RecordType {
+    fn default() -> Self {
+        RecordType::Other(String::new())
+    }
+}
+
+/// Compares types case-insensitively, so spellings of [`RecordType::Other`] are equal.
+impl PartialEq for RecordType {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str().eq_ignore_ascii_case(other.as_str())
+    }
+}
+
+impl Eq for RecordType {}
+
+/// Hashes the uppercase name, consistent with the case-insensitive equality.
+impl Hash for RecordType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for byte in self.as_str().bytes() {
+            state.write_u8(byte.to_ascii_uppercase());
+        }
+        state.write_u8(0xff);
+    }
+}
+
+/// Compares with the name of a type case-insensitively, e.g. `rrset.rrset_type == "aaaa"`.
+impl PartialEq<str> for RecordType {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str().eq_ignore_ascii_case(other)
+    }
+}
+
+impl PartialEq<&str> for RecordType {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl From<&str> for RecordType {
+    fn from(value: &str) -> Self {
+        match value.parse() {
+            Ok(record_type) => record_type,
+            Err(never) => match never {},
+        }
+    }
+}
+
+impl From<&String> for RecordType {
+    fn from(value: &String) -> Self {
+        RecordType::from(value.as_str())
+    }
+}
+
+impl From<String> for RecordType {
+    fn from(value: String) -> Self {
+        RecordType::from(value.as_str())
+    }
+}
+
+/// Serializes as the canonical uppercase name, like [`Display`][display].
+///
+/// [display]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+impl Serialize for RecordType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes from a string case-insensitively, like [`FromStr`][fromstr].
+///
+/// [fromstr]: https://doc.rust-lang.org/std/str/trait.FromStr.html
+impl<'de> Deserialize<'de> for RecordType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(RecordType::from(value))
+    }
+}
+
+/// Renders the canonical uppercase name of the type.
+impl fmt::Display for RecordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordType::Other(other) => f.write_str(&other.to_ascii_uppercase()),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+/// Value of a single record of an RRset.
+///
+/// Use [`RecordValue::Text`] for the content of TXT (or SPF) records, which gets wrapped in
+/// double-quotes and escaped exactly once by this client. Content longer than 255 bytes is split
+/// into multiple character-strings as required by DNS.
+/// Use [`RecordValue::Raw`] for everything else, it is sent verbatim.
+///
+/// Strings convert into [`RecordValue::Raw`], so already quoted values keep working.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordValue {
+    /// Record content in presentation format, sent as is (e.g. `192.0.2.1` or `"quoted text"`).
+    Raw(String),
+    /// Unquoted text, quoted and escaped by this client (e.g. `v=spf1 -all`).
+    Text(String),
+}
+
+impl RecordValue {
+    /// Returns the record content in the presentation format expected by the API.
+    pub fn to_record(&self) -> String {
+        match self {
+            RecordValue::Raw(raw) => raw.clone(),
+            RecordValue::Text(text) => quote_text(text),
+        }
+    }
+}
+
+impl From<&str> for RecordValue {
+    fn from(value: &str) -> Self {
+        RecordValue::Raw(value.to_string())
+    }
+}
+
+impl From<String> for RecordValue {
+    fn from(value: String) -> Self {
+        RecordValue::Raw(value)
+    }
+}
+
+impl fmt::Display for RecordValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_record())
+    }
+}
+
+impl Serialize for RecordValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_record())
+    }
+}
+
+// Quotes text as one or more DNS character-strings of at most 255 bytes each.
+fn quote_text(text: &str) -> String {
+    let mut strings = Vec::new();
+    let mut rest = text;
+    loop {
+        let mut end = rest.len().min(255);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        strings.push(format!(
+            "\"{}\"",
+            chunk.replace('\\', "\\\\").replace('"', "\\\"")
+        ));
+        rest = tail;
+        if rest.is_empty() {
+            break;
+        }
+    }
+    strings.join(" ")
+}
+
+#[cfg(feature = "std")]
 impl<'a> RrsetClient<'a> {
     /// Creates a new RRSet and returns the newly created [`ResourceRecordSet`][rrset].
     ///
-    /// For the creation of a rrset of type TXT (and maybe others), the values in the records vector need to be wrapped in douple-quotes!
+    /// For the creation of a rrset of type TXT (and maybe others), the values in the records vector need to be wrapped in douple-quotes!
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if deSEC is known not to support the type,
+    /// see [`RecordType::is_supported_by_desec`][supported], or if more than
+    /// [`MAX_RECORDS_PER_RRSET`][max] records are given.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [rrset]: ./struct.ResourceRecordSet.html
+    /// [supported]: enum.RecordType.html#method.is_supported_by_desec
+    /// [max]: constant.MAX_RECORDS_PER_RRSET.html
+    pub async fn create_rrset(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: &str,
+        ttl: u64,
+        records: &[String],
+    ) -> Result<ResourceRecordSet, Error> {
+        self.create_rrset_records(domain, subname, rrset_type, ttl, records)
+            .await
+    }
+
+    /// Creates a new RRSet using the [recommended TTL][ttl] for its type and returns the newly created [`ResourceRecordSet`][rrset].
+    ///
+    /// The domain is retrieved first to respect its minimum TTL.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [rrset]: ./struct.ResourceRecordSet.html
+    /// [ttl]: ./enum.RecordType.html#method.recommended_ttl
+    pub async fn create_rrset_recommended_ttl(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: &str,
+        records: &[String],
+    ) -> Result<ResourceRecordSet, Error> {
+        let minimum_ttl = self.client.domain().get_domain(domain).await?.minimum_ttl;
+        let ttl = RecordType::from(rrset_type).recommended_ttl(minimum_ttl);
+        self.create_rrset_records(domain, subname, rrset_type, ttl, records)
+            .await
+    }
+
+    /// Creates a new RRSet from typed [`RecordValue`][value]s and returns the newly created [`ResourceRecordSet`][rrset].
+    ///
+    /// In contrast to [`create_rrset`][create], [`RecordValue::Text`][value] values are quoted by this client,
+    /// so TXT records can be given without wrapping them in double-quotes.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [rrset]: ./struct.ResourceRecordSet.html
+    /// [value]: ./enum.RecordValue.html
+    /// [create]: #method.create_rrset
+    pub async fn create_rrset_with_values(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: &str,
+        ttl: u64,
+        records: &[RecordValue],
+    ) -> Result<ResourceRecordSet, Error> {
+        self.create_rrset_records(domain, subname, rrset_type, ttl, records)
+            .await
+    }
+
+    /// Creates several RRSets in a single bulk request and returns the newly created [`ResourceRecordSet`][rrset]s.
+    ///
+    /// deSEC validates the whole batch atomically: if any RRset is rejected, none is created.
+    /// To keep this guarantee, the RRsets are always sent in a single request regardless of the
+    /// [maximum body size][max_body_bytes] of the client.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with
+    /// - [`Error::InvalidInput`][error] if deSEC is known not to support the type of an RRset,
+    ///   or an RRset has more than [`MAX_RECORDS_PER_RRSET`][max] records
+    /// - [`Error::ApiError`][error] if the API rejects the batch, the messages of the rejected
+    ///   RRsets are keyed by their index, e.g. `ApiErrorBody::field("1.records")`
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [rrset]: ./struct.ResourceRecordSet.html
+    /// [max]: constant.MAX_RECORDS_PER_RRSET.html
+    /// [max_body_bytes]: ../struct.Client.html#method.set_max_body_bytes
+    pub async fn create_rrsets(
+        &self,
+        domain: &str,
+        rrsets: &[NewRrset],
+    ) -> Result<Vec<ResourceRecordSet>, Error> {
+        if rrsets.is_empty() {
+            return Ok(Vec::new());
+        }
+        for rrset in rrsets {
+            check_new_rrset(rrset.rrset_type.as_str(), rrset.records.len())?;
+        }
+        self.client.rrset_cache.remove_domain(domain);
+        let response = self
+            .client
+            .post(
+                format!("/domains/{domain}/rrsets/").as_str(),
+                Some(self.client.serialize_body(&rrsets)?),
+            )
+            .await?;
+        match response.status() {
+            StatusCode::CREATED => {
+                let response_text = crate::response_text(response).await?;
+                crate::parse_list(response_text)
+            }
+            _ => Err(Error::UnexpectedStatusCode(
+                response.status().into(),
+                response.text().await.unwrap_or_default(),
+            )),
+        }
+    }
+
+    async fn create_rrset_records<R: Serialize>(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: &str,
+        ttl: u64,
+        records: &[R],
+    ) -> Result<ResourceRecordSet, Error> {
+        check_new_rrset(rrset_type, records.len())?;
+        let rrset = json!({
+            "subname": subname.unwrap_or_default(),
+            "type": rrset_type,
+            "ttl": ttl,
+            "records": records
+        });
+        let response = self
+            .client
+            .post(
+                format!("/domains/{domain}/rrsets/").as_str(),
+                Some(self.client.serialize_body(&rrset)?),
+            )
+            .await?;
+        match response.status() {
+            StatusCode::CREATED => {
+                let response_text = crate::response_text(response).await?;
+                serde_json::from_str(&response_text)
+                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+            }
+            _ => Err(Error::UnexpectedStatusCode(
+                response.status().into(),
+                response.text().await.unwrap_or_default(),
+            )),
+        }
+    }
+
+    /// Retrieves all RRSets in the given zone.
+    ///
+    /// All pages of the listing are followed, so zones with more than 500 RRsets are supported.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn get_rrsets(&self, domain: &str) -> Result<Vec<ResourceRecordSet>, Error> {
+        self.stream_rrsets(domain).try_collect().await
+    }
+
+    /// Lazily streams all RRSets in the given zone, page by page, e.g. to process large zones
+    /// without holding all of their RRsets in memory.
+    ///
+    /// The next page is only requested once all RRsets of the current page have been consumed.
+    /// The stream ends after yielding the first error.
+    ///
+    /// ```no_run
+    /// use desec_api::Client;
+    /// use futures_util::{pin_mut, StreamExt};
+    ///
+    /// # async fn run(client: Client) {
+    /// let rrsets = client.rrset().stream_rrsets("example.com");
+    /// pin_mut!(rrsets);
+    /// while let Some(rrset) = rrsets.next().await {
+    ///     println!("{:?}", rrset);
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub fn stream_rrsets(
+        &self,
+        domain: &str,
+    ) -> impl Stream<Item = Result<ResourceRecordSet, Error>> + 'a {
+        self.client
+            .stream_items(format!("/domains/{domain}/rrsets/"))
+    }
+
+    /// Retrieves all RRSets in the given zone grouped by subname, e.g. to render the zone as a tree.
+    ///
+    /// The zone apex is keyed by `@`. Subnames are sorted by their byte order and the RRsets
+    /// of each subname by type. All pages of the listing are followed.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn get_rrsets_grouped(
+        &self,
+        domain: &str,
+    ) -> Result<BTreeMap<String, Vec<ResourceRecordSet>>, Error> {
+        let mut grouped: BTreeMap<String, Vec<ResourceRecordSet>> = BTreeMap::new();
+        for rrset in self.query(domain).all().await? {
+            let subname = match rrset.subname.as_deref() {
+                None | Some("") => "@".to_string(),
+                Some(subname) => subname.to_string(),
+            };
+            grouped.entry(subname).or_default().push(rrset);
+        }
+        for rrsets in grouped.values_mut() {
+            rrsets.sort_by_cached_key(|rrset| rrset.rrset_type.to_string());
+        }
+        Ok(grouped)
+    }
+
+    /// Retrieves a single page of the RRSets in the given zone.
+    ///
+    /// Pass `None` to get the first page and the [`next_cursor`][page] of a page to get the following one.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [page]: ../pagination/struct.Page.html
+    pub async fn get_rrsets_page(
+        &self,
+        domain: &str,
+        cursor: Option<&str>,
+    ) -> Result<Page<ResourceRecordSet>, Error> {
+        self.client
+            .get_page(format!("/domains/{domain}/rrsets/").as_str(), cursor)
+            .await
+    }
+
+    /// Returns the number of RRSets in the given zone.
+    ///
+    /// See [pagination][pagination] on how the count is determined.
     ///
     /// # Errors
     ///
     /// see [General errors][general_errors]
     ///
     /// [general_errors]: ../index.html#general-errors-for-all-clients
-    /// [rrset]: ./struct.ResourceRecordSet.html
-    pub async fn create_rrset(
-        &self,
-        domain: &str,
-        subname: Option<&str>,
-        rrset_type: &str,
-        ttl: u64,
-        records: &Vec<String>,
-    ) -> Result<ResourceRecordSet, Error> {
-        let rrset = json!({
-            "subname": subname.unwrap_or_default(),
-            "type": rrset_type,
-            "ttl": ttl,
-            "records": records
-        });
-        let response = self
-            .client
-            .post(
-                format!("/domains/{domain}/rrsets/").as_str(),
-                Some(
-                    serde_json::to_string(&rrset)
-                        .map_err(|error| Error::Serialize(error.to_string()))?,
-                ),
-            )
-            .await?;
-        match response.status() {
-            StatusCode::CREATED => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
-            }
-            _ => Err(Error::UnexpectedStatusCode(
-                response.status().into(),
-                response.text().await.unwrap_or_default(),
-            )),
-        }
+    /// [pagination]: ../pagination/index.html
+    pub async fn count_rrsets(&self, domain: &str) -> Result<u64, Error> {
+        self.client
+            .count_items::<ResourceRecordSet>(format!("/domains/{domain}/rrsets/").as_str())
+            .await
     }
 
-    /// Retrieves all RRSets in the given zone.
+    /// Retrieves all RRSets in the given zone which have been modified after the given point in time.
+    ///
+    /// deSEC does not offer a server-side filter for this, so all RRsets of the zone are retrieved
+    /// and filtered by their `touched` field on the client side.
     ///
     /// # Errors
     ///
     /// see [General errors][general_errors]
     ///
+    /// This method also fails with [`Error::InvalidAPIResponse`][error] if an RRset contains an invalid `touched` timestamp.
+    ///
     /// [general_errors]: ../index.html#general-errors-for-all-clients
-    pub async fn get_rrsets(&self, domain: &str) -> Result<Vec<ResourceRecordSet>, Error> {
-        let response = self
-            .client
-            .get(format!("/domains/{domain}/rrsets/").as_str())
-            .await?;
-        match response.status() {
-            StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+    /// [error]: ../enum.Error.html
+    #[cfg(feature = "time")]
+    pub async fn get_rrsets_modified_since(
+        &self,
+        domain: &str,
+        since: time::OffsetDateTime,
+    ) -> Result<Vec<ResourceRecordSet>, Error> {
+        let mut modified = Vec::new();
+        for rrset in self.get_rrsets(domain).await? {
+            if crate::timestamp::parse_timestamp(&rrset.touched)? > since {
+                modified.push(rrset);
             }
-            _ => Err(Error::UnexpectedStatusCode(
-                response.status().into(),
-                response.text().await.unwrap_or_default(),
-            )),
+        }
+        Ok(modified)
+    }
+
+    /// Starts a query for RRsets in the given zone, see [`RrsetQuery`][query].
+    ///
+    /// ```no_run
+    /// # async fn run(client: desec_api::Client) -> Result<(), desec_api::Error> {
+    /// let mail = client
+    ///     .rrset()
+    ///     .query("example.com")
+    ///     .subname("")
+    ///     .types(&["MX", "TXT"])
+    ///     .all()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [query]: struct.RrsetQuery.html
+    pub fn query(&self, domain: &str) -> RrsetQuery<'a> {
+        RrsetQuery {
+            client: self.client,
+            domain: domain.to_string(),
+            subname: None,
+            types: Vec::new(),
+            limit: None,
         }
     }
 
@@ -113,23 +1469,9 @@ impl<'a> RrsetClient<'a> {
     pub async fn get_rrsets_by_type(
         &self,
         domain: &str,
-        r#type: &str,
+        r#type: impl Into<RecordType>,
     ) -> Result<Vec<ResourceRecordSet>, Error> {
-        let response = self
-            .client
-            .get(format!("/domains/{domain}/rrsets/?type={}", r#type).as_str())
-            .await?;
-        match response.status() {
-            StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
-            }
-            _ => Err(Error::UnexpectedStatusCode(
-                response.status().into(),
-                response.text().await.unwrap_or_default(),
-            )),
-        }
+        self.query(domain).rrset_type(r#type).all().await
     }
 
     /// Retrieves all RRSets in the given zone filtered by a given subname.
@@ -144,21 +1486,51 @@ impl<'a> RrsetClient<'a> {
         domain: &str,
         subname: &str,
     ) -> Result<Vec<ResourceRecordSet>, Error> {
-        let response = self
-            .client
-            .get(format!("/domains/{domain}/rrsets/?subname={subname}").as_str())
-            .await?;
-        match response.status() {
-            StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
-            }
-            _ => Err(Error::UnexpectedStatusCode(
-                response.status().into(),
-                response.text().await.unwrap_or_default(),
-            )),
+        self.query(domain).subname(subname).all().await
+    }
+
+    /// Retrieves all RRSets in the given zone matching the given filters, use an empty subname
+    /// for the zone apex. Both filters are applied by the API, `None` disables a filter.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn get_rrsets_filtered(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: Option<&str>,
+    ) -> Result<Vec<ResourceRecordSet>, Error> {
+        let mut query = self.query(domain);
+        if let Some(subname) = subname {
+            query = query.subname(subname);
         }
+        if let Some(rrset_type) = rrset_type {
+            query = query.rrset_type(rrset_type);
+        }
+        query.all().await
+    }
+
+    /// Retrieves a specific RRSet, `None` if it does not exist.
+    ///
+    /// Unlike [`get_rrset`][get_rrset], a missing resource is not reported as [`Error::NotFound`][error].
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [get_rrset]: #method.get_rrset
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn try_get_rrset(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: &str,
+    ) -> Result<Option<ResourceRecordSet>, Error> {
+        crate::not_found_as_none(self.get_rrset(domain, subname, rrset_type).await)
     }
 
     /// Retrieves a specific RRSet.
@@ -176,16 +1548,113 @@ impl<'a> RrsetClient<'a> {
     ) -> Result<ResourceRecordSet, Error> {
         // https://desec.readthedocs.io/en/latest/dns/rrsets.html#accessing-the-zone-apex
         let subname = subname.unwrap_or("@");
+        let endpoint = format!("/domains/{domain}/rrsets/{subname}/{rrset_type}/");
+        let key = cache_key(domain, subname, rrset_type);
+        let cached = match self.client.rrset_cache_ttl {
+            Some(ttl) => match self.client.rrset_cache.get(&key) {
+                Some(cached) if cached.fetched.elapsed() < ttl => return Ok(cached.rrset),
+                cached => cached,
+            },
+            None => None,
+        };
+        let response = match cached.as_ref().and_then(|cached| cached.etag.as_deref()) {
+            Some(etag) => self.client.get_if_none_match(&endpoint, etag).await?,
+            None => self.client.get(&endpoint).await?,
+        };
+        match (response.status(), cached) {
+            (StatusCode::NOT_MODIFIED, Some(cached)) => {
+                self.client.rrset_cache.refresh(&key);
+                Ok(cached.rrset)
+            }
+            (StatusCode::OK, _) => {
+                let etag = response
+                    .headers()
+                    .get(reqwest::header::ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(str::to_string);
+                let response_text = crate::response_text(response).await?;
+                let rrset: ResourceRecordSet = crate::parse_single(response_text)?;
+                if self.client.rrset_cache_ttl.is_some() {
+                    self.client.rrset_cache.insert(key, rrset.clone(), etag);
+                }
+                Ok(rrset)
+            }
+            _ => Err(Error::UnexpectedStatusCode(
+                response.status().into(),
+                response.text().await.unwrap_or_default(),
+            )),
+        }
+    }
+
+    /// Retrieves several specific RRsets of a domain concurrently.
+    ///
+    /// Each target is a pair of subname (`None` for the zone apex) and type.
+    /// The results are aligned with `targets`, so the n-th result belongs to the n-th target,
+    /// regardless of the order in which the requests complete. Failed retrievals do not affect
+    /// the other targets. The number of concurrent requests is bounded by the
+    /// [maximum number of concurrent requests][max] of the client.
+    ///
+    /// [max]: ../struct.ClientBuilder.html#method.max_concurrent_requests
+    pub async fn get_rrsets_ordered(
+        &self,
+        domain: &str,
+        targets: &[(Option<String>, String)],
+    ) -> Vec<Result<ResourceRecordSet, Error>> {
+        stream::iter(targets)
+            .map(|(subname, rrset_type)| self.get_rrset(domain, subname.as_deref(), rrset_type))
+            .buffered(self.client.max_concurrent_requests)
+            .collect()
+            .await
+    }
+
+    /// Creates the RRSet or fully replaces it if it already exists.
+    ///
+    /// The RRset is sent with a bulk PUT request, which creates missing RRsets and leaves all other
+    /// RRsets of the zone untouched. As PUT is idempotent, the request is retried on timeouts.
+    /// Returns `None` if empty records have been given, which deletes the RRset.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if deSEC is known not to support the type,
+    /// or more than [`MAX_RECORDS_PER_RRSET`][max] records have been given.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [max]: constant.MAX_RECORDS_PER_RRSET.html
+    pub async fn put_rrset(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: &str,
+        ttl: u64,
+        records: &[String],
+    ) -> Result<Option<ResourceRecordSet>, Error> {
+        check_new_rrset(rrset_type, records.len())?;
+        let subname = bulk_subname(subname);
+        self.client
+            .rrset_cache
+            .remove(&cache_key(domain, subname, rrset_type));
         let response = self
             .client
-            .get(format!("/domains/{domain}/rrsets/{subname}/{rrset_type}/").as_str())
+            .put(
+                format!("/domains/{domain}/rrsets/").as_str(),
+                self.client.serialize_body(&json!([{
+                    "subname": subname,
+                    "type": rrset_type,
+                    "ttl": ttl,
+                    "records": records
+                }]))?,
+            )
             .await?;
         match response.status() {
             StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+                let response_text = crate::response_text(response).await?;
+                let rrsets: Vec<ResourceRecordSet> = crate::parse_list(response_text)?;
+                Ok(rrsets.into_iter().next())
             }
+            StatusCode::NO_CONTENT => Ok(None),
             _ => Err(Error::UnexpectedStatusCode(
                 response.status().into(),
                 response.text().await.unwrap_or_default(),
@@ -193,6 +1662,303 @@ impl<'a> RrsetClient<'a> {
         }
     }
 
+    /// Adds a record to an RRSet and returns the resulting RRset.
+    ///
+    /// The current RRset is retrieved bypassing the cache and patched with the record appended,
+    /// unless it already contains the record (compared textually). A missing RRset is created with
+    /// the single record and the [recommended TTL][ttl] for its type.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [ttl]: ./enum.RecordType.html#method.recommended_ttl
+    pub async fn append_record(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: &str,
+        record: &str,
+    ) -> Result<ResourceRecordSet, Error> {
+        let mut rrset = match self.get_rrset_uncached(domain, subname, rrset_type).await? {
+            Some(rrset) => rrset,
+            None => {
+                return self
+                    .create_rrset_recommended_ttl(
+                        domain,
+                        subname,
+                        rrset_type,
+                        &[record.to_string()],
+                    )
+                    .await
+            }
+        };
+        if rrset.records.iter().any(|existing| existing == record) {
+            return Ok(rrset);
+        }
+        rrset.records.push(record.to_string());
+        let patched = self
+            .patch_rrset(domain, subname, rrset_type, &rrset.records, rrset.ttl)
+            .await?;
+        Ok(patched.unwrap_or(rrset))
+    }
+
+    /// Removes a record from an RRSet and returns the resulting RRset.
+    ///
+    /// The current RRset is retrieved bypassing the cache and patched with the record removed,
+    /// nothing is changed if the RRset does not contain the record (compared textually).
+    /// Returns `None` if the RRset does not exist or has been deleted by removing its last record.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn remove_record(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: &str,
+        record: &str,
+    ) -> Result<Option<ResourceRecordSet>, Error> {
+        let mut rrset = match self.get_rrset_uncached(domain, subname, rrset_type).await? {
+            Some(rrset) => rrset,
+            None => return Ok(None),
+        };
+        let count = rrset.records.len();
+        rrset.records.retain(|existing| existing != record);
+        if rrset.records.len() == count {
+            return Ok(Some(rrset));
+        }
+        self.patch_rrset(domain, subname, rrset_type, &rrset.records, rrset.ttl)
+            .await
+    }
+
+    // Retrieves the current state of an RRset for read-modify-write operations.
+    async fn get_rrset_uncached(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: &str,
+    ) -> Result<Option<ResourceRecordSet>, Error> {
+        self.client
+            .rrset_cache
+            .remove(&cache_key(domain, subname.unwrap_or_default(), rrset_type));
+        self.try_get_rrset(domain, subname, rrset_type).await
+    }
+
+    /// Brings the zone to the desired state and returns the changes made.
+    ///
+    /// The current RRsets are retrieved and compared against `desired` by subname and type,
+    /// ignoring server-assigned fields like `created` and `touched`. Records are compared as a set
+    /// after normalizing insignificant whitespace and qualifying hostname targets. TTLs are normalized
+    /// by raising desired TTLs below the minimum TTL of the domain to it, as deSEC would reject them.
+    /// RRsets missing in `desired` are deleted, except for those managed by deSEC (SOA and the DNSSEC
+    /// types) and the apex NS RRset, which is only changed if `desired` contains it.
+    ///
+    /// All changes are applied with a single atomic bulk request. With `dry_run`, the planned
+    /// changes are returned without applying them.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn sync_zone(
+        &self,
+        domain: &str,
+        desired: &[ResourceRecordSet],
+        dry_run: bool,
+    ) -> Result<ZoneDiff, Error> {
+        let zone = self.client.domain().get_domain(domain).await?;
+        let current = self.get_rrsets(domain).await?;
+        let diff = plan_sync(domain, u64::from(zone.minimum_ttl), &current, desired);
+        if dry_run || diff.is_empty() {
+            return Ok(diff);
+        }
+        let changes: Vec<ResourceRecordSet> = diff
+            .to_create
+            .iter()
+            .chain(diff.to_update.iter().map(|modified| &modified.after))
+            .cloned()
+            .chain(diff.to_delete.iter().map(|rrset| ResourceRecordSet {
+                records: Vec::new(),
+                ..rrset.clone()
+            }))
+            .collect();
+        self.patch_rrsets(domain, &changes).await?;
+        Ok(diff)
+    }
+
+    /// Points the A or AAAA RRset, depending on the address family, to the given address and
+    /// returns the resulting RRset.
+    ///
+    /// The RRset is created or replaced with [`put_rrset`][put_rrset], so other addresses are removed.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [put_rrset]: #method.put_rrset
+    pub async fn update_address(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        new_ip: IpAddr,
+        ttl: u64,
+    ) -> Result<ResourceRecordSet, Error> {
+        let rrset_type = address_type(new_ip);
+        self.put_rrset(domain, subname, &rrset_type, ttl, &[new_ip.to_string()])
+            .await?
+            .ok_or_else(|| {
+                Error::InvalidAPIResponse(
+                    "address RRset missing in response".to_string(),
+                    String::new(),
+                )
+            })
+    }
+
+    /// Points the A or AAAA RRset to the given address unless it already does, for dynamic DNS.
+    ///
+    /// The current RRset is retrieved bypassing the cache and only updated with
+    /// [`update_address`][update_address] if its records or TTL differ, which avoids unnecessary
+    /// writes and the rate limits on them. Returns whether the RRset has been updated.
+    /// Detecting the public address is up to the caller.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [update_address]: #method.update_address
+    pub async fn sync_dynamic_dns(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        new_ip: IpAddr,
+        ttl: u64,
+    ) -> Result<bool, Error> {
+        let rrset_type = address_type(new_ip);
+        if let Some(current) = self
+            .get_rrset_uncached(domain, subname, &rrset_type)
+            .await?
+        {
+            let matches = current.ttl == ttl
+                && current
+                    .parsed_addresses()
+                    .is_ok_and(|addresses| addresses == [new_ip]);
+            if matches {
+                return Ok(false);
+            }
+        }
+        self.update_address(domain, subname, new_ip, ttl).await?;
+        Ok(true)
+    }
+
+    /// Sets the TXT record for an ACME DNS-01 challenge and returns the resulting RRset.
+    ///
+    /// The record is named `_acme-challenge` below the given name, which is `subname.domain` or
+    /// `domain` itself without subname. As `domain` may also be a name within a zone, the zone
+    /// responsible for the challenge is resolved with [`get_owning_domain`][owning].
+    /// The value is quoted by this client and replaces any existing challenge values,
+    /// use [`append_record`][append_record] to validate several certificates for the same name at once.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if no domain of the account is responsible for the challenge,
+    /// or the API names a responsible domain the challenge is not within.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [owning]: ../domain/struct.DomainClient.html#method.get_owning_domain
+    /// [append_record]: #method.append_record
+    pub async fn set_acme_challenge(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        token_value: &str,
+        ttl: u64,
+    ) -> Result<ResourceRecordSet, Error> {
+        let (zone, challenge) = self.acme_challenge_target(domain, subname).await?;
+        let records = [quote_text(token_value)];
+        self.put_rrset(&zone, Some(&challenge), "TXT", ttl, &records)
+            .await?
+            .ok_or_else(|| {
+                Error::InvalidAPIResponse(
+                    "challenge RRset missing in response".to_string(),
+                    String::new(),
+                )
+            })
+    }
+
+    /// Deletes the TXT record for an ACME DNS-01 challenge set with [`set_acme_challenge`][set_acme_challenge].
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] if no domain of the account is responsible for the challenge,
+    /// or the API names a responsible domain the challenge is not within.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [set_acme_challenge]: #method.set_acme_challenge
+    pub async fn clear_acme_challenge(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+    ) -> Result<(), Error> {
+        let (zone, challenge) = self.acme_challenge_target(domain, subname).await?;
+        self.delete_rrset(&zone, Some(&challenge), "TXT").await
+    }
+
+    // Resolves the zone and the subname within it of the ACME challenge for the given name.
+    async fn acme_challenge_target(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+    ) -> Result<(String, String), Error> {
+        let name = match subname {
+            Some(subname) if !subname.is_empty() && subname != "@" => {
+                format!("_acme-challenge.{subname}.{domain}")
+            }
+            _ => format!("_acme-challenge.{domain}"),
+        };
+        let name = name.trim_end_matches('.');
+        let zone = self
+            .client
+            .domain()
+            .get_owning_domain(name)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "no domain of the account is responsible for {name}"
+                ))
+            })?
+            .name;
+        let challenge = if name.eq_ignore_ascii_case(&zone) {
+            ""
+        } else {
+            name.len()
+                .checked_sub(zone.len() + 1)
+                .filter(|&end| {
+                    name.is_char_boundary(end)
+                        && name[end..].eq_ignore_ascii_case(&format!(".{zone}"))
+                })
+                .map(|end| &name[..end])
+                .ok_or_else(|| {
+                    Error::InvalidInput(format!("{name} is not within the owning domain {zone}"))
+                })?
+        };
+        Ok((zone, challenge.to_string()))
+    }
+
     /// Updates an existing RRSet based on the given RRSet.
     ///
     /// # Errors
@@ -207,7 +1973,7 @@ impl<'a> RrsetClient<'a> {
         self.patch_rrset(
             &rrset.domain,
             rrset.subname.as_deref(),
-            &rrset.rrset_type,
+            &rrset.rrset_type.to_string(),
             &rrset.records,
             rrset.ttl,
         )
@@ -228,23 +1994,119 @@ impl<'a> RrsetClient<'a> {
         rrset_type: &str,
         records: &[String],
         ttl: u64,
+    ) -> Result<Option<ResourceRecordSet>, Error> {
+        self.patch_rrset_records(domain, subname, rrset_type, records, ttl)
+            .await
+    }
+
+    /// Updates an existing RRSet based on the given typed [`RecordValue`][value]s.
+    ///
+    /// [`RecordValue::Text`][value] values are quoted by this client.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [value]: ./enum.RecordValue.html
+    pub async fn patch_rrset_with_values(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: &str,
+        records: &[RecordValue],
+        ttl: u64,
+    ) -> Result<Option<ResourceRecordSet>, Error> {
+        self.patch_rrset_records(domain, subname, rrset_type, records, ttl)
+            .await
+    }
+
+    /// Updates several RRSets in a single bulk request and returns the resulting RRSets.
+    ///
+    /// Only `subname`, `rrset_type`, `ttl` and `records` of each RRset are sent. A subname of
+    /// `None`, `""` or `"@"` selects the zone apex, the apex is always sent as empty subname
+    /// within the bulk request. RRsets with empty records are deleted and missing from the result.
+    ///
+    /// deSEC applies the whole batch atomically: if any RRset is rejected, none is changed.
+    /// To keep this guarantee, the RRsets are always sent in a single request regardless of the
+    /// [maximum body size][max_body_bytes] of the client.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::ApiError`][error] if the API rejects the batch, the messages
+    /// of the rejected RRsets are keyed by their index, e.g. `ApiErrorBody::field("1.records")`.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [max_body_bytes]: ../struct.Client.html#method.set_max_body_bytes
+    pub async fn patch_rrsets(
+        &self,
+        domain: &str,
+        rrsets: &[ResourceRecordSet],
+    ) -> Result<Vec<ResourceRecordSet>, Error> {
+        if rrsets.is_empty() {
+            return Ok(Vec::new());
+        }
+        let payload: Vec<_> = rrsets
+            .iter()
+            .map(|rrset| {
+                json!({
+                    "subname": bulk_subname(rrset.subname.as_deref()),
+                    "type": rrset.rrset_type,
+                    "ttl": rrset.ttl,
+                    "records": rrset.records
+                })
+            })
+            .collect();
+        self.client.rrset_cache.remove_domain(domain);
+        let response = self
+            .client
+            .patch(
+                format!("/domains/{domain}/rrsets/").as_str(),
+                self.client.serialize_body(&payload)?,
+            )
+            .await?;
+        match response.status() {
+            StatusCode::OK => {
+                let response_text = crate::response_text(response).await?;
+                crate::parse_list(response_text)
+            }
+            StatusCode::NO_CONTENT => Ok(Vec::new()),
+            _ => Err(Error::UnexpectedStatusCode(
+                response.status().into(),
+                response.text().await.unwrap_or_default(),
+            )),
+        }
+    }
+
+    async fn patch_rrset_records<R: Serialize>(
+        &self,
+        domain: &str,
+        subname: Option<&str>,
+        rrset_type: &str,
+        records: &[R],
+        ttl: u64,
     ) -> Result<Option<ResourceRecordSet>, Error> {
         // https://desec.readthedocs.io/en/latest/dns/rrsets.html#accessing-the-zone-apex
         let subname = subname.unwrap_or("@");
+        self.client
+            .rrset_cache
+            .remove(&cache_key(domain, subname, rrset_type));
         let response = self
             .client
             .patch(
                 format!("/domains/{domain}/rrsets/{subname}/{rrset_type}/").as_str(),
-                serde_json::to_string(&json!({
+                self.client.serialize_body(&json!({
                     "ttl": ttl,
                     "records": records
-                }))
-                .map_err(|error| Error::Serialize(error.to_string()))?,
+                }))?,
             )
             .await?;
         match response.status() {
             StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
+                let response_text = crate::response_text(response).await?;
                 serde_json::from_str(&response_text)
                     .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
             }
@@ -271,6 +2133,9 @@ impl<'a> RrsetClient<'a> {
     ) -> Result<(), Error> {
         // https://desec.readthedocs.io/en/latest/dns/rrsets.html#accessing-the-zone-apex
         let subname = subname.unwrap_or("@");
+        self.client
+            .rrset_cache
+            .remove(&cache_key(domain, subname, rrset_type));
         let response = self
             .client
             .delete(format!("/domains/{domain}/rrsets/{subname}/{rrset_type}/").as_str())
@@ -285,4 +2150,1312 @@ impl<'a> RrsetClient<'a> {
             )),
         }
     }
+
+    /// Deletes the RRSets given as pairs of subname (`None` for the zone apex) and type.
+    ///
+    /// The RRSets are deleted with a single bulk request sending them with empty records, so either
+    /// all or none are deleted, like with [`patch_rrsets`][patch_rrsets]. Like [`delete_rrset`][delete_rrset],
+    /// targets which do not exist are ignored and do not fail the request.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [patch_rrsets]: #method.patch_rrsets
+    /// [delete_rrset]: #method.delete_rrset
+    pub async fn delete_rrsets(
+        &self,
+        domain: &str,
+        targets: &[(Option<String>, String)],
+    ) -> Result<(), Error> {
+        if targets.is_empty() {
+            return Ok(());
+        }
+        self.client.rrset_cache.remove_domain(domain);
+        // RRsets are deleted in bulk by sending them with empty records
+        let payload: Vec<_> = targets
+            .iter()
+            .map(|(subname, rrset_type)| {
+                json!({
+                    "subname": bulk_subname(subname.as_deref()),
+                    "type": rrset_type,
+                    "records": []
+                })
+            })
+            .collect();
+        let response = self
+            .client
+            .patch(
+                format!("/domains/{domain}/rrsets/").as_str(),
+                self.client.serialize_body(&payload)?,
+            )
+            .await?;
+        match response.status() {
+            StatusCode::OK | StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(Error::UnexpectedStatusCode(
+                response.status().into(),
+                response.text().await.unwrap_or_default(),
+            )),
+        }
+    }
+
+    /// Deletes all RRSets of the given type in the zone and returns the number of deleted RRSets.
+    ///
+    /// The matching RRSets are deleted with a single bulk request, see [`delete_rrsets`][delete_rrsets].
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [delete_rrsets]: #method.delete_rrsets
+    pub async fn delete_rrsets_by_type(
+        &self,
+        domain: &str,
+        rrset_type: impl Into<RecordType>,
+    ) -> Result<usize, Error> {
+        let rrsets = self.get_rrsets_by_type(domain, rrset_type).await?;
+        if rrsets.is_empty() {
+            return Ok(0);
+        }
+        let targets: Vec<_> = rrsets
+            .iter()
+            .map(|rrset| (rrset.subname.clone(), rrset.rrset_type.to_string()))
+            .collect();
+        self.delete_rrsets(domain, &targets).await?;
+        Ok(rrsets.len())
+    }
+}
+
+/// Builder for queries of the RRsets in a zone, created by [`RrsetClient::query`][query].
+///
+/// Filters by subname and a single type are applied by the API, filters by several types are
+/// applied on the client side. All pages of the listing are followed, so zones with more than
+/// 500 RRsets are supported.
+///
+/// [query]: struct.RrsetClient.html#method.query
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct RrsetQuery<'a> {
+    client: &'a Client,
+    domain: String,
+    subname: Option<String>,
+    types: Vec<String>,
+    limit: Option<usize>,
+}
+
+#[cfg(feature = "std")]
+impl<'a> RrsetQuery<'a> {
+    /// Only returns RRsets with the given subname, use an empty subname for the zone apex.
+    pub fn subname(mut self, subname: &str) -> Self {
+        self.subname = Some(subname.to_string());
+        self
+    }
+
+    /// Only returns RRsets of the given type, replacing previously given types.
+    pub fn rrset_type(mut self, rrset_type: impl Into<RecordType>) -> Self {
+        self.types = vec![rrset_type.into().to_string()];
+        self
+    }
+
+    /// Only returns RRsets of any of the given types, replacing previously given types.
+    pub fn types(mut self, types: &[&str]) -> Self {
+        self.types = types.iter().map(|t| t.to_ascii_uppercase()).collect();
+        self
+    }
+
+    /// Returns at most the given number of RRsets.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Retrieves the single RRset identified by subname and type.
+    ///
+    /// This is equivalent to [`RrsetClient::get_rrset`][get_rrset] and benefits from its cache.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidInput`][error] unless exactly one subname and one type have been given.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [get_rrset]: struct.RrsetClient.html#method.get_rrset
+    pub async fn one(self) -> Result<ResourceRecordSet, Error> {
+        match (self.subname.as_deref(), self.types.as_slice()) {
+            (Some(subname), [rrset_type]) => {
+                let subname = if subname.is_empty() {
+                    None
+                } else {
+                    Some(subname)
+                };
+                self.client
+                    .rrset()
+                    .get_rrset(&self.domain, subname, rrset_type)
+                    .await
+            }
+            _ => Err(Error::InvalidInput(
+                "querying a single RRset requires a subname and exactly one type".to_string(),
+            )),
+        }
+    }
+
+    /// Retrieves all matching RRsets.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn all(self) -> Result<Vec<ResourceRecordSet>, Error> {
+        self.stream().try_collect().await
+    }
+
+    /// Lazily streams all matching RRsets, requesting further pages only as needed.
+    /// The stream ends after yielding the first error.
+    pub fn stream(self) -> impl Stream<Item = Result<ResourceRecordSet, Error>> + 'a {
+        let mut filters = Vec::new();
+        if let Some(subname) = &self.subname {
+            filters.push(format!("subname={subname}"));
+        }
+        if let [rrset_type] = self.types.as_slice() {
+            filters.push(format!("type={rrset_type}"));
+        }
+        let mut endpoint = format!("/domains/{}/rrsets/", self.domain);
+        if !filters.is_empty() {
+            endpoint = format!("{endpoint}?{}", filters.join("&"));
+        }
+        let types = self.types;
+        self.client
+            .stream_items(endpoint)
+            .filter(move |rrset: &Result<ResourceRecordSet, Error>| {
+                future::ready(match rrset {
+                    Ok(rrset) => types.len() < 2 || types.contains(&rrset.rrset_type.to_string()),
+                    Err(_) => true,
+                })
+            })
+            .take(self.limit.unwrap_or(usize::MAX))
+    }
+}
+
+// Key of an RRset in the cache, the apex is always identified by @
+#[cfg(feature = "std")]
+fn cache_key(domain: &str, subname: &str, rrset_type: &str) -> crate::cache::RrsetKey {
+    let subname = if subname.is_empty() { "@" } else { subname };
+    (
+        domain.to_string(),
+        subname.to_string(),
+        rrset_type.to_ascii_uppercase(),
+    )
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        domain_json, mock_client, mock_client_with, page_response, request_body, response,
+        response_with_headers, rrset_json,
+    };
+    use std::time::Duration;
+
+    fn rrset(subname: &str, rrset_type: &str, ttl: u64, records: &[&str]) -> ResourceRecordSet {
+        ResourceRecordSet {
+            domain: "example.com".to_string(),
+            subname: Some(subname.to_string()),
+            rrset_type: RecordType::from(rrset_type),
+            ttl,
+            records: records.iter().map(|record| record.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn timestamps_with_microseconds_are_parsed() {
+        let rrset = ResourceRecordSet {
+            created: "2018-09-18T16:36:16.510368Z".to_string(),
+            touched: "broken".to_string(),
+            ..Default::default()
+        };
+        let created = rrset.created_at().unwrap();
+        assert_eq!(created.unix_timestamp(), 1537288576);
+        assert_eq!(created.microsecond(), 510368);
+        assert!(matches!(
+            rrset.touched_at(),
+            Err(Error::InvalidAPIResponse(_, _))
+        ));
+    }
+
+    #[cfg(feature = "time")]
+    #[tokio::test]
+    async fn only_rrsets_touched_after_given_time_are_returned() {
+        let touched = |subname: &str, touched: &str| {
+            rrset_json("example.com", subname, "A", 3600, &["192.0.2.1"]).replace(
+                "\"touched\":\"2024-05-04T10:00:00Z\"",
+                &format!("\"touched\":\"{touched}\""),
+            )
+        };
+        let body = format!(
+            "[{}, {}, {}]",
+            touched("old", "2024-05-01T00:00:00Z"),
+            touched("new", "2024-05-04T10:00:00.000001Z"),
+            touched("same", "2024-05-04T10:00:00Z"),
+        );
+        let (client, server) = mock_client(vec![
+            response(200, &body),
+            response(200, &format!("[{}]", touched("bad", "now"))),
+        ])
+        .await;
+        let since = crate::timestamp::parse_timestamp("2024-05-04T10:00:00Z").unwrap();
+        let modified = client
+            .rrset()
+            .get_rrsets_modified_since("example.com", since)
+            .await
+            .unwrap();
+        assert_eq!(modified.len(), 1);
+        assert_eq!(modified[0].subname.as_deref(), Some("new"));
+        let error = client
+            .rrset()
+            .get_rrsets_modified_since("example.com", since)
+            .await;
+        match error {
+            Err(Error::InvalidAPIResponse(_, touched)) => assert_eq!(touched, "now"),
+            _ => panic!("Should yield desec_api::Error::InvalidAPIResponse"),
+        }
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/domains/example.com/rrsets/ "));
+    }
+
+    // Zonefile in the format exported by deSEC
+    const EXPORTED_ZONEFILE: &str =
+        "; Zonefile for example.com exported from desec.io at 2024-05-04 12:00:00.000000+00:00
+example.com. 3600 IN SOA set.an.example. get.desec.io. 2024050401 86400 3600 2419200 3600
+example.com. 3600 IN NS ns1.desec.io.
+example.com. 3600 IN NS ns2.desec.org.
+example.com. 3600 IN A 192.0.2.1
+www.example.com. 300 IN CNAME example.com.
+_acme-challenge.example.com. 60 IN TXT \"token; with semicolon\"
+";
+
+    #[tokio::test]
+    async fn recommended_ttl_honors_minimum_ttl_of_domain() {
+        assert_eq!(RecordType::MX.recommended_ttl(60), 86400);
+        assert_eq!(RecordType::TXT.recommended_ttl(60), 300);
+        assert_eq!(RecordType::SRV.recommended_ttl(60), 3600);
+        assert_eq!(RecordType::A.recommended_ttl(900), 900);
+
+        let (client, server) = mock_client(vec![
+            response(200, &domain_json("example.com")),
+            response(
+                201,
+                &rrset_json("example.com", "www", "A", 3600, &["192.0.2.1"]),
+            ),
+        ])
+        .await;
+        client
+            .rrset()
+            .create_rrset_recommended_ttl(
+                "example.com",
+                Some("www"),
+                "a",
+                &["192.0.2.1".to_string()],
+            )
+            .await
+            .unwrap();
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/domains/example.com/ "));
+        assert_eq!(request_body(&requests[1])["ttl"], 3600);
+    }
+
+    #[test]
+    fn merged_rrset_keeps_order_and_drops_duplicates() {
+        let ours = rrset("www", "A", 3600, &["192.0.2.2", "192.0.2.1"]);
+        let theirs = rrset("www", "a", 300, &["192.0.2.3", "192.0.2.1", "192.0.2.3"]);
+        let merged = ours.merge(&theirs, TtlPolicy::Min).unwrap();
+        assert_eq!(merged.records, ["192.0.2.2", "192.0.2.1", "192.0.2.3"]);
+        assert_eq!(merged.rrset_type, RecordType::A);
+        assert_eq!(merged.subname.as_deref(), Some("www"));
+        assert_eq!(merged.ttl, 300);
+        assert_eq!(ours.merge(&theirs, TtlPolicy::Max).unwrap().ttl, 3600);
+        assert_eq!(
+            ours.merge(&theirs, TtlPolicy::Explicit(60)).unwrap().ttl,
+            60
+        );
+
+        let apex = ResourceRecordSet {
+            subname: None,
+            ..rrset("", "A", 3600, &["192.0.2.4"])
+        };
+        let merged = apex.merge(&rrset("", "A", 3600, &[]), TtlPolicy::Min);
+        assert_eq!(merged.unwrap().records, ["192.0.2.4"]);
+    }
+
+    #[test]
+    fn rrsets_of_other_subname_or_type_are_not_merged() {
+        let ours = rrset("www", "A", 3600, &["192.0.2.1"]);
+        for theirs in [
+            rrset("mail", "A", 3600, &["192.0.2.1"]),
+            rrset("www", "AAAA", 3600, &["2001:db8::1"]),
+        ] {
+            match ours.merge(&theirs, TtlPolicy::Min) {
+                Err(Error::InvalidInput(message)) => assert!(message.contains("www/A")),
+                _ => panic!("Should yield desec_api::Error::InvalidInput"),
+            }
+        }
+    }
+
+    #[test]
+    fn exported_zonefile_is_parsed() {
+        let rrsets = parse_zonefile(EXPORTED_ZONEFILE).unwrap();
+        let find = |subname: &str, rrset_type: &str| {
+            rrsets
+                .iter()
+                .find(|rrset| {
+                    rrset.subname.as_deref() == Some(subname) && rrset.rrset_type == rrset_type
+                })
+                .unwrap()
+        };
+        assert_eq!(rrsets.len(), 5);
+        assert!(rrsets.iter().all(|rrset| rrset.domain == "example.com"
+            && rrset.created.is_empty()
+            && rrset.touched.is_empty()));
+        assert_eq!(find("", "NS").records, ["ns1.desec.io.", "ns2.desec.org."]);
+        assert_eq!(find("www", "CNAME").ttl, 300);
+        assert_eq!(
+            find("_acme-challenge", "TXT").records,
+            ["\"token; with semicolon\""]
+        );
+    }
+
+    #[test]
+    fn record_type_is_parsed_case_insensitively() {
+        assert_eq!("aaaa".parse::<RecordType>().unwrap(), RecordType::AAAA);
+        assert_eq!("Cname".parse::<RecordType>().unwrap(), RecordType::CNAME);
+        assert_eq!(RecordType::from("tXt").to_string(), "TXT");
+
+        let svcb: RecordType = "Svcb".parse().unwrap();
+        assert_eq!(svcb, RecordType::Other("Svcb".to_string()));
+        assert_eq!(svcb.as_str(), "Svcb");
+        assert_eq!(svcb.to_string(), "SVCB");
+        assert_eq!(svcb, RecordType::Other("SVCB".to_string()));
+        assert!(svcb == "svcb");
+
+        let types: std::collections::HashSet<RecordType> = ["svcb", "SVCB", "Svcb"]
+            .into_iter()
+            .map(RecordType::from)
+            .collect();
+        assert_eq!(types.len(), 1);
+    }
+
+    #[test]
+    fn record_type_is_serialized_as_string() {
+        assert_eq!(
+            serde_json::to_string(&[RecordType::AAAA, RecordType::from("https")]).unwrap(),
+            r#"["AAAA","HTTPS"]"#
+        );
+        let types: Vec<RecordType> = serde_json::from_str(r#"["txt", "SVCB"]"#).unwrap();
+        assert_eq!(
+            types,
+            [RecordType::TXT, RecordType::Other("SVCB".to_string())]
+        );
+        assert_eq!(
+            rrset("www", "CNAME", 3600, &[]).record_type(),
+            RecordType::CNAME
+        );
+        let rrset: ResourceRecordSet = serde_json::from_str(
+            r#"{"created": "", "domain": "example.com", "subname": "www", "name": "www.example.com.", "type": "svcb", "ttl": 3600, "records": [], "touched": ""}"#,
+        )
+        .unwrap();
+        assert_eq!(rrset.rrset_type, RecordType::Other("SVCB".to_string()));
+        assert!(rrset.rrset_type == "Svcb");
+        #[allow(deprecated)]
+        let name = rrset.rrset_type_str();
+        assert_eq!(name, "svcb");
+        assert!(serde_json::to_string(&rrset)
+            .unwrap()
+            .contains(r#""type":"SVCB""#));
+    }
+
+    #[test]
+    fn addresses_are_formatted_and_parsed() {
+        let apex = ResourceRecordSet::a(None, 300, &[Ipv4Addr::new(192, 0, 2, 1)]);
+        assert_eq!(apex.subname.as_deref(), Some(""));
+        assert_eq!(apex.records, ["192.0.2.1"]);
+        assert_eq!(
+            apex.parsed_addresses().unwrap(),
+            [IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))]
+        );
+        let www = ResourceRecordSet::aaaa(Some("www"), 300, &[Ipv6Addr::LOCALHOST]);
+        assert_eq!(www.records, ["::1"]);
+        assert!(matches!(
+            rrset("www", "A", 300, &["not-an-ip"]).parsed_addresses(),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            rrset("www", "A", 300, &["::1"]).parsed_addresses(),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn builder_defaults_to_apex_and_requires_fields() {
+        let rrset = ResourceRecordSet::builder()
+            .domain("example.com")
+            .rrset_type(RecordType::MX)
+            .ttl(3600)
+            .records(vec!["10 mail.example.com.".to_string()])
+            .build()
+            .unwrap();
+        assert_eq!(rrset.subname.as_deref(), Some(""));
+        assert_eq!(rrset.name, "example.com.");
+        assert_eq!(rrset.rrset_type, "MX");
+        assert!(matches!(
+            ResourceRecordSet::builder()
+                .domain("example.com")
+                .ttl(3600)
+                .build(),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn ttl_below_domain_minimum_is_rejected() {
+        let domain = Domain {
+            name: "example.com".to_string(),
+            minimum_ttl: 3600,
+            ..Default::default()
+        };
+        assert!(rrset("www", "A", 3600, &["192.0.2.1"])
+            .validate_ttl(&domain)
+            .is_ok());
+        assert!(matches!(
+            rrset("www", "A", 300, &["192.0.2.1"]).validate_ttl(&domain),
+            Err(Error::InvalidInput(message)) if message.contains("minimum TTL 3600")
+        ));
+    }
+
+    #[test]
+    fn zone_sync_is_planned() {
+        let current = [
+            rrset("", "NS", 3600, &["ns1.desec.io.", "ns2.desec.org."]),
+            rrset(
+                "",
+                "SOA",
+                3600,
+                &["ns1.desec.io. get.desec.io. 1 86400 3600 2419200 3600"],
+            ),
+            rrset("www", "CNAME", 3600, &["example.com."]),
+            rrset("mail", "A", 3600, &["192.0.2.1"]),
+            rrset("old", "A", 3600, &["192.0.2.2"]),
+        ];
+        let desired = [
+            rrset("www", "CNAME", 3600, &["@"]),
+            rrset("mail", "A", 7200, &["192.0.2.1"]),
+            ResourceRecordSet::a(None, 60, &[Ipv4Addr::new(192, 0, 2, 3)]),
+        ];
+        let diff = plan_sync("example.com", 3600, &current, &desired);
+        assert_eq!(diff.to_create.len(), 1);
+        assert_eq!(diff.to_create[0].subname.as_deref(), Some(""));
+        assert_eq!(diff.to_create[0].domain, "example.com");
+        assert_eq!(diff.to_create[0].ttl, 3600);
+        assert_eq!(diff.to_update.len(), 1);
+        assert_eq!(diff.to_update[0].after.ttl, 7200);
+        assert_eq!(diff.to_delete.len(), 1);
+        assert_eq!(diff.to_delete[0].subname.as_deref(), Some("old"));
+        assert!(plan_sync("example.com", 3600, &current[..4], &current[2..4]).is_empty());
+        // A TTL below the minimum is no change, as it would be raised to the current TTL
+        let desired = [rrset("www", "CNAME", 60, &["example.com."])];
+        assert!(plan_sync("example.com", 3600, &current[2..3], &desired).is_empty());
+    }
+
+    #[test]
+    fn apex_is_sent_as_empty_subname_in_bulk_requests() {
+        assert_eq!(bulk_subname(None), "");
+        assert_eq!(bulk_subname(Some("@")), "");
+        assert_eq!(bulk_subname(Some("")), "");
+        assert_eq!(bulk_subname(Some("www")), "www");
+    }
+
+    #[test]
+    fn rendered_zonefile_is_parsed_into_same_rrsets() {
+        let fields = |rrsets: &[ResourceRecordSet]| {
+            rrsets
+                .iter()
+                .map(|rrset| {
+                    (
+                        rrset.domain.clone(),
+                        rrset.subname.clone(),
+                        rrset.name.clone(),
+                        rrset.rrset_type.clone(),
+                        rrset.ttl,
+                        rrset.records.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let rrsets = parse_zonefile(EXPORTED_ZONEFILE).unwrap();
+        let zonefile = to_zonefile("example.com", &rrsets);
+        assert!(zonefile.starts_with("$ORIGIN example.com.\n"));
+        let reparsed = parse_zonefile(&zonefile).unwrap();
+        assert_eq!(fields(&reparsed), fields(&rrsets));
+        assert_eq!(to_zonefile("example.com", &reparsed), zonefile);
+    }
+
+    #[test]
+    fn zonefile_directives_and_multiline_records_are_parsed() {
+        let zonefile = "$ORIGIN example.org.
+$TTL 600
+@ IN MX 10 mail
+mail IN A 192.0.2.2
+txt ( TXT \"first\"
+      \"second\" )
+";
+        let rrsets = parse_zonefile(zonefile).unwrap();
+        assert_eq!(rrsets.len(), 3);
+        assert_eq!(rrsets[0].subname.as_deref(), Some(""));
+        assert_eq!(rrsets[0].records, ["10 mail.example.org."]);
+        assert!(rrsets.iter().all(|rrset| rrset.ttl == 600));
+        assert_eq!(rrsets[2].records, ["\"first\" \"second\""]);
+        assert!(matches!(
+            parse_zonefile("www 3600 IN A 192.0.2.1"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn rrset_limits_are_enforced() {
+        let small = rrset("www", "TXT", 3600, &["\"a\"", "\"b\""]);
+        assert!(validate_rrset_limits(&[small]).is_ok());
+
+        let many = vec!["192.0.2.1"; MAX_RECORDS_PER_RRSET + 1];
+        match validate_rrset_limits(&[rrset("www", "A", 3600, &many)]) {
+            Err(Error::InvalidInput(message)) => {
+                assert!(message.contains("MAX_RECORDS_PER_RRSET"))
+            }
+            _ => panic!("Should yield desec_api::Error::InvalidInput"),
+        }
+
+        let large = "x".repeat(MAX_RRSET_RECORDS_BYTES + 1);
+        match validate_rrset_limits(&[rrset("www", "TXT", 3600, &[&large])]) {
+            Err(Error::InvalidInput(message)) => {
+                assert!(message.contains("MAX_RRSET_RECORDS_BYTES"))
+            }
+            _ => panic!("Should yield desec_api::Error::InvalidInput"),
+        }
+    }
+
+    #[test]
+    fn presentation_format_of_zone_snippet() {
+        let zone: String = [
+            rrset("", "NS", 3600, &["ns1.desec.io.", "ns2.desec.org."]),
+            rrset("", "MX", 3600, &["10 mail"]),
+            rrset("www", "A", 300, &["192.0.2.1"]),
+            rrset("www", "CNAME", 3600, &["@"]),
+            rrset("_sip._tcp", "SRV", 3600, &["10 60 5060 sip.example.com."]),
+            rrset("", "TXT", 3600, &["\"v=spf1 -all\""]),
+        ]
+        .iter()
+        .map(|rrset| rrset.to_presentation("example.com."))
+        .collect();
+        assert_eq!(
+            zone,
+            "example.com.\t3600\tIN\tNS\tns1.desec.io.\n\
+             example.com.\t3600\tIN\tNS\tns2.desec.org.\n\
+             example.com.\t3600\tIN\tMX\t10 mail.example.com.\n\
+             www.example.com.\t300\tIN\tA\t192.0.2.1\n\
+             www.example.com.\t3600\tIN\tCNAME\texample.com.\n\
+             _sip._tcp.example.com.\t3600\tIN\tSRV\t10 60 5060 sip.example.com.\n\
+             example.com.\t3600\tIN\tTXT\t\"v=spf1 -all\"\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejected_bulk_rrsets_are_keyed_by_index() {
+        let (client, server) = mock_client(vec![response(
+            400,
+            r#"[{}, {"records": ["Record content malformed."]}]"#,
+        )])
+        .await;
+        let rrsets = [
+            NewRrset::new(Some("www"), "a", 3600, vec!["192.0.2.1".to_string()]),
+            NewRrset::new(None, RecordType::A, 3600, vec!["192.0.2".to_string()]),
+        ];
+        let error = client
+            .rrset()
+            .create_rrsets("example.com", &rrsets)
+            .await
+            .unwrap_err();
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("POST /api/v1/domains/example.com/rrsets/ "));
+        let body = request_body(&requests[0]);
+        assert_eq!(body[0]["type"], "A");
+        assert_eq!(body[1]["type"], "A");
+        match error {
+            Error::ApiError(400, body) => {
+                assert!(body.field("0.records").is_empty());
+                assert_eq!(body.field("1.records"), ["Record content malformed."]);
+            }
+            _ => panic!("Should yield desec_api::Error::ApiError"),
+        }
+    }
+
+    #[tokio::test]
+    async fn rrsets_are_deleted_in_a_single_bulk_request() {
+        let (client, server) = mock_client_with(
+            Client::builder().max_body_bytes(1),
+            vec![response(204, ""), response(200, "[]"), response(409, "")],
+        )
+        .await;
+        let targets = [
+            (None, "A".to_string()),
+            (Some("@".to_string()), "AAAA".to_string()),
+            (Some("www".to_string()), "TXT".to_string()),
+        ];
+        let rrsets = client.rrset();
+        rrsets.delete_rrsets("example.com", &targets).await.unwrap();
+        rrsets.delete_rrsets("example.com", &targets).await.unwrap();
+        assert!(matches!(
+            rrsets.delete_rrsets("example.com", &targets).await,
+            Err(Error::UnexpectedStatusCode(409, _))
+        ));
+        rrsets.delete_rrsets("example.com", &[]).await.unwrap();
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("PATCH /api/v1/domains/example.com/rrsets/ "));
+        // The body limit does not split the request
+        assert_eq!(
+            request_body(&requests[0]),
+            serde_json::json!([
+                {"subname": "", "type": "A", "records": []},
+                {"subname": "", "type": "AAAA", "records": []},
+                {"subname": "www", "type": "TXT", "records": []}
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn ordered_rrsets_are_aligned_with_targets() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Answers the first targets last, so the requests complete in reverse order
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/api/v1", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buffer = [0u8; 1024];
+                    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                        let read = stream.read(&mut buffer).await.unwrap();
+                        request.extend_from_slice(&buffer[..read]);
+                    }
+                    let request = String::from_utf8(request).unwrap();
+                    let (delay, response) = if request.contains("/rrsets/@/A/") {
+                        let body = rrset_json("example.com", "", "A", 3600, &["192.0.2.1"]);
+                        (200, response(200, &body))
+                    } else if request.contains("/rrsets/www/AAAA/") {
+                        (100, response(404, ""))
+                    } else {
+                        let body = rrset_json("example.com", "www", "A", 3600, &["192.0.2.2"]);
+                        (0, response(200, &body))
+                    };
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                    stream.shutdown().await.unwrap();
+                });
+            }
+        });
+        let client = Client::builder()
+            .no_proxy()
+            .base_url(&base_url)
+            .build()
+            .unwrap();
+        let targets = [
+            (None, "A".to_string()),
+            (Some("www".to_string()), "AAAA".to_string()),
+            (Some("www".to_string()), "A".to_string()),
+        ];
+        let results = client
+            .rrset()
+            .get_rrsets_ordered("example.com", &targets)
+            .await;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().records, ["192.0.2.1"]);
+        assert!(matches!(results[1], Err(Error::NotFound { .. })));
+        assert_eq!(results[2].as_ref().unwrap().records, ["192.0.2.2"]);
+    }
+
+    #[tokio::test]
+    async fn query_filters_several_types_locally_and_limits() {
+        let body = format!(
+            "[{}, {}, {}, {}]",
+            rrset_json("example.com", "", "A", 3600, &["192.0.2.1"]),
+            rrset_json("example.com", "", "MX", 3600, &["10 mx.example.com."]),
+            rrset_json("example.com", "", "NS", 3600, &["ns1.desec.io."]),
+            rrset_json("example.com", "", "TXT", 3600, &["\"v=spf1 -all\""]),
+        );
+        let (client, server) = mock_client(vec![response(200, &body), response(200, &body)]).await;
+        let rrsets = client
+            .rrset()
+            .query("example.com")
+            .subname("")
+            .types(&["mx", "txt"])
+            .all()
+            .await
+            .unwrap();
+        assert_eq!(
+            rrsets
+                .iter()
+                .map(|rrset| rrset.rrset_type.to_string())
+                .collect::<Vec<_>>(),
+            ["MX", "TXT"]
+        );
+        let rrsets = client
+            .rrset()
+            .query("example.com")
+            .limit(2)
+            .all()
+            .await
+            .unwrap();
+        assert_eq!(rrsets.len(), 2);
+        assert!(matches!(
+            client
+                .rrset()
+                .query("example.com")
+                .subname("www")
+                .one()
+                .await,
+            Err(Error::InvalidInput(_))
+        ));
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/domains/example.com/rrsets/?subname= "));
+        assert!(requests[1].starts_with("GET /api/v1/domains/example.com/rrsets/ "));
+    }
+
+    #[tokio::test]
+    async fn rrset_stream_requests_next_page_only_when_needed() {
+        use futures_util::StreamExt;
+
+        let body = format!(
+            "[{}, {}]",
+            rrset_json("example.com", "", "A", 3600, &["192.0.2.1"]),
+            rrset_json("example.com", "www", "A", 3600, &["192.0.2.2"]),
+        );
+        let (client, server) = mock_client(vec![page_response(&body, Some("c1"))]).await;
+        let rrset_client = client.rrset();
+        let mut rrsets = Box::pin(rrset_client.stream_rrsets("example.com"));
+        assert_eq!(
+            rrsets.next().await.unwrap().unwrap().subname.as_deref(),
+            Some("")
+        );
+        assert_eq!(
+            rrsets.next().await.unwrap().unwrap().subname.as_deref(),
+            Some("www")
+        );
+        // The first page has been consumed without requesting the next one
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(matches!(rrsets.next().await, Some(Err(Error::Reqwest(_)))));
+        assert!(rrsets.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn rrsets_of_a_type_are_deleted_in_bulk() {
+        let body = format!(
+            "[{}, {}]",
+            rrset_json("example.com", "", "TXT", 3600, &["\"v=spf1 -all\""]),
+            rrset_json("example.com", "_dmarc", "TXT", 3600, &["\"v=DMARC1\""]),
+        );
+        let (client, server) = mock_client(vec![
+            response(200, &body),
+            response(204, ""),
+            response(200, "[]"),
+        ])
+        .await;
+        let rrsets = client.rrset();
+        assert_eq!(
+            rrsets
+                .delete_rrsets_by_type("example.com", RecordType::TXT)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            rrsets
+                .delete_rrsets_by_type("example.com", "txt")
+                .await
+                .unwrap(),
+            0
+        );
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("GET /api/v1/domains/example.com/rrsets/?type=TXT "));
+        assert_eq!(
+            request_body(&requests[1]),
+            serde_json::json!([
+                {"subname": "", "type": "TXT", "records": []},
+                {"subname": "_dmarc", "type": "TXT", "records": []}
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn rrsets_are_filtered_by_subname_and_type() {
+        let (client, server) = mock_client(vec![response(200, "[]")]).await;
+        let rrsets = client
+            .rrset()
+            .get_rrsets_filtered("example.com", Some("www"), Some("a"))
+            .await
+            .unwrap();
+        let requests = server.await.unwrap();
+        assert!(
+            requests[0].starts_with("GET /api/v1/domains/example.com/rrsets/?subname=www&type=A ")
+        );
+        assert!(rrsets.is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_rrset_is_none_for_try_get() {
+        let (client, server) = mock_client(vec![response(404, "")]).await;
+        let rrset = client
+            .rrset()
+            .try_get_rrset("example.com", Some("www"), "A")
+            .await;
+        assert!(rrset.unwrap().is_none());
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn removing_last_but_one_record_patches_rrset() {
+        let (client, server) = mock_client(vec![
+            response(
+                200,
+                &rrset_json("example.com", "www", "A", 3600, &["192.0.2.1", "192.0.2.2"]),
+            ),
+            response(
+                200,
+                &rrset_json("example.com", "www", "A", 3600, &["192.0.2.2"]),
+            ),
+        ])
+        .await;
+        let rrset = client
+            .rrset()
+            .remove_record("example.com", Some("www"), "A", "192.0.2.1")
+            .await
+            .unwrap()
+            .unwrap();
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/domains/example.com/rrsets/www/A/ "));
+        assert!(requests[1].starts_with("PATCH /api/v1/domains/example.com/rrsets/www/A/ "));
+        assert_eq!(rrset.records, ["192.0.2.2"]);
+    }
+
+    #[tokio::test]
+    async fn acme_challenge_is_set_in_owning_zone() {
+        let challenge = rrset_json(
+            "dev.example.net",
+            "_acme-challenge.www",
+            "TXT",
+            3600,
+            &["\"token\""],
+        );
+        let (client, server) = mock_client(vec![
+            response(200, &format!("[{}]", domain_json("dev.example.net"))),
+            response(200, &format!("[{challenge}]")),
+            response(200, &format!("[{}]", domain_json("other.example"))),
+        ])
+        .await;
+        // The zone is stripped from the challenge regardless of the case
+        client
+            .rrset()
+            .set_acme_challenge("Example.NET", Some("www.dev"), "token", 3600)
+            .await
+            .unwrap();
+        assert!(matches!(
+            client
+                .rrset()
+                .clear_acme_challenge("example.net", Some("www.dev"))
+                .await,
+            Err(Error::InvalidInput(_))
+        ));
+        let requests = server.await.unwrap();
+        assert!(requests[0]
+            .starts_with("GET /api/v1/domains/?owns_qname=_acme-challenge.www.dev.Example.NET "));
+        assert!(requests[1].starts_with("PUT /api/v1/domains/dev.example.net/rrsets/ "));
+        assert_eq!(
+            request_body(&requests[1])[0]["subname"],
+            "_acme-challenge.www"
+        );
+        assert_eq!(requests.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn zone_sync_applies_a_single_bulk_patch() {
+        let domain = domain_json("example.com");
+        let rrsets = format!(
+            "[{}, {}, {}]",
+            rrset_json("example.com", "", "NS", 3600, &["ns1.desec.io."]),
+            rrset_json("example.com", "mail", "A", 3600, &["192.0.2.1"]),
+            rrset_json("example.com", "old", "A", 3600, &["192.0.2.2"])
+        );
+        let (client, server) = mock_client(vec![
+            response(200, &domain),
+            response(200, &rrsets),
+            response(200, &domain),
+            response(200, &rrsets),
+            response(200, "[]"),
+        ])
+        .await;
+        let desired = [
+            ResourceRecordSet::a(Some("mail"), 60, &["192.0.2.3".parse().unwrap()]),
+            ResourceRecordSet::a(Some("www"), 7200, &["192.0.2.4".parse().unwrap()]),
+        ];
+        let planned = client
+            .rrset()
+            .sync_zone("example.com", &desired, true)
+            .await
+            .unwrap();
+        let applied = client
+            .rrset()
+            .sync_zone("example.com", &desired, false)
+            .await
+            .unwrap();
+        for diff in [&planned, &applied] {
+            assert_eq!(diff.to_create.len(), 1);
+            assert_eq!(diff.to_update.len(), 1);
+            assert_eq!(diff.to_update[0].after.ttl, 3600);
+            assert_eq!(diff.to_delete.len(), 1);
+        }
+
+        let requests = server.await.unwrap();
+        // The dry run only reads
+        assert!(requests[0].starts_with("GET /api/v1/domains/example.com/ "));
+        assert!(requests[1].starts_with("GET /api/v1/domains/example.com/rrsets/"));
+        assert!(requests[2].starts_with("GET /api/v1/domains/example.com/ "));
+        assert!(requests[3].starts_with("GET /api/v1/domains/example.com/rrsets/"));
+        assert!(requests[4].starts_with("PATCH /api/v1/domains/example.com/rrsets/ "));
+        assert_eq!(
+            request_body(&requests[4]),
+            serde_json::json!([
+                {"subname": "www", "type": "A", "ttl": 7200, "records": ["192.0.2.4"]},
+                {"subname": "mail", "type": "A", "ttl": 3600, "records": ["192.0.2.3"]},
+                {"subname": "old", "type": "A", "ttl": 3600, "records": []}
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn dynamic_dns_skips_matching_address() {
+        let (client, server) = mock_client(vec![response(
+            200,
+            &rrset_json("example.com", "home", "AAAA", 60, &["2001:db8::1"]),
+        )])
+        .await;
+        let updated = client
+            .rrset()
+            .sync_dynamic_dns(
+                "example.com",
+                Some("home"),
+                "2001:db8::1".parse().unwrap(),
+                60,
+            )
+            .await
+            .unwrap();
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/domains/example.com/rrsets/home/AAAA/ "));
+        assert!(!updated);
+    }
+
+    #[tokio::test]
+    async fn put_rrset_upserts_with_bulk_put() {
+        let (client, server) = mock_client(vec![response(
+            200,
+            &format!(
+                "[{}]",
+                rrset_json("example.com", "", "A", 3600, &["192.0.2.1"])
+            ),
+        )])
+        .await;
+        let rrset = client
+            .rrset()
+            .put_rrset("example.com", None, "A", 3600, &["192.0.2.1".to_string()])
+            .await
+            .unwrap()
+            .unwrap();
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("PUT /api/v1/domains/example.com/rrsets/ "));
+        assert_eq!(rrset.records, ["192.0.2.1"]);
+    }
+
+    #[tokio::test]
+    async fn text_values_are_quoted_once() {
+        let (client, server) = mock_client(vec![response(
+            201,
+            &rrset_json("example.com", "", "TXT", 3600, &["\"a\\\"b\"", "\"raw\""]),
+        )])
+        .await;
+        client
+            .rrset()
+            .create_rrset_with_values(
+                "example.com",
+                None,
+                "TXT",
+                3600,
+                &[
+                    RecordValue::Text("a\"b".to_string()),
+                    RecordValue::Raw("\"raw\"".to_string()),
+                ],
+            )
+            .await
+            .unwrap();
+        let requests = server.await.unwrap();
+        assert_eq!(
+            request_body(&requests[0])["records"],
+            serde_json::json!(["\"a\\\"b\"", "\"raw\""])
+        );
+    }
+
+    #[tokio::test]
+    async fn rrsets_are_grouped_by_subname() {
+        let rrsets = format!(
+            "[{}, {}, {}]",
+            rrset_json("example.com", "www", "TXT", 3600, &["\"text\""]),
+            rrset_json("example.com", "", "NS", 3600, &["ns1.desec.io."]),
+            rrset_json("example.com", "www", "A", 3600, &["192.0.2.1"])
+        );
+        let (client, server) = mock_client(vec![response(200, &rrsets)]).await;
+        let grouped = client
+            .rrset()
+            .get_rrsets_grouped("example.com")
+            .await
+            .unwrap();
+        server.await.unwrap();
+        assert_eq!(grouped.keys().collect::<Vec<_>>(), ["@", "www"]);
+        assert_eq!(grouped["@"][0].rrset_type, RecordType::NS);
+        let types = grouped["www"]
+            .iter()
+            .map(|rrset| rrset.rrset_type.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(types, ["A", "TXT"]);
+    }
+
+    #[tokio::test]
+    async fn unsupported_types_are_rejected_without_request() {
+        assert!(RecordType::A.is_supported_by_desec());
+        assert!(RecordType::from("SOA").is_known_unsupported_by_desec());
+        assert!(!RecordType::from("TYPE65000").is_known_unsupported_by_desec());
+        let (client, server) = mock_client(vec![]).await;
+        let result = client
+            .rrset()
+            .create_rrset(
+                "example.com",
+                None,
+                "SOA",
+                3600,
+                &["ns1.desec.io.".to_string()],
+            )
+            .await;
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+        assert!(server.await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fresh_cached_rrset_is_served_without_request() {
+        let (client, server) = mock_client_with(
+            Client::builder().rrset_cache_ttl(Duration::from_secs(60)),
+            vec![response(
+                200,
+                &rrset_json("example.com", "www", "A", 3600, &["192.0.2.1"]),
+            )],
+        )
+        .await;
+        let rrsets = client.rrset();
+        let first = rrsets.get_rrset("example.com", Some("www"), "A").await;
+        let second = rrsets.get_rrset("example.com", Some("www"), "A").await;
+        assert_eq!(first.unwrap().records, ["192.0.2.1"]);
+        assert_eq!(second.unwrap().records, ["192.0.2.1"]);
+        assert_eq!(server.await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn stale_cached_rrset_is_revalidated() {
+        let rrset = rrset_json("example.com", "www", "A", 3600, &["192.0.2.1"]);
+        let (client, server) = mock_client_with(
+            Client::builder().rrset_cache_ttl(Duration::ZERO),
+            vec![
+                response_with_headers(200, &[("ETag", "\"v1\"")], &rrset),
+                response_with_headers(304, &[("ETag", "\"v1\"")], ""),
+            ],
+        )
+        .await;
+        let key = cache_key("example.com", "www", "A");
+        let rrsets = client.rrset();
+        rrsets
+            .get_rrset("example.com", Some("www"), "A")
+            .await
+            .unwrap();
+        let fetched = client.rrset_cache.get(&key).unwrap().fetched;
+        let revalidated = rrsets.get_rrset("example.com", Some("www"), "A").await;
+        assert_eq!(revalidated.unwrap().records, ["192.0.2.1"]);
+        assert!(client.rrset_cache.get(&key).unwrap().fetched > fetched);
+
+        let requests = server.await.unwrap();
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\"\r\n"));
+    }
+
+    #[tokio::test]
+    async fn modified_rrset_replaces_cached_one() {
+        let (client, server) = mock_client_with(
+            Client::builder().rrset_cache_ttl(Duration::ZERO),
+            vec![
+                response_with_headers(
+                    200,
+                    &[("ETag", "\"v1\"")],
+                    &rrset_json("example.com", "www", "A", 3600, &["192.0.2.1"]),
+                ),
+                response_with_headers(
+                    200,
+                    &[("ETag", "\"v2\"")],
+                    &rrset_json("example.com", "www", "A", 3600, &["192.0.2.2"]),
+                ),
+            ],
+        )
+        .await;
+        let rrsets = client.rrset();
+        rrsets
+            .get_rrset("example.com", Some("www"), "A")
+            .await
+            .unwrap();
+        let modified = rrsets.get_rrset("example.com", Some("www"), "A").await;
+        assert_eq!(modified.unwrap().records, ["192.0.2.2"]);
+        let cached = client
+            .rrset_cache
+            .get(&cache_key("example.com", "www", "A"))
+            .unwrap();
+        assert_eq!(cached.rrset.records, ["192.0.2.2"]);
+        assert_eq!(cached.etag.as_deref(), Some("\"v2\""));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn changes_evict_cached_rrsets() {
+        let apex = rrset_json("example.com", "", "A", 3600, &["192.0.2.1"]);
+        let (client, server) = mock_client_with(
+            Client::builder().rrset_cache_ttl(Duration::from_secs(60)),
+            vec![
+                response(200, &apex),
+                response(200, &apex),
+                response(200, &apex),
+                response(204, ""),
+                response(200, &apex),
+                response(200, &format!("[{apex}]")),
+                response(200, &apex),
+            ],
+        )
+        .await;
+        let key = cache_key("example.com", "", "A");
+        let rrsets = client.rrset();
+        // The apex is cached once, however it is spelled
+        rrsets.get_rrset("example.com", None, "A").await.unwrap();
+        rrsets
+            .get_rrset("example.com", Some("@"), "a")
+            .await
+            .unwrap();
+        rrsets
+            .patch_rrset(
+                "example.com",
+                Some("@"),
+                "A",
+                &["192.0.2.1".to_string()],
+                3600,
+            )
+            .await
+            .unwrap();
+        assert!(client.rrset_cache.get(&key).is_none());
+
+        rrsets.get_rrset("example.com", None, "A").await.unwrap();
+        rrsets.delete_rrset("example.com", None, "A").await.unwrap();
+        assert!(client.rrset_cache.get(&key).is_none());
+
+        rrsets
+            .get_rrset("example.com", Some("@"), "A")
+            .await
+            .unwrap();
+        rrsets
+            .put_rrset(
+                "example.com",
+                Some(""),
+                "A",
+                3600,
+                &["192.0.2.1".to_string()],
+            )
+            .await
+            .unwrap();
+        assert!(client.rrset_cache.get(&key).is_none());
+
+        rrsets.get_rrset("example.com", None, "A").await.unwrap();
+        client.invalidate_rrset_cache();
+        assert!(client.rrset_cache.get(&key).is_none());
+
+        let requests = server.await.unwrap();
+        assert!(requests[1].starts_with("PATCH /api/v1/domains/example.com/rrsets/@/A/ "));
+        assert!(requests[3].starts_with("DELETE /api/v1/domains/example.com/rrsets/@/A/ "));
+        assert!(requests[5].starts_with("PUT /api/v1/domains/example.com/rrsets/ "));
+    }
+
+    #[test]
+    fn diff_ignores_whitespace_outside_of_quotes_only() {
+        let current = [
+            rrset("", "MX", 3600, &["10  mail.example.com."]),
+            rrset("", "TXT", 3600, &["\"a  b\" \"c\""]),
+        ];
+        let desired = [
+            rrset("@", "mx", 3600, &[" 10 mail.example.com. "]),
+            rrset("", "TXT", 3600, &["\"a b\"   \"c\""]),
+        ];
+        let changes = diff(&current, &desired);
+        assert!(changes.to_create.is_empty() && changes.to_delete.is_empty());
+        assert_eq!(changes.to_update.len(), 1);
+        assert_eq!(changes.to_update[0].after.records, ["\"a b\"   \"c\""]);
+        assert_eq!(
+            normalize_whitespace(" \"a \\\"  b\"\t \"c\" "),
+            "\"a \\\"  b\" \"c\""
+        );
+    }
+
+    #[test]
+    fn diff_merges_rrsets_given_twice() {
+        let current = [
+            rrset("www", "A", 300, &["192.0.2.1", "192.0.2.2"]),
+            rrset("old", "A", 300, &["192.0.2.3"]),
+        ];
+        let desired = [
+            rrset("www", "A", 3600, &["192.0.2.2"]),
+            rrset("new", "A", 300, &["192.0.2.4"]),
+            rrset("www", "A", 300, &["192.0.2.1"]),
+        ];
+        let changes = diff(&current, &desired);
+        assert!(changes.to_update.is_empty());
+        assert_eq!(changes.to_create.len(), 1);
+        assert_eq!(changes.to_create[0].subname.as_deref(), Some("new"));
+        assert_eq!(changes.to_delete.len(), 1);
+        assert_eq!(changes.to_delete[0].subname.as_deref(), Some("old"));
+
+        let desired = [
+            rrset("www", "A", 300, &["192.0.2.1"]),
+            rrset("www", "A", 60, &["192.0.2.3"]),
+        ];
+        let changes = diff(&current[..1], &desired);
+        assert_eq!(changes.to_update.len(), 1);
+        assert_eq!(changes.to_update[0].after.ttl, 60);
+        assert_eq!(
+            changes.to_update[0].after.records,
+            ["192.0.2.1", "192.0.2.3"]
+        );
+    }
 }
@@ -0,0 +1,104 @@
+//! Signing of requests with an HMAC, for deployments routing outbound API calls through a signing gateway.
+//!
+//! When a signer is configured via [`ClientBuilder::request_signer`][request_signer], every request
+//! (including retries and polls) carries the lowercase hex encoded HMAC of its canonical form in the
+//! [signature header][signature_header] (`X-Signature` by default).
+//!
+//! The canonical form is the concatenation of
+//! 1. the HTTP method in uppercase, e.g. `PATCH`,
+//! 2. a line feed (`\n`),
+//! 3. the path of the URL including the query string if present, e.g. `/api/v1/domains/?cursor=`,
+//! 4. a line feed (`\n`),
+//! 5. the exact bytes of the request body, nothing for requests without body.
+//!
+//! Since the body is signed as sent, enabling [canonical JSON][canonical_json] makes the signature
+//! reproducible for the gateway independent of the order of object keys.
+//!
+//! [request_signer]: ../struct.ClientBuilder.html#method.request_signer
+//! [signature_header]: ../struct.ClientBuilder.html#method.signature_header
+//! [canonical_json]: ../struct.ClientBuilder.html#method.canonical_json
+
+use core::fmt;
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha512};
+
+/// Name of the header carrying the signature used by default.
+pub const DEFAULT_SIGNATURE_HEADER: &str = "X-Signature";
+
+/// HMAC algorithm used to sign requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningAlgorithm {
+    HmacSha256,
+    HmacSha512,
+}
+
+/// Signs requests with a shared secret.
+#[derive(Clone)]
+pub(crate) struct RequestSigner {
+    key: Vec<u8>,
+    algorithm: SigningAlgorithm,
+    pub(crate) header: String,
+}
+
+impl fmt::Debug for RequestSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestSigner")
+            .field("key", &"<redacted>")
+            .field("algorithm", &self.algorithm)
+            .field("header", &self.header)
+            .finish()
+    }
+}
+
+impl RequestSigner {
+    pub(crate) fn new(key: &[u8], algorithm: SigningAlgorithm, header: &str) -> Self {
+        RequestSigner {
+            key: key.to_vec(),
+            algorithm,
+            header: header.to_string(),
+        }
+    }
+
+    /// Returns the hex encoded HMAC of the canonical form of the request.
+    pub(crate) fn sign(&self, method: &str, path: &str, body: &[u8]) -> String {
+        let message = [method.as_bytes(), b"\n", path.as_bytes(), b"\n", body];
+        let signature = match self.algorithm {
+            SigningAlgorithm::HmacSha256 => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(&self.key)
+                    .expect("HMAC accepts keys of any length");
+                message.iter().for_each(|part| mac.update(part));
+                mac.finalize().into_bytes().to_vec()
+            }
+            SigningAlgorithm::HmacSha512 => {
+                let mut mac = Hmac::<Sha512>::new_from_slice(&self.key)
+                    .expect("HMAC accepts keys of any length");
+                message.iter().for_each(|part| mac.update(part));
+                mac.finalize().into_bytes().to_vec()
+            }
+        };
+        signature.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_canonical_request() {
+        let signer = RequestSigner::new(b"secret", SigningAlgorithm::HmacSha256, "X-Signature");
+        assert_eq!(
+            signer.sign(
+                "PATCH",
+                "/api/v1/domains/example.com/rrsets/",
+                br#"[{"records":[],"subname":"www","type":"A"}]"#
+            ),
+            "bf7b0ca3623469ecffa9188af2e645bd8f3bd89455cec6367e77f5c8bd25d504"
+        );
+        let signer = RequestSigner::new(b"secret", SigningAlgorithm::HmacSha512, "X-Signature");
+        assert_eq!(
+            signer.sign("GET", "/api/v1/domains/?cursor=", b""),
+            "533e4acae902b3962b261213ca41d9eda73a16e3229b86ab351a79650f7c5bd9bc55b8e40838b0a372081432c41a24c398f8c171b49f5ed2fb3b4c14e668fda4"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Deduplication of identical GET requests which are in flight at the same time.
+
+use crate::{Client, Error};
+use reqwest::header::HeaderMap;
+use reqwest::{Response, ResponseBuilderExt, StatusCode, Url};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+/// Outcome published by the leading request, `None` if followers have to send their own request.
+type Outcome = Option<SharedResponse>;
+
+/// GET requests in flight by URL, shared across clones of the client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SingleFlight {
+    in_flight: Arc<Mutex<HashMap<String, watch::Receiver<Option<Outcome>>>>>,
+}
+
+/// A response read into memory, so it can be handed to every waiting caller.
+#[derive(Debug, Clone)]
+struct SharedResponse {
+    status: StatusCode,
+    url: Url,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl SharedResponse {
+    async fn read(response: Response) -> Result<Self, Error> {
+        Ok(SharedResponse {
+            status: response.status(),
+            url: response.url().clone(),
+            headers: response.headers().clone(),
+            body: response.bytes().await.map_err(Error::Reqwest)?.to_vec(),
+        })
+    }
+
+    fn to_response(&self) -> Response {
+        let mut builder = http::Response::builder()
+            .status(self.status)
+            .url(self.url.clone());
+        if let Some(headers) = builder.headers_mut() {
+            *headers = self.headers.clone();
+        }
+        Response::from(
+            builder
+                .body(self.body.clone())
+                .expect("the parts are taken from a valid response"),
+        )
+    }
+}
+
+enum Role {
+    Leader(watch::Sender<Option<Outcome>>),
+    Follower(watch::Receiver<Option<Outcome>>),
+}
+
+// Removes the entry of the leading request, also if its future is dropped.
+struct InFlightGuard<'a> {
+    single_flight: &'a SingleFlight,
+    url: &'a str,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.single_flight
+            .in_flight
+            .lock()
+            .expect("the single-flight lock is never poisoned")
+            .remove(self.url);
+    }
+}
+
+impl Client {
+    /// Sends a GET request, sharing the response with identical GET requests in flight.
+    ///
+    /// The first caller sends the request, all callers arriving until its response has been
+    /// read wait for and receive a copy of it. If the first request fails, is accepted for
+    /// asynchronous processing or its body exceeds the maximum body size of the client,
+    /// the waiting callers send their own request.
+    pub(crate) async fn get_single_flight(&self, url: String) -> Result<Response, Error> {
+        let role = {
+            let mut in_flight = self
+                .single_flight
+                .in_flight
+                .lock()
+                .expect("the single-flight lock is never poisoned");
+            match in_flight.get(&url) {
+                Some(receiver) => Role::Follower(receiver.clone()),
+                None => {
+                    let (sender, receiver) = watch::channel(None);
+                    in_flight.insert(url.clone(), receiver);
+                    Role::Leader(sender)
+                }
+            }
+        };
+        match role {
+            Role::Follower(mut receiver) => {
+                let outcome = loop {
+                    let current = receiver.borrow().clone();
+                    if let Some(outcome) = current {
+                        break outcome;
+                    }
+                    // The leading request has been dropped without publishing an outcome
+                    if receiver.changed().await.is_err() {
+                        break None;
+                    }
+                };
+                match outcome {
+                    Some(shared) => Ok(shared.to_response()),
+                    None => self.send_get(&url).await,
+                }
+            }
+            Role::Leader(sender) => {
+                let guard = InFlightGuard {
+                    single_flight: &self.single_flight,
+                    url: &url,
+                };
+                let response = match self.send_get(&url).await {
+                    Ok(response)
+                        if response.status() != StatusCode::ACCEPTED
+                            && response.content_length().unwrap_or_default()
+                                <= self.max_body_bytes as u64 =>
+                    {
+                        response
+                    }
+                    result => {
+                        sender.send_replace(Some(None));
+                        return result;
+                    }
+                };
+                let shared = match SharedResponse::read(response).await {
+                    Ok(shared) => shared,
+                    Err(error) => {
+                        sender.send_replace(Some(None));
+                        return Err(error);
+                    }
+                };
+                drop(guard);
+                // Copies of large bodies are not handed out, e.g. of a body without Content-Length
+                if shared.body.len() > self.max_body_bytes {
+                    sender.send_replace(Some(None));
+                } else {
+                    sender.send_replace(Some(Some(shared.clone())));
+                }
+                Ok(shared.to_response())
+            }
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use crate::Error;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// Parses a timestamp as returned by deSEC (e.g. `2024-05-04T12:34:56.123456Z`).
+pub(crate) fn parse_timestamp(timestamp: &str) -> Result<OffsetDateTime, Error> {
+    OffsetDateTime::parse(timestamp, &Rfc3339)
+        .map_err(|error| Error::InvalidAPIResponse(error.to_string(), timestamp.to_string()))
+}
@@ -1,14 +1,27 @@
+#[cfg(not(feature = "std"))]
+use crate::alloc_prelude::*;
+#[cfg(feature = "std")]
+use crate::pagination::Page;
+#[cfg(feature = "std")]
 use crate::{Client, Error};
+#[cfg(feature = "std")]
 use core::convert::From;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use futures_util::Stream;
+#[cfg(feature = "std")]
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use serde_json::{Map, Value};
 
 /// An asynchronous client to work with the deSEC token API.
+#[cfg(feature = "std")]
 pub struct TokenClient<'a> {
     pub(crate) client: &'a crate::Client,
 }
 
+#[cfg(feature = "std")]
 impl<'a> Client {
     /// Returns a wrapping client for the token API.
     pub fn token(&'a self) -> TokenClient<'a> {
@@ -19,7 +32,11 @@ impl<'a> Client {
 /// Representation of a deSEC [`token`][reference].
 ///
 /// [reference]: https://desec.readthedocs.io/en/latest/auth/tokens.html#token-field-reference
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+///
+/// The `Debug` output redacts the token secret, which is only returned on creation.
+/// With the `zeroize` feature, the secret is overwritten with zeros on drop.
+/// `Token` implements `Drop` regardless of the features, use [`Option::take`] to move the secret out.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Token {
     pub created: String,
     pub id: String,
@@ -32,6 +49,163 @@ pub struct Token {
     pub token: Option<String>,
 }
 
+impl core::fmt::Debug for Token {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Token")
+            .field("created", &self.created)
+            .field("id", &self.id)
+            .field("last_used", &self.last_used)
+            .field("name", &self.name)
+            .field("perm_manage_tokens", &self.perm_manage_tokens)
+            .field("allowed_subnets", &self.allowed_subnets)
+            .field("max_age", &self.max_age)
+            .field("max_unused_period", &self.max_unused_period)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl Drop for Token {
+    fn drop(&mut self) {
+        if let Some(token) = self.token.as_mut() {
+            crate::scrub(token);
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl Token {
+    /// Returns the time the token has been created.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if `created` is not a valid timestamp.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn created_at(&self) -> Result<time::OffsetDateTime, Error> {
+        crate::timestamp::parse_timestamp(&self.created)
+    }
+
+    /// Returns the time the token has last been used, if any.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::InvalidAPIResponse`][error] if `last_used` is not a valid timestamp.
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn last_used_at(&self) -> Result<Option<time::OffsetDateTime>, Error> {
+        self.last_used
+            .as_deref()
+            .map(crate::timestamp::parse_timestamp)
+            .transpose()
+    }
+}
+
+impl Token {
+    /// Returns whether the token is allowed to manage tokens and their policies.
+    pub fn can_manage_tokens(&self) -> bool {
+        self.perm_manage_tokens
+    }
+}
+
+#[cfg(feature = "std")]
+impl Token {
+    /// Returns the maximum age of the token, `None` if it is unlimited.
+    ///
+    /// # Errors
+    ///
+    /// The inner result is [`Error::InvalidAPIResponse`][error] if `max_age` is not a valid duration,
+    /// see [`parse_duration`].
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn max_age_duration(&self) -> Option<Result<Duration, Error>> {
+        self.max_age.as_deref().map(parse_duration)
+    }
+
+    /// Returns the period after which the token expires if unused, `None` if it is unlimited.
+    ///
+    /// # Errors
+    ///
+    /// The inner result is [`Error::InvalidAPIResponse`][error] if `max_unused_period` is not a valid duration,
+    /// see [`parse_duration`].
+    ///
+    /// [error]: ../enum.Error.html
+    pub fn max_unused_period_duration(&self) -> Option<Result<Duration, Error>> {
+        self.max_unused_period.as_deref().map(parse_duration)
+    }
+}
+
+/// Parses a duration in the format `[D ]HH:MM:SS[.ffffff]` used by deSEC, e.g. `"30 00:00:00"`.
+///
+/// # Errors
+///
+/// This method fails with [`Error::InvalidAPIResponse`][error] if the value does not match the format.
+///
+/// [error]: ../enum.Error.html
+#[cfg(feature = "std")]
+pub fn parse_duration(value: &str) -> Result<Duration, Error> {
+    let invalid = || {
+        Error::InvalidAPIResponse(
+            "duration does not match the format [D ]HH:MM:SS[.ffffff]".to_string(),
+            value.to_string(),
+        )
+    };
+    let number = |digits: &str| -> Result<u64, Error> {
+        if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        digits.parse().map_err(|_| invalid())
+    };
+    let (days, time) = match value.trim().split_once(' ') {
+        Some((days, time)) => (number(days)?, time),
+        None => (0, value.trim()),
+    };
+    let (time, micros) = match time.split_once('.') {
+        Some((time, fraction)) if fraction.len() <= 6 => (
+            time,
+            number(fraction)? * 10u64.pow(6 - fraction.len() as u32),
+        ),
+        Some(_) => return Err(invalid()),
+        None => (time, 0),
+    };
+    let mut parts = time.split(':');
+    let (hours, minutes, seconds) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(hours), Some(minutes), Some(seconds), None) => {
+            (number(hours)?, number(minutes)?, number(seconds)?)
+        }
+        _ => return Err(invalid()),
+    };
+    if minutes >= 60 || seconds >= 60 {
+        return Err(invalid());
+    }
+    let seconds = days
+        .checked_mul(86400)
+        .and_then(|days| days.checked_add(hours.checked_mul(3600)?))
+        .and_then(|total| total.checked_add(minutes * 60 + seconds))
+        .ok_or_else(invalid)?;
+    Ok(Duration::from_secs(seconds) + Duration::from_micros(micros))
+}
+
+/// Formats a duration in the format `[D ]HH:MM:SS[.ffffff]` used by deSEC, e.g. `"30 00:00:00"`.
+///
+/// Fractions of a second below a microsecond are truncated.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let mut formatted = format!(
+        "{:02}:{:02}:{:02}",
+        seconds % 86400 / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    );
+    if duration.subsec_micros() > 0 {
+        formatted = format!("{formatted}.{:06}", duration.subsec_micros());
+    }
+    if seconds >= 86400 {
+        formatted = format!("{} {formatted}", seconds / 86400);
+    }
+    formatted
+}
+
 /// Representation of a deSEC [`token policy`][reference].
 ///
 /// [reference]: https://desec.readthedocs.io/en/latest/auth/tokens.html#token-policy-field-reference
@@ -44,14 +218,76 @@ pub struct TokenPolicy {
     pub perm_write: bool,
 }
 
+impl TokenPolicy {
+    /// Returns whether the policy applies to the RRset given by domain, subname and type.
+    ///
+    /// Fields which are not set match any value. Domains and types are compared case-insensitively.
+    pub fn matches(&self, domain: &str, subname: &str, rrset_type: &str) -> bool {
+        self.domain.as_deref().map_or(true, |policy_domain| {
+            policy_domain
+                .trim_end_matches('.')
+                .eq_ignore_ascii_case(domain.trim_end_matches('.'))
+        }) && self
+            .subname
+            .as_deref()
+            .map_or(true, |policy_subname| policy_subname == subname)
+            && self.r#type.as_deref().map_or(true, |policy_type| {
+                policy_type.eq_ignore_ascii_case(rrset_type)
+            })
+    }
+
+    /// Returns whether this is the default policy of a token, which matches any RRset.
+    pub fn is_default(&self) -> bool {
+        self.specificity() == (false, false, false)
+    }
+
+    // Specificity in deSEC's order of precedence: domain before subname before type.
+    fn specificity(&self) -> (bool, bool, bool) {
+        (
+            self.domain.is_some(),
+            self.subname.is_some(),
+            self.r#type.is_some(),
+        )
+    }
+}
+
+/// Returns the policy governing writes to the RRset given by domain, subname (empty for the
+/// zone apex) and type, following deSEC's [precedence rules][reference].
+///
+/// Of all matching policies the most specific one wins, where a policy for a domain is more
+/// specific than any policy without domain, regardless of subname and type. Among policies
+/// with the same domain specificity, a subname is more specific than a type.
+/// The default policy (without domain, subname and type) is the fallback.
+/// Returns `None` if no policy matches, in which case deSEC denies the write.
+///
+/// [reference]: https://desec.readthedocs.io/en/latest/auth/tokens.html#token-scoping-policies
+pub fn matching_policy<'a>(
+    policies: &'a [TokenPolicy],
+    domain: &str,
+    subname: &str,
+    rrset_type: &str,
+) -> Option<&'a TokenPolicy> {
+    policies
+        .iter()
+        .filter(|policy| policy.matches(domain, subname, rrset_type))
+        .max_by_key(|policy| policy.specificity())
+}
+
+#[cfg(feature = "std")]
 impl<'a> TokenClient<'a> {
     /// Creates a new token.
     ///
+    /// Creating tokens requires the authenticating token to have `perm_manage_tokens`.
+    /// For clients created from credentials this is checked before sending the request.
+    ///
     /// # Errors
     ///
+    /// This method fails with [`Error::MissingPermission`][error] if the client is known to lack `perm_manage_tokens`.
+    ///
     /// see [General errors][general_errors]
     ///
     /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [error]: ../enum.Error.html
     pub async fn create(
         &self,
         name: Option<String>,
@@ -60,21 +296,43 @@ impl<'a> TokenClient<'a> {
         max_age: Option<String>,
         max_unused_period: Option<String>,
     ) -> Result<Token, Error> {
-        let payload_map = construct_token_payload(
+        self.create_token(TokenBuilder {
             name,
             allowed_subnets,
             perm_manage_tokens,
             max_age,
             max_unused_period,
-        );
-        let payload = Some(serde_json::to_string(&payload_map).unwrap());
+        })
+        .await
+    }
+
+    /// Creates a new token with the fields set on the given [`TokenBuilder`][builder].
+    ///
+    /// Creating tokens requires the authenticating token to have `perm_manage_tokens`.
+    /// For clients created from credentials this is checked before sending the request.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::MissingPermission`][error] if the client is known to lack `perm_manage_tokens`.
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [error]: ../enum.Error.html
+    /// [builder]: struct.TokenBuilder.html
+    pub async fn create_token(&self, token: TokenBuilder) -> Result<Token, Error> {
+        if self.client.perm_manage_tokens == Some(false) {
+            return Err(Error::MissingPermission(
+                "creating tokens requires a token with perm_manage_tokens".to_string(),
+            ));
+        }
+        let payload = Some(self.client.serialize_body(&token.payload())?);
         // Send create token request
         let response = self.client.post("/auth/tokens/", payload).await?;
         match response.status() {
             StatusCode::CREATED => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+                let response_text = crate::response_text(response).await?;
+                crate::parse_secret(response_text)
             }
             _ => Err(Error::UnexpectedStatusCode(
                 response.status().into(),
@@ -106,7 +364,8 @@ impl<'a> TokenClient<'a> {
 
     /// List all tokens.
     ///
-    /// Up to 500 items are returned at a time. Pagination is currently no implemented by this client.
+    /// All pages of the listing are followed by their cursor announced in the `Link` header,
+    /// so more than 500 tokens are returned. Throttled page requests are retried like any other request.
     ///
     /// # Errors
     ///
@@ -114,18 +373,77 @@ impl<'a> TokenClient<'a> {
     ///
     /// [general_errors]: ../index.html#general-errors-for-all-clients
     pub async fn list(&self) -> Result<Vec<Token>, Error> {
-        let response = self.client.get("/auth/tokens/").await?;
-        match response.status() {
-            StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
-            }
-            _ => Err(Error::UnexpectedStatusCode(
-                response.status().into(),
-                response.text().await.unwrap_or_default(),
-            )),
-        }
+        self.client.get_all_pages("/auth/tokens/").await
+    }
+
+    /// Retrieves a single page of the tokens.
+    ///
+    /// Pass `None` to get the first page and the [`next_cursor`][page] of a page to get the following one.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [page]: ../pagination/struct.Page.html
+    pub async fn list_page(&self, cursor: Option<&str>) -> Result<Page<Token>, Error> {
+        self.client.get_page("/auth/tokens/", cursor).await
+    }
+
+    /// Lazily streams all tokens, page by page.
+    ///
+    /// The next page is only requested once all tokens of the current page have been consumed.
+    /// The stream ends after yielding the first error.
+    ///
+    /// ```no_run
+    /// use desec_api::Client;
+    /// use futures_util::{pin_mut, StreamExt};
+    ///
+    /// # async fn run(client: Client) {
+    /// let tokens = client.token().tokens_stream();
+    /// pin_mut!(tokens);
+    /// while let Some(token) = tokens.next().await {
+    ///     println!("{:?}", token);
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub fn tokens_stream(&self) -> impl Stream<Item = Result<Token, Error>> + 'a {
+        self.client.stream_items("/auth/tokens/".to_string())
+    }
+
+    /// Returns the number of tokens.
+    ///
+    /// See [pagination][pagination] on how the count is determined.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [pagination]: ../pagination/index.html
+    pub async fn count(&self) -> Result<u64, Error> {
+        self.client.count_items::<Token>("/auth/tokens/").await
+    }
+
+    /// Retrieves a specific token, `None` if it does not exist.
+    ///
+    /// Unlike [`get`][get], a missing resource is not reported as [`Error::NotFound`][error].
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [get]: #method.get
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn try_get(&self, token_id: &str) -> Result<Option<Token>, Error> {
+        crate::not_found_as_none(self.get(token_id).await)
     }
 
     /// Retrieves a specific token.
@@ -142,9 +460,8 @@ impl<'a> TokenClient<'a> {
             .await?;
         match response.status() {
             StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+                let response_text = crate::response_text(response).await?;
+                crate::parse_single(response_text)
             }
             _ => Err(Error::UnexpectedStatusCode(
                 response.status().into(),
@@ -169,21 +486,36 @@ impl<'a> TokenClient<'a> {
         max_age: Option<String>,
         max_unused_period: Option<String>,
     ) -> Result<Token, Error> {
-        let payload_map = construct_token_payload(
-            name,
-            allowed_subnets,
-            perm_manage_tokens,
-            max_age,
-            max_unused_period,
-        );
-        let payload = serde_json::to_string(&payload_map).unwrap();
+        self.update_token(
+            token_id,
+            TokenBuilder {
+                name,
+                allowed_subnets,
+                perm_manage_tokens,
+                max_age,
+                max_unused_period,
+            },
+        )
+        .await
+    }
+
+    /// Updates the fields of a token set on the given [`TokenBuilder`][builder], all other fields are kept.
+    ///
+    /// # Errors
+    ///
+    /// see [General errors][general_errors]
+    ///
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [builder]: struct.TokenBuilder.html
+    pub async fn update_token(&self, token_id: &str, token: TokenBuilder) -> Result<Token, Error> {
+        let payload = self.client.serialize_body(&token.payload())?;
         let response = self
             .client
             .patch(format!("/auth/tokens/{token_id}/").as_str(), payload)
             .await?;
         match response.status() {
             StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
+                let response_text = crate::response_text(response).await?;
                 serde_json::from_str(&response_text)
                     .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
             }
@@ -198,8 +530,12 @@ impl<'a> TokenClient<'a> {
     ///
     /// # Errors
     ///
-    /// see [General errors][general_errors]
+    /// This method fails with [`Error::MissingPermission`][error] if the client is known to lack `perm_manage_tokens`
+    /// or the API denies access to the policies.
     ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
     /// [general_errors]: ../index.html#general-errors-for-all-clients
     pub async fn create_policy(
         &self,
@@ -209,18 +545,91 @@ impl<'a> TokenClient<'a> {
         r#type: Option<String>,
         perm_write: Option<bool>,
     ) -> Result<TokenPolicy, Error> {
-        let payload_map = construct_policy_payload(domain, subname, r#type, perm_write);
-        let payload = Some(serde_json::to_string(&payload_map).unwrap());
+        self.check_policy_permission()?;
+        self.post_policy(
+            token_id,
+            construct_policy_payload(domain, subname, r#type, perm_write),
+        )
+        .await
+    }
+
+    /// Creates the default policy of a token, which applies to all RRsets not matched by a more
+    /// specific policy. deSEC requires the default policy to exist before any other policy is created.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::MissingPermission`][error] if the client is known to lack `perm_manage_tokens`
+    /// or the API denies access to the policies.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn create_default_policy(
+        &self,
+        token_id: &str,
+        perm_write: bool,
+    ) -> Result<TokenPolicy, Error> {
+        self.create_token_policy(token_id, TokenPolicyBuilder::new().perm_write(perm_write))
+            .await
+    }
+
+    /// Creates a token policy with the fields set on the given [`TokenPolicyBuilder`][builder].
+    ///
+    /// deSEC requires the default policy (see [`create_default_policy`][default]) to exist before
+    /// any more specific policy. Before creating a specific policy, the existing policies are
+    /// retrieved to check for the default policy.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with
+    /// - [`Error::MissingPermission`][error] if the client is known to lack `perm_manage_tokens`
+    ///   or the API denies access to the policies
+    /// - [`Error::InvalidInput`][error] if a specific policy is to be created but the token has no default policy
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    /// [builder]: struct.TokenPolicyBuilder.html
+    /// [default]: #method.create_default_policy
+    pub async fn create_token_policy(
+        &self,
+        token_id: &str,
+        policy: TokenPolicyBuilder,
+    ) -> Result<TokenPolicy, Error> {
+        self.check_policy_permission()?;
+        if !policy.is_default()
+            && !self
+                .list_policies(token_id)
+                .await?
+                .iter()
+                .any(TokenPolicy::is_default)
+        {
+            return Err(Error::InvalidInput(format!(
+                "token {token_id} needs a default policy before a specific policy can be created"
+            )));
+        }
+        self.post_policy(token_id, policy.payload()).await
+    }
+
+    async fn post_policy(
+        &self,
+        token_id: &str,
+        payload_map: Map<String, Value>,
+    ) -> Result<TokenPolicy, Error> {
+        let payload = Some(self.client.serialize_body(&payload_map)?);
         let response = self
             .client
             .post(
                 format!("/auth/tokens/{token_id}/policies/rrsets/").as_str(),
                 payload,
             )
-            .await?;
+            .await
+            .map_err(policy_permission_error)?;
         match response.status() {
             StatusCode::CREATED => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
+                let response_text = crate::response_text(response).await?;
                 serde_json::from_str(&response_text)
                     .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
             }
@@ -235,8 +644,12 @@ impl<'a> TokenClient<'a> {
     ///
     /// # Errors
     ///
-    /// see [General errors][general_errors]
+    /// This method fails with [`Error::MissingPermission`][error] if the client is known to lack `perm_manage_tokens`
+    /// or the API denies access to the policies.
     ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
     /// [general_errors]: ../index.html#general-errors-for-all-clients
     pub async fn patch_policy(
         &self,
@@ -247,18 +660,20 @@ impl<'a> TokenClient<'a> {
         r#type: Option<String>,
         perm_write: Option<bool>,
     ) -> Result<TokenPolicy, Error> {
+        self.check_policy_permission()?;
         let payload_map = construct_policy_payload(domain, subname, r#type, perm_write);
-        let payload = serde_json::to_string(&payload_map).unwrap();
+        let payload = self.client.serialize_body(&payload_map)?;
         let response = self
             .client
             .patch(
                 format!("/auth/tokens/{token_id}/policies/rrsets/{policy_id}/").as_str(),
                 payload,
             )
-            .await?;
+            .await
+            .map_err(policy_permission_error)?;
         match response.status() {
             StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
+                let response_text = crate::response_text(response).await?;
                 serde_json::from_str(&response_text)
                     .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
             }
@@ -269,23 +684,50 @@ impl<'a> TokenClient<'a> {
         }
     }
 
+    /// Retrieves a specific token policy, `None` if it does not exist.
+    ///
+    /// Unlike [`get_policy`][get_policy], a missing resource is not reported as [`Error::NotFound`][error].
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::MissingPermission`][error] if the client is known to lack `perm_manage_tokens`
+    /// or the API denies access to the policies.
+    ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [get_policy]: #method.get_policy
+    /// [error]: ../enum.Error.html
+    /// [general_errors]: ../index.html#general-errors-for-all-clients
+    pub async fn try_get_policy(
+        &self,
+        token_id: &str,
+        policy_id: &str,
+    ) -> Result<Option<TokenPolicy>, Error> {
+        crate::not_found_as_none(self.get_policy(token_id, policy_id).await)
+    }
+
     /// Retrieves a specific token policy.
     ///
     /// # Errors
     ///
-    /// see [General errors][general_errors]
+    /// This method fails with [`Error::MissingPermission`][error] if the client is known to lack `perm_manage_tokens`
+    /// or the API denies access to the policies.
     ///
+    /// Further errors, see [General errors][general_errors].
+    ///
+    /// [error]: ../enum.Error.html
     /// [general_errors]: ../index.html#general-errors-for-all-clients
     pub async fn get_policy(&self, token_id: &str, policy_id: &str) -> Result<TokenPolicy, Error> {
+        self.check_policy_permission()?;
         let response = self
             .client
             .get(format!("/auth/tokens/{token_id}/policies/rrsets/{policy_id}/").as_str())
-            .await?;
+            .await
+            .map_err(policy_permission_error)?;
         match response.status() {
             StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
+                let response_text = crate::response_text(response).await?;
+                crate::parse_single(response_text)
             }
             _ => Err(Error::UnexpectedStatusCode(
                 response.status().into(),
@@ -296,41 +738,44 @@ impl<'a> TokenClient<'a> {
 
     /// Get all policies for the given token.
     ///
+    /// All pages of the listing are followed, the policies are returned in the order of the API
+    /// across pages. The listing ends with the first page without a next cursor, even if it is empty.
+    ///
     /// # Errors
     ///
-    /// see [General errors][general_errors]
+    /// This method fails with [`Error::MissingPermission`][error] if the client is known to lack `perm_manage_tokens`
+    /// or the API denies access to the policies.
+    ///
+    /// Further errors, see [General errors][general_errors].
     ///
+    /// [error]: ../enum.Error.html
     /// [general_errors]: ../index.html#general-errors-for-all-clients
     pub async fn list_policies(&self, token_id: &str) -> Result<Vec<TokenPolicy>, Error> {
-        let response = self
-            .client
-            .get(format!("/auth/tokens/{token_id}/policies/rrsets/").as_str())
-            .await?;
-        match response.status() {
-            StatusCode::OK => {
-                let response_text = response.text().await.map_err(Error::Reqwest)?;
-                serde_json::from_str(&response_text)
-                    .map_err(|error| Error::InvalidAPIResponse(error.to_string(), response_text))
-            }
-            _ => Err(Error::UnexpectedStatusCode(
-                response.status().into(),
-                response.text().await.unwrap_or_default(),
-            )),
-        }
+        self.check_policy_permission()?;
+        self.client
+            .get_all_pages(format!("/auth/tokens/{token_id}/policies/rrsets/").as_str())
+            .await
+            .map_err(policy_permission_error)
     }
 
     /// Deletes a specific token policy.
     ///
     /// # Errors
     ///
-    /// see [General errors][general_errors]
+    /// This method fails with [`Error::MissingPermission`][error] if the client is known to lack `perm_manage_tokens`
+    /// or the API denies access to the policies.
+    ///
+    /// Further errors, see [General errors][general_errors].
     ///
+    /// [error]: ../enum.Error.html
     /// [general_errors]: ../index.html#general-errors-for-all-clients
     pub async fn delete_policy(&self, token_id: &str, policy_id: &str) -> Result<(), Error> {
+        self.check_policy_permission()?;
         let response = self
             .client
             .delete(format!("/auth/tokens/{token_id}/policies/rrsets/{policy_id}/").as_str())
-            .await?;
+            .await
+            .map_err(policy_permission_error)?;
         match response.status() {
             StatusCode::NO_CONTENT => Ok(()),
             _ => Err(Error::UnexpectedStatusCode(
@@ -339,9 +784,32 @@ impl<'a> TokenClient<'a> {
             )),
         }
     }
+
+    // Fails early if the client is known to lack the permission to manage token policies.
+    fn check_policy_permission(&self) -> Result<(), Error> {
+        if self.client.perm_manage_tokens == Some(false) {
+            return Err(Error::MissingPermission(
+                POLICY_PERMISSION_MESSAGE.to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+const POLICY_PERMISSION_MESSAGE: &str = "managing token policies requires perm_manage_tokens";
+
+// A restricted token gets a bare 403 on the policy endpoints, so point to the missing permission.
+#[cfg(feature = "std")]
+fn policy_permission_error(error: Error) -> Error {
+    match error {
+        Error::Forbidden { .. } => Error::MissingPermission(POLICY_PERMISSION_MESSAGE.to_string()),
+        error => error,
+    }
 }
 
 // Construct token policy payload for CREATE and PATCH
+#[cfg(feature = "std")]
 fn construct_policy_payload(
     domain: Option<String>,
     subname: Option<String>,
@@ -362,7 +830,165 @@ fn construct_policy_payload(
     payload_map
 }
 
+/// Fields of a token policy to create with [`TokenClient::create_token_policy`][create_token_policy].
+///
+/// Fields which are not set match any domain, subname or type. Without any of them set, the
+/// builder describes the default policy of a token.
+///
+/// ```
+/// use desec_api::token::TokenPolicyBuilder;
+///
+/// let policy = TokenPolicyBuilder::new()
+///     .domain("example.com")
+///     .subname("_acme-challenge")
+///     .rrset_type("TXT")
+///     .perm_write(true);
+/// ```
+///
+/// [create_token_policy]: struct.TokenClient.html#method.create_token_policy
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenPolicyBuilder {
+    domain: Option<String>,
+    subname: Option<String>,
+    rrset_type: Option<String>,
+    perm_write: bool,
+}
+
+#[cfg(feature = "std")]
+impl TokenPolicyBuilder {
+    /// Creates a new builder for a read-only default policy.
+    pub fn new() -> Self {
+        TokenPolicyBuilder::default()
+    }
+
+    /// Restricts the policy to the given domain.
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domain = Some(domain.to_string());
+        self
+    }
+
+    /// Restricts the policy to the given subname, use an empty subname for the zone apex.
+    pub fn subname(mut self, subname: &str) -> Self {
+        self.subname = Some(subname.to_string());
+        self
+    }
+
+    /// Restricts the policy to the given type.
+    pub fn rrset_type(mut self, rrset_type: &str) -> Self {
+        self.rrset_type = Some(rrset_type.to_string());
+        self
+    }
+
+    /// Sets whether matching RRsets may be written.
+    pub fn perm_write(mut self, perm_write: bool) -> Self {
+        self.perm_write = perm_write;
+        self
+    }
+
+    /// Returns whether the builder describes the default policy of a token.
+    pub fn is_default(&self) -> bool {
+        self.domain.is_none() && self.subname.is_none() && self.rrset_type.is_none()
+    }
+
+    fn payload(self) -> Map<String, Value> {
+        construct_policy_payload(
+            self.domain,
+            self.subname,
+            self.rrset_type,
+            Some(self.perm_write),
+        )
+    }
+}
+
+/// Fields of a token to create with [`TokenClient::create_token`][create_token]
+/// or to update with [`TokenClient::update_token`][update_token].
+///
+/// Fields which are not set are left to the defaults of the API on creation and unchanged on update.
+///
+/// ```
+/// use desec_api::token::TokenBuilder;
+///
+/// let token = TokenBuilder::new()
+///     .name("ddns")
+///     .allowed_subnets(vec!["192.0.2.0/24".to_string()])
+///     .max_unused_period("30 00:00:00");
+/// ```
+///
+/// [create_token]: struct.TokenClient.html#method.create_token
+/// [update_token]: struct.TokenClient.html#method.update_token
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenBuilder {
+    name: Option<String>,
+    allowed_subnets: Option<Vec<String>>,
+    perm_manage_tokens: Option<bool>,
+    max_age: Option<String>,
+    max_unused_period: Option<String>,
+}
+
+#[cfg(feature = "std")]
+impl TokenBuilder {
+    /// Creates a new builder without any fields set.
+    pub fn new() -> Self {
+        TokenBuilder::default()
+    }
+
+    /// Sets the name of the token.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Sets the subnets (in CIDR notation) from which the token may be used.
+    pub fn allowed_subnets(mut self, allowed_subnets: Vec<String>) -> Self {
+        self.allowed_subnets = Some(allowed_subnets);
+        self
+    }
+
+    /// Sets whether the token may manage tokens.
+    pub fn perm_manage_tokens(mut self, perm_manage_tokens: bool) -> Self {
+        self.perm_manage_tokens = Some(perm_manage_tokens);
+        self
+    }
+
+    /// Sets the maximum age of the token, e.g. `"365 00:00:00"`.
+    pub fn max_age(mut self, max_age: &str) -> Self {
+        self.max_age = Some(max_age.to_string());
+        self
+    }
+
+    /// Sets the period after which an unused token expires, e.g. `"30 00:00:00"`.
+    pub fn max_unused_period(mut self, max_unused_period: &str) -> Self {
+        self.max_unused_period = Some(max_unused_period.to_string());
+        self
+    }
+
+    /// Sets the maximum age of the token from a [`Duration`], see [`format_duration`].
+    pub fn max_age_duration(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(format_duration(max_age));
+        self
+    }
+
+    /// Sets the period after which an unused token expires from a [`Duration`], see [`format_duration`].
+    pub fn max_unused_period_duration(mut self, max_unused_period: Duration) -> Self {
+        self.max_unused_period = Some(format_duration(max_unused_period));
+        self
+    }
+
+    fn payload(self) -> Map<String, Value> {
+        construct_token_payload(
+            self.name,
+            self.allowed_subnets,
+            self.perm_manage_tokens,
+            self.max_age,
+            self.max_unused_period,
+        )
+    }
+}
+
 // Construct token payload for CREATE and PATCH
+#[cfg(feature = "std")]
 fn construct_token_payload(
     name: Option<String>,
     allowed_subnets: Option<Vec<String>>,
@@ -394,3 +1020,265 @@ fn construct_token_payload(
     }
     payload_map
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::tests::{mock_client, mock_client_with, page_response, response_with_headers};
+
+    fn policy(
+        id: &str,
+        domain: Option<&str>,
+        subname: Option<&str>,
+        rrset_type: Option<&str>,
+    ) -> TokenPolicy {
+        TokenPolicy {
+            id: id.to_string(),
+            domain: domain.map(str::to_string),
+            subname: subname.map(str::to_string),
+            r#type: rrset_type.map(str::to_string),
+            perm_write: false,
+        }
+    }
+
+    #[test]
+    fn token_builder_only_sends_set_fields() {
+        let payload = TokenBuilder::new()
+            .name("ddns")
+            .perm_manage_tokens(false)
+            .payload();
+        assert_eq!(
+            Value::Object(payload),
+            serde_json::json!({"name": "ddns", "perm_manage_tokens": false})
+        );
+    }
+
+    #[test]
+    fn durations_are_parsed_and_formatted() {
+        assert_eq!(
+            parse_duration("30 00:00:00").unwrap(),
+            Duration::from_secs(30 * 86400)
+        );
+        assert_eq!(
+            parse_duration("01:02:03.5").unwrap(),
+            Duration::from_millis(3_723_500)
+        );
+        assert!(parse_duration("1 day").is_err());
+        assert!(parse_duration("00:60:00").is_err());
+        assert_eq!(
+            format_duration(Duration::from_secs(30 * 86400)),
+            "30 00:00:00"
+        );
+        assert_eq!(
+            format_duration(Duration::from_micros(3_723_000_001)),
+            "01:02:03.000001"
+        );
+        let duration = Duration::from_secs(400 * 86400 + 59);
+        assert_eq!(
+            parse_duration(&format_duration(duration)).unwrap(),
+            duration
+        );
+    }
+
+    #[test]
+    fn policy_builder_payload_and_default() {
+        assert!(TokenPolicyBuilder::new().is_default());
+        assert!(policy("p", None, None, None).is_default());
+        assert!(!policy("p", Some("example.com"), None, None).is_default());
+        let builder = TokenPolicyBuilder::new()
+            .subname("_acme-challenge")
+            .perm_write(true);
+        assert!(!builder.is_default());
+        assert_eq!(
+            Value::Object(builder.payload()),
+            serde_json::json!({"domain": null, "subname": "_acme-challenge", "type": null, "perm_write": true})
+        );
+    }
+
+    #[test]
+    fn debug_redacts_token_secret() {
+        let token = Token {
+            created: "2024-01-01T00:00:00Z".to_string(),
+            id: "3a6b94b5-d20e-40bd-a7cc-521f5c79fab3".to_string(),
+            last_used: None,
+            name: "ci".to_string(),
+            perm_manage_tokens: false,
+            allowed_subnets: vec![],
+            max_age: None,
+            max_unused_period: None,
+            token: Some("4pnk7u-NHvrEkFzrhFDRTjGFyX_S".to_string()),
+        };
+        let debug = format!("{token:?}");
+        assert!(debug.contains(r#"token: Some("<redacted>")"#));
+        assert!(!debug.contains("4pnk7u"));
+    }
+
+    #[tokio::test]
+    async fn tokens_are_not_created_without_permission() {
+        let mut builder = Client::builder();
+        builder.perm_manage_tokens = Some(false);
+        let (client, server) = mock_client_with(builder, vec![]).await;
+        match client
+            .token()
+            .create_token(TokenBuilder::new().name("ci"))
+            .await
+        {
+            Err(Error::MissingPermission(message)) => {
+                assert!(message.contains("perm_manage_tokens"))
+            }
+            _ => panic!("Should yield desec_api::Error::MissingPermission"),
+        }
+        assert!(server.await.unwrap().is_empty());
+        let token: Token = serde_json::from_str(
+            r#"{"created": "", "id": "t1", "last_used": null, "name": "ci", "perm_manage_tokens": true, "allowed_subnets": []}"#,
+        )
+        .unwrap();
+        assert!(token.can_manage_tokens());
+    }
+
+    #[test]
+    fn most_specific_policy_wins() {
+        let policies = vec![
+            policy("default", None, None, None),
+            policy("type", None, None, Some("TXT")),
+            policy("subname", None, Some("_acme-challenge"), None),
+            policy("domain", Some("example.com"), None, None),
+            policy("domain-type", Some("example.com"), None, Some("A")),
+            policy("domain-subname", Some("example.com"), Some("www"), None),
+            policy("exact", Some("example.com"), Some("www"), Some("AAAA")),
+        ];
+        let matching = |domain, subname, rrset_type| {
+            matching_policy(&policies, domain, subname, rrset_type).map(|p| p.id.as_str())
+        };
+        assert_eq!(matching("example.com", "www", "AAAA"), Some("exact"));
+        // subname takes precedence over type
+        assert_eq!(matching("example.com", "www", "A"), Some("domain-subname"));
+        assert_eq!(matching("example.com", "mail", "a"), Some("domain-type"));
+        // domain takes precedence over subname and type
+        assert_eq!(
+            matching("Example.com.", "_acme-challenge", "TXT"),
+            Some("domain")
+        );
+        assert_eq!(
+            matching("example.org", "_acme-challenge", "TXT"),
+            Some("subname")
+        );
+        assert_eq!(matching("example.org", "", "TXT"), Some("type"));
+        assert_eq!(matching("example.org", "", "MX"), Some("default"));
+    }
+
+    #[test]
+    fn no_matching_policy_without_default() {
+        let policies = vec![policy("domain", Some("example.com"), None, None)];
+        assert!(matching_policy(&policies, "example.org", "", "A").is_none());
+        assert!(matching_policy(&[], "example.com", "", "A").is_none());
+    }
+
+    #[tokio::test]
+    async fn token_list_retries_throttled_pages() {
+        let token = |id: &str| {
+            format!(
+                r#"{{"created": "2018-09-18T16:36:16.510368Z", "id": "{id}", "last_used": null, "name": "", "perm_manage_tokens": false, "allowed_subnets": [], "max_age": null, "max_unused_period": null}}"#
+            )
+        };
+        let (client, server) = mock_client(vec![
+            page_response(&format!("[{}]", token("t1")), Some("c1")),
+            response_with_headers(429, &[("Retry-After", "0")], ""),
+            page_response(&format!("[{}]", token("t2")), None),
+        ])
+        .await;
+        let tokens = client.token().list().await.unwrap();
+        let requests = server.await.unwrap();
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.id.as_str())
+                .collect::<Vec<_>>(),
+            ["t1", "t2"]
+        );
+        assert!(requests[0].starts_with("GET /api/v1/auth/tokens/ "));
+        assert!(requests[1].starts_with("GET /api/v1/auth/tokens/?cursor=c1 "));
+        assert!(requests[2].starts_with("GET /api/v1/auth/tokens/?cursor=c1 "));
+    }
+
+    #[tokio::test]
+    async fn policy_methods_explain_missing_permission() {
+        let (client, server) = mock_client(vec![response_with_headers(
+            403,
+            &[],
+            r#"{"detail": "You do not have permission to perform this action."}"#,
+        )])
+        .await;
+        match client.token().get_policy("t1", "p1").await {
+            Err(Error::MissingPermission(message)) => {
+                assert_eq!(message, POLICY_PERMISSION_MESSAGE)
+            }
+            _ => panic!("Should yield desec_api::Error::MissingPermission"),
+        }
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /api/v1/auth/tokens/t1/policies/rrsets/p1/ "));
+
+        let mut builder = Client::builder();
+        builder.perm_manage_tokens = Some(false);
+        let (client, server) = mock_client_with(builder, vec![]).await;
+        assert!(matches!(
+            client.token().delete_policy("t1", "p1").await,
+            Err(Error::MissingPermission(_))
+        ));
+        assert!(server.await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn token_stream_yields_pages_and_ends_after_error() {
+        use futures_util::StreamExt;
+
+        let token = |id: &str| {
+            format!(
+                r#"{{"created": "2018-09-18T16:36:16.510368Z", "id": "{id}", "last_used": null, "name": "", "perm_manage_tokens": false, "allowed_subnets": []}}"#
+            )
+        };
+        let (client, server) = mock_client(vec![
+            page_response(&format!("[{}, {}]", token("t1"), token("t2")), Some("c1")),
+            response_with_headers(404, &[], ""),
+        ])
+        .await;
+        let token_client = client.token();
+        let mut tokens = Box::pin(token_client.tokens_stream());
+        assert_eq!(tokens.next().await.unwrap().unwrap().id, "t1");
+        assert_eq!(tokens.next().await.unwrap().unwrap().id, "t2");
+        assert!(matches!(
+            tokens.next().await,
+            Some(Err(Error::NotFound { .. }))
+        ));
+        assert!(tokens.next().await.is_none());
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].starts_with("GET /api/v1/auth/tokens/?cursor=c1 "));
+    }
+
+    #[tokio::test]
+    async fn policy_list_keeps_order_and_ends_on_empty_last_page() {
+        let policy = |id: &str| {
+            format!(
+                r#"{{"id": "{id}", "domain": "example.com", "subname": "{id}", "type": null, "perm_write": true}}"#
+            )
+        };
+        let (client, server) = mock_client(vec![
+            page_response(&format!("[{}, {}]", policy("p1"), policy("p2")), Some("c1")),
+            page_response(&format!("[{}]", policy("p3")), Some("c2")),
+            page_response("[]", None),
+        ])
+        .await;
+        let policies = client.token().list_policies("t1").await.unwrap();
+        let requests = server.await.unwrap();
+        assert_eq!(
+            policies
+                .iter()
+                .map(|policy| policy.id.as_str())
+                .collect::<Vec<_>>(),
+            ["p1", "p2", "p3"]
+        );
+        assert_eq!(requests.len(), 3);
+        assert!(requests[2].starts_with("GET /api/v1/auth/tokens/t1/policies/rrsets/?cursor=c2 "));
+    }
+}
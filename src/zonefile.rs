@@ -0,0 +1,293 @@
+//! Parser for zonefiles in RFC 1035 presentation format.
+//!
+//! Supported are `$ORIGIN` and `$TTL` directives, comments, multi-line records in parentheses,
+//! relative and absolute owner names, blank owners (repeating the previous owner) and optional
+//! TTL and class fields in any order. `$INCLUDE` and `$GENERATE` are not supported.
+
+use crate::rrset::{fully_qualify, qualify_target, RecordType, ResourceRecordSet};
+use crate::Error;
+
+/// TTL used for records without explicit TTL if neither `$TTL` nor a previous TTL is given.
+const DEFAULT_TTL: u64 = 3600;
+
+const CLASSES: [&str; 4] = ["IN", "CH", "HS", "CS"];
+
+/// State carried from one record to the next while parsing.
+pub(crate) struct Parser {
+    /// Fully qualified name of the zone
+    zone: String,
+    /// Current origin for relative names, changed by `$ORIGIN`
+    origin: String,
+    /// Default TTL set by `$TTL`
+    default_ttl: Option<u64>,
+    /// Owner and TTL of the previous record
+    last_owner: Option<String>,
+    last_ttl: Option<u64>,
+    /// Parsed RRsets in order of their first appearance
+    rrsets: Vec<ResourceRecordSet>,
+    /// Incomplete entry spanning several lines in parentheses
+    pending: String,
+    pending_line: usize,
+}
+
+impl Parser {
+    /// Creates a parser for a zonefile of the given zone.
+    pub(crate) fn new(zone: &str) -> Self {
+        let zone = fully_qualify(zone.trim_end_matches('.'), ".");
+        Parser {
+            origin: zone.clone(),
+            zone,
+            default_ttl: None,
+            last_owner: None,
+            last_ttl: None,
+            rrsets: Vec::new(),
+            pending: String::new(),
+            pending_line: 0,
+        }
+    }
+
+    /// Feeds the next line of the zonefile to the parser.
+    pub(crate) fn parse_line(&mut self, line_number: usize, line: &str) -> Result<(), Error> {
+        let line = strip_comment(line);
+        if self.pending.is_empty() {
+            self.pending_line = line_number;
+        } else {
+            self.pending.push(' ');
+        }
+        self.pending.push_str(line);
+        if paren_depth(&self.pending) > 0 {
+            return Ok(());
+        }
+        let entry = std::mem::take(&mut self.pending);
+        self.parse_entry(&entry).map_err(|message| {
+            Error::InvalidInput(format!("line {}: {message}", self.pending_line))
+        })
+    }
+
+    /// Returns the number of RRsets parsed so far and not yet taken.
+    pub(crate) fn rrset_count(&self) -> usize {
+        self.rrsets.len()
+    }
+
+    /// Takes the RRsets parsed so far, except the ones of the current owner which may still
+    /// receive further records from the following lines.
+    pub(crate) fn take_completed(&mut self) -> Vec<ResourceRecordSet> {
+        let last_owner = self.last_owner.as_deref().unwrap_or_default();
+        let (current, completed) = std::mem::take(&mut self.rrsets)
+            .into_iter()
+            .partition(|rrset| rrset.name.eq_ignore_ascii_case(last_owner));
+        self.rrsets = current;
+        completed
+    }
+
+    /// Finishes parsing and returns the RRsets.
+    pub(crate) fn finish(self) -> Result<Vec<ResourceRecordSet>, Error> {
+        if !self.pending.trim().is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "line {}: unbalanced parentheses",
+                self.pending_line
+            )));
+        }
+        Ok(self.rrsets)
+    }
+
+    fn parse_entry(&mut self, entry: &str) -> Result<(), String> {
+        if entry.trim().is_empty() {
+            return Ok(());
+        }
+        let starts_with_blank = entry.starts_with(|c: char| c.is_whitespace());
+        let mut tokens = tokenize(entry).into_iter();
+        let first = match tokens.next() {
+            Some(first) => first,
+            None => return Ok(()),
+        };
+        match first.to_ascii_uppercase().as_str() {
+            "$ORIGIN" => {
+                let origin = tokens.next().ok_or("$ORIGIN without name")?;
+                self.origin = fully_qualify(&origin, &self.origin);
+                return Ok(());
+            }
+            "$TTL" => {
+                let ttl = tokens.next().ok_or("$TTL without value")?;
+                self.default_ttl = Some(parse_ttl(&ttl)?);
+                return Ok(());
+            }
+            directive if directive.starts_with('$') => {
+                return Err(format!("unsupported directive {first}"));
+            }
+            _ => (),
+        }
+        let mut tokens: Vec<String> = if starts_with_blank {
+            std::iter::once(first).chain(tokens).collect()
+        } else {
+            self.last_owner = Some(fully_qualify(&first, &self.origin));
+            tokens.collect()
+        };
+        let owner = self.last_owner.clone().ok_or("record without owner name")?;
+
+        // TTL and class are optional and may appear in any order
+        let mut ttl = None;
+        while let Some(token) = tokens.first() {
+            if ttl.is_none() && token.starts_with(|c: char| c.is_ascii_digit()) {
+                ttl = Some(parse_ttl(token)?);
+            } else if CLASSES.contains(&token.to_ascii_uppercase().as_str()) {
+                if !token.eq_ignore_ascii_case("IN") {
+                    return Err(format!("unsupported class {token}"));
+                }
+            } else {
+                break;
+            }
+            tokens.remove(0);
+        }
+        if tokens.is_empty() {
+            return Err("record without type".to_string());
+        }
+        let rrset_type = tokens.remove(0).to_ascii_uppercase();
+        if tokens.is_empty() {
+            return Err(format!("{rrset_type} record without data"));
+        }
+        let ttl = ttl
+            .or(self.default_ttl)
+            .or(self.last_ttl)
+            .unwrap_or(DEFAULT_TTL);
+        self.last_ttl = Some(ttl);
+        let record = qualify_target(&rrset_type, &tokens.join(" "), &self.origin);
+        self.add_record(&owner, RecordType::from(rrset_type), ttl, record)
+    }
+
+    fn add_record(
+        &mut self,
+        owner: &str,
+        rrset_type: RecordType,
+        ttl: u64,
+        record: String,
+    ) -> Result<(), String> {
+        let subname = if owner.eq_ignore_ascii_case(&self.zone) {
+            String::new()
+        } else {
+            match owner
+                .len()
+                .checked_sub(self.zone.len() + 1)
+                .filter(|&split| {
+                    owner[split + 1..].eq_ignore_ascii_case(&self.zone)
+                        && owner.as_bytes()[split] == b'.'
+                }) {
+                Some(split) => owner[..split].to_string(),
+                None => return Err(format!("{owner} is not within the zone {}", self.zone)),
+            }
+        };
+        match self.rrsets.iter_mut().find(|rrset| {
+            rrset.subname.as_deref() == Some(subname.as_str()) && rrset.rrset_type == rrset_type
+        }) {
+            Some(rrset) => {
+                if !rrset.records.contains(&record) {
+                    rrset.records.push(record);
+                }
+            }
+            None => self.rrsets.push(ResourceRecordSet {
+                domain: self.zone.trim_end_matches('.').to_string(),
+                subname: Some(subname),
+                name: owner.to_string(),
+                rrset_type,
+                ttl,
+                records: vec![record],
+                ..Default::default()
+            }),
+        }
+        Ok(())
+    }
+}
+
+/// Parses a complete zonefile of the given zone into RRsets.
+pub(crate) fn parse(zone: &str, zonefile: &str) -> Result<Vec<ResourceRecordSet>, Error> {
+    let mut parser = Parser::new(zone);
+    for (index, line) in zonefile.lines().enumerate() {
+        parser.parse_line(index + 1, line)?;
+    }
+    parser.finish()
+}
+
+/// Determines the zone of a zonefile from its first `$ORIGIN` directive or the owner of its SOA record.
+pub(crate) fn detect_zone(zonefile: &str) -> Option<String> {
+    zonefile.lines().find_map(|line| {
+        let tokens = tokenize(strip_comment(line));
+        match tokens.first()?.to_ascii_uppercase().as_str() {
+            "$ORIGIN" => tokens.get(1).cloned(),
+            _ if tokens.first()?.ends_with('.')
+                && tokens
+                    .iter()
+                    .skip(1)
+                    .any(|token| token.eq_ignore_ascii_case("SOA")) =>
+            {
+                tokens.first().cloned()
+            }
+            _ => None,
+        }
+    })
+}
+
+fn parse_ttl(ttl: &str) -> Result<u64, String> {
+    ttl.parse().map_err(|_| format!("invalid TTL {ttl}"))
+}
+
+// Removes a comment, semicolons within quoted strings are kept.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => quoted = !quoted,
+            ';' if !quoted => return &line[..index],
+            _ => (),
+        }
+    }
+    line
+}
+
+// Returns the number of unclosed parentheses outside of quoted strings.
+fn paren_depth(entry: &str) -> i32 {
+    let mut depth = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in entry.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth -= 1,
+            _ => (),
+        }
+    }
+    depth
+}
+
+// Splits an entry into whitespace separated tokens, quoted strings are kept including their quotes.
+// Parentheses outside of quoted strings only group lines and are dropped.
+fn tokenize(entry: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in entry.chars() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            quoted = !quoted;
+        } else if (c.is_whitespace() || c == '(' || c == ')') && !quoted {
+            if !token.is_empty() {
+                tokens.push(std::mem::take(&mut token));
+            }
+            continue;
+        }
+        token.push(c);
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    tokens
+}
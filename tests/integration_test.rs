@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use desec_api::account::AccountInformation;
 use desec_api::Client;
 use std::env::var;
@@ -68,6 +70,10 @@ async fn zonefile() {
         zonefile.contains("exported from desec.io"),
         "Zonefile does not contain expected string"
     );
+    let rrsets = desec_api::rrset::parse_zonefile(&zonefile).expect("Zonefile should be parseable");
+    assert!(rrsets
+        .iter()
+        .any(|rrset| rrset.domain == config.domain && rrset.rrset_type == "SOA"));
 }
 
 #[allow(clippy::needless_return)]
@@ -91,7 +97,7 @@ async fn missing_resssources() {
         .get_rrset(&config.domain, Some("non-existing-subname"), "A")
         .await;
     match rrset {
-        Err(desec_api::Error::NotFound) => (),
+        Err(desec_api::Error::NotFound { .. }) => (),
         _ => panic!("Should yield desec_api::Error::NotFound"),
     }
 
@@ -102,7 +108,7 @@ async fn missing_resssources() {
         .get_domain("non-existing-domain")
         .await;
     match rrset {
-        Err(desec_api::Error::NotFound) => (),
+        Err(desec_api::Error::NotFound { .. }) => (),
         _ => panic!("Should yield desec_api::Error::NotFound"),
     };
 }